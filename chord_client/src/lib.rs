@@ -0,0 +1,239 @@
+use chord_proto::addr::node_url;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::{
+    AppendRequest, DeleteNamespaceRequest, DeleteRangeRequest, GetRequest, GetResponse, PutRequest,
+    PutResponse,
+};
+use chord_proto::{hash_addr, namespaced_key};
+use log::warn;
+use std::future::Future;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Code, Request, Response, Status};
+
+// Backoff before the first retry, doubled on every further attempt up to
+// the cap
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(10);
+// Retries a new client makes while the node is unavailable
+const DEFAULT_RETRIES: u32 = 3;
+
+/// A handle to the DHT through one node. Any node can serve any key, so a
+/// single entry point is enough. Clones share the connection.
+#[derive(Debug, Clone)]
+pub struct DhtClient {
+    client: ChordClient<Channel>,
+    retries: u32,
+    namespace: String,
+}
+
+impl DhtClient {
+    /// A client for the node at `addr` (`host:port` or a full URL). The
+    /// connection is made on first use, so a node that is briefly down
+    /// surfaces as an unavailable status that retries can wait out.
+    pub fn new(addr: &str) -> Result<Self, tonic::transport::Error> {
+        let channel = Endpoint::from_shared(endpoint(addr))?.connect_lazy();
+        Ok(Self {
+            client: ChordClient::new(channel),
+            retries: DEFAULT_RETRIES,
+            namespace: String::new(),
+        })
+    }
+
+    /// How many times calls are retried while the node is unavailable or
+    /// the call times out.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Scopes keys to `namespace`, so the same key in another namespace is a
+    /// different entry. Requests that name their own namespace keep it.
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = namespace.into();
+        self
+    }
+
+    /// The generated client over the same connection, for RPCs this wrapper
+    /// doesn't cover.
+    pub fn raw(&self) -> ChordClient<Channel> {
+        self.client.clone()
+    }
+
+    /// Runs an RPC, retrying with exponential backoff while the node is
+    /// unavailable or the call times out. The channel reconnects on its own,
+    /// so each attempt redials if the connection was lost.
+    pub async fn retry<T, F, Fut>(&self, call: F) -> Result<T, Status>
+    where
+        F: Fn(ChordClient<Channel>) -> Fut,
+        Fut: Future<Output = Result<Response<T>, Status>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match call(self.client.clone()).await {
+                Err(status) if is_unreachable(&status) && attempt < self.retries => {
+                    let delay = retry_delay(attempt);
+                    attempt += 1;
+                    warn!(
+                        "Request failed ({}), retrying in {}ms ({}/{})",
+                        status.message(),
+                        delay.as_millis(),
+                        attempt,
+                        self.retries
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result.map(Response::into_inner),
+            }
+        }
+    }
+
+    /// Stores `value` under `key`.
+    pub async fn put(&self, key: &str, value: impl Into<Vec<u8>>) -> Result<(), Status> {
+        self.put_request(PutRequest {
+            key: key.to_string(),
+            value: value.into(),
+            ..Default::default()
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Sends a full put request. Retries are deduplicated by the owner, using
+    /// the request's id or a fresh one if it has none.
+    pub async fn put_request(&self, mut request: PutRequest) -> Result<PutResponse, Status> {
+        if request.request_id.is_empty() {
+            request.request_id = new_request_id();
+        }
+        if request.namespace.is_empty() {
+            request.namespace = self.namespace.clone();
+        }
+        self.retry(|mut client| {
+            let request = request.clone();
+            async move { client.put(Request::new(request)).await }
+        })
+        .await
+    }
+
+    /// The value under `key`, or None if the ring was reached and has none.
+    /// Failing to reach the ring is an error, see `is_unreachable`.
+    pub async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, Status> {
+        let response = self
+            .get_request(GetRequest {
+                key: key.to_string(),
+                ..Default::default()
+            })
+            .await?;
+        Ok(response.found.then_some(response.value))
+    }
+
+    /// Sends a full get request.
+    pub async fn get_request(&self, mut request: GetRequest) -> Result<GetResponse, Status> {
+        if request.namespace.is_empty() {
+            request.namespace = self.namespace.clone();
+        }
+        self.retry(|mut client| {
+            let request = request.clone();
+            async move { client.get(Request::new(request)).await }
+        })
+        .await
+    }
+
+    /// Whether `key` holds a value.
+    pub async fn exists(&self, key: &str) -> Result<bool, Status> {
+        Ok(self.get(key).await?.is_some())
+    }
+
+    /// Removes `key` from its owner and replicas. Returns whether it existed.
+    pub async fn delete(&self, key: &str) -> Result<bool, Status> {
+        // The range holding exactly the key's id
+        let id =
+            hash_addr(&namespaced_key(&self.namespace, key).map_err(Status::invalid_argument)?);
+        let request = DeleteRangeRequest {
+            start_id: id.wrapping_sub(1),
+            end_id: id,
+        };
+        let response = self
+            .retry(|mut client| async move { client.delete_range(Request::new(request)).await })
+            .await?;
+        Ok(response.deleted > 0)
+    }
+
+    /// Removes every key in `namespace`. Returns how many there were.
+    pub async fn delete_namespace(&self, namespace: &str) -> Result<u64, Status> {
+        let request = DeleteNamespaceRequest {
+            namespace: namespace.to_string(),
+        };
+        let response = self
+            .retry(|mut client| {
+                let request = request.clone();
+                async move { client.delete_namespace(Request::new(request)).await }
+            })
+            .await?;
+        Ok(response.deleted)
+    }
+
+    /// Adds `value` to the end of the list under `key`.
+    pub async fn append(&self, key: &str, value: impl Into<Vec<u8>>) -> Result<(), Status> {
+        let request = AppendRequest {
+            key: key.to_string(),
+            value: value.into(),
+            request_id: new_request_id(),
+            namespace: self.namespace.clone(),
+            ..Default::default()
+        };
+        self.retry(|mut client| {
+            let request = request.clone();
+            async move { client.append(Request::new(request)).await }
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Every value appended to `key`, oldest first, or None if there is no
+    /// list under it.
+    pub async fn get_list(&self, key: &str) -> Result<Option<Vec<Vec<u8>>>, Status> {
+        let request = GetRequest {
+            key: key.to_string(),
+            namespace: self.namespace.clone(),
+            ..Default::default()
+        };
+        let list = self
+            .retry(|mut client| {
+                let request = request.clone();
+                async move { client.get_list(Request::new(request)).await }
+            })
+            .await?;
+        Ok(list.found.then_some(list.items))
+    }
+}
+
+/// How long to wait before retrying after `attempt` earlier retries:
+/// `RETRY_BASE_DELAY` doubled for each, but never more than
+/// `RETRY_MAX_DELAY`, however many retries a client is allowed.
+pub fn retry_delay(attempt: u32) -> Duration {
+    RETRY_BASE_DELAY
+        .checked_mul(2u32.saturating_pow(attempt))
+        .map_or(RETRY_MAX_DELAY, |delay| delay.min(RETRY_MAX_DELAY))
+}
+
+/// Whether a call failed because the node or the key's owner couldn't be
+/// reached, as opposed to the ring answering with an error.
+pub fn is_unreachable(status: &Status) -> bool {
+    matches!(status.code(), Code::Unavailable | Code::DeadlineExceeded)
+}
+
+// Tags a write so that a retry of an attempt the owner already applied is
+// deduplicated instead of writing the value twice
+fn new_request_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    format!("client-{}-{}", std::process::id(), nanos)
+}
+
+/// `addr` as a normalized URL, adding `http://` when no scheme is given.
+pub fn endpoint(addr: &str) -> String {
+    node_url(addr)
+}
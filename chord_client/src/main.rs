@@ -1,8 +1,24 @@
-use chord_proto::chord::chord_client::ChordClient;
-use chord_proto::chord::{GetRequest, PutRequest};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chord_client::{endpoint, is_unreachable, DhtClient};
+use chord_proto::chord::{
+    ClusterHealthRequest, Empty, GetRequest, KeyValue, NodeInfo, NodeState, PutRequest,
+};
+use chord_proto::chunk::{key_value_chunks, KeyValueAssembler};
+use chord_proto::latency;
 use clap::{Parser, Subcommand};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, BufReader};
 use tonic::Request;
 
+// Import reads at most this many value chunks ahead of what the node has taken
+const IMPORT_QUEUE_LEN: usize = 256;
+// Import and export report how far they got every so many entries
+const BULK_PROGRESS_EVERY: u64 = 10_000;
+
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
@@ -10,6 +26,18 @@ struct Cli {
     #[arg(short, long, default_value = "http://127.0.0.1:5000")]
     node: String,
 
+    /// Read and print values as base64 instead of UTF-8 text
+    #[arg(long, global = true)]
+    base64: bool,
+
+    /// How many times to retry put/get/find-successor while the node is unavailable
+    #[arg(long, global = true, default_value_t = 3)]
+    retries: u32,
+
+    /// Namespace to put, get and delete keys in (default: the shared one)
+    #[arg(long, global = true, default_value = "")]
+    namespace: String,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -17,45 +45,694 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Put a key-value pair into the DHT
-    Put { key: String, value: String },
+    Put {
+        key: String,
+        value: String,
+        /// Metadata to store with the value, as name=value (repeatable)
+        #[arg(long = "meta", value_parser = parse_metadata)]
+        metadata: Vec<(String, String)>,
+    },
     /// Get a value from the DHT
-    Get { key: String },
+    Get {
+        key: String,
+        /// Accept the connected node's own copy, even if it's a replica that
+        /// may be behind the primary
+        #[arg(long)]
+        allow_stale: bool,
+    },
+    /// Append a value to the list stored under a key
+    Append { key: String, value: String },
+    /// Get every value appended to a key, oldest first
+    GetList { key: String },
     /// Find successor of an ID
-    FindSuccessor { id: u64 },
+    #[command(alias = "find")]
+    FindSuccessor {
+        id: u64,
+        /// Print every node the lookup passed through
+        #[arg(long)]
+        trace: bool,
+        /// Resolve through the fingers only, without using or warming any
+        /// node's caches
+        #[arg(long, conflicts_with = "trace")]
+        dry_run: bool,
+    },
+    /// Find the node preceding an ID
+    FindPredecessor { id: u64 },
+    /// Show which nodes hold a key: its primary and the replicas that have it
+    Replicas { key: String },
+    /// List all keys starting with a prefix (asks every node)
+    Scan { prefix: String },
+    /// Delete all keys whose id falls in (start, end]
+    DeleteRange { start: u64, end: u64 },
+    /// Delete every key in a namespace
+    DeleteNamespace { namespace: String },
+    /// Bulk-load keys from a JSON Lines file with one KeyValue per line
+    Import { file: PathBuf },
+    /// Write every key in the ring to a JSON Lines file that import can load
+    Export {
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Show whether the node has joined and is ready for traffic
+    Health,
+    /// Show the health and store size of every node, walking the ring from
+    /// the connected node
+    ClusterHealth {
+        /// Stop after this many nodes (0: the node's default)
+        #[arg(long, default_value_t = 0)]
+        max_nodes: u32,
+    },
+    /// Show the node's counters
+    Stats,
+    /// Show how many keys each node is primary for and how skewed that is
+    Distribution,
+    /// Print a node's routing state (defaults to the connected node)
+    Dump {
+        /// Address of the node to inspect, e.g. 127.0.0.1:5001
+        addr: Option<String>,
+        /// Print as JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// Start an interactive session over a single connection
+    Repl,
+    /// Drive mixed put/get load against the node and report latencies
+    Bench {
+        /// Total number of operations across all workers
+        #[arg(long, default_value_t = 1000)]
+        ops: usize,
+        /// Number of concurrent workers, each with its own connection
+        #[arg(long, default_value_t = 10)]
+        concurrency: usize,
+        /// Fraction of operations that are gets (0.0 - 1.0)
+        #[arg(long, default_value_t = 0.5)]
+        read_ratio: f64,
+    },
 }
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
+// A single line typed into the REPL, parsed with the same commands as the CLI
+#[derive(Parser)]
+#[command(no_binary_name = true, disable_help_flag = true)]
+struct ReplLine {
+    #[command(subcommand)]
+    command: Commands,
+}
 
-    let mut client = ChordClient::connect(cli.node).await?;
+fn parse_metadata(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected name=value, got '{}'", s))
+}
 
-    match cli.command {
-        Commands::Put { key, value } => {
-            let request = Request::new(PutRequest { key, value });
-            let response = client.put(request).await?;
-            if response.into_inner().success {
+async fn run_command(
+    client: &DhtClient,
+    command: Commands,
+    base64: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut raw = client.raw();
+    match command {
+        Commands::Put {
+            key,
+            value,
+            metadata,
+        } => {
+            let response = client
+                .put_request(PutRequest {
+                    key,
+                    value: encode_value(value, base64)?,
+                    metadata: metadata.into_iter().collect(),
+                    ..Default::default()
+                })
+                .await?;
+            if response.success {
                 println!("Put successful");
             } else {
                 println!("Put failed");
             }
         }
-        Commands::Get { key } => {
-            let request = Request::new(GetRequest { key });
-            let response = client.get(request).await?;
-            let resp = response.into_inner();
+        Commands::Get { key, allow_stale } => {
+            let request = GetRequest {
+                key,
+                allow_stale,
+                ..Default::default()
+            };
+            let resp = match client.get_request(request).await {
+                Ok(resp) => resp,
+                Err(status) if is_unreachable(&status) => {
+                    return Err(format!("Could not reach the ring: {}", status.message()).into())
+                }
+                Err(status) => return Err(format!("Get failed: {}", status.message()).into()),
+            };
             if resp.found {
-                println!("Value: {}", resp.value);
+                println!("Value: {}", decode_value(&resp.value, base64));
+                if resp.is_replica {
+                    println!("  (from a replica, may be stale)");
+                }
+                let mut metadata: Vec<_> = resp.metadata.into_iter().collect();
+                metadata.sort();
+                for (name, value) in metadata {
+                    println!("  {}: {}", name, value);
+                }
             } else {
                 println!("Key not found");
             }
         }
-        Commands::FindSuccessor { id } => {
-            let request = Request::new(chord_proto::chord::FindSuccessorRequest { id });
-            let response = client.find_successor(request).await?;
-            let node = response.into_inner();
+        Commands::Append { key, value } => {
+            client.append(&key, encode_value(value, base64)?).await?;
+            println!("Append successful");
+        }
+        Commands::GetList { key } => match client.get_list(&key).await? {
+            Some(items) => {
+                for item in &items {
+                    println!("{}", decode_value(item, base64));
+                }
+            }
+            None => println!("Key not found"),
+        },
+        Commands::FindSuccessor {
+            id, trace: true, ..
+        } => {
+            let request = Request::new(chord_proto::chord::TracedLookupRequest {
+                id,
+                path: Vec::new(),
+            });
+            let response = raw.find_successor_traced(request).await?.into_inner();
+            for (hop, node) in response.path.iter().enumerate() {
+                println!("{:>3}. ID={}, Address={}", hop, node.id, node.address);
+            }
+            match response.successor {
+                Some(node) => println!("Successor: ID={}, Address={}", node.id, node.address),
+                None => println!("Routing loop: the lookup came back to a node on the path"),
+            }
+        }
+        Commands::FindSuccessor {
+            id,
+            trace: false,
+            dry_run,
+        } => {
+            let node = client
+                .retry(|mut client| async move {
+                    client
+                        .find_successor(Request::new(chord_proto::chord::FindSuccessorRequest {
+                            id,
+                            dry_run,
+                            ..Default::default()
+                        }))
+                        .await
+                })
+                .await?;
             println!("Successor: ID={}, Address={}", node.id, node.address);
         }
+        Commands::FindPredecessor { id } => {
+            let request = Request::new(chord_proto::chord::FindPredecessorRequest { id });
+            let response = raw.find_predecessor(request).await?;
+            let node = response.into_inner();
+            println!("Predecessor: ID={}, Address={}", node.id, node.address);
+        }
+        Commands::Replicas { key } => {
+            let request = Request::new(GetRequest {
+                key,
+                ..Default::default()
+            });
+            let locations = raw.get_replicas(request).await?.into_inner();
+            if let Some(primary) = &locations.primary {
+                let held = if locations.primary_found {
+                    ""
+                } else {
+                    " (key missing)"
+                };
+                println!("Primary: {}{}", format_node(primary), held);
+            }
+            println!("Replicas:");
+            for replica in &locations.replicas {
+                println!("  {}", format_node(replica));
+            }
+        }
+        Commands::Scan { prefix } => {
+            let request = Request::new(chord_proto::chord::ScanPrefixRequest { prefix });
+            let keys = raw.scan_prefix(request).await?.into_inner().keys;
+            for key in &keys {
+                println!("{}", key);
+            }
+            println!("({} keys)", keys.len());
+        }
+        Commands::DeleteRange { start, end } => {
+            let request = Request::new(chord_proto::chord::DeleteRangeRequest {
+                start_id: start,
+                end_id: end,
+            });
+            let response = raw.delete_range(request).await?;
+            println!("Deleted {} keys", response.into_inner().deleted);
+        }
+        Commands::DeleteNamespace { namespace } => {
+            let deleted = client.delete_namespace(&namespace).await?;
+            println!("Deleted {} keys", deleted);
+        }
+        Commands::Import { file } => {
+            let file = tokio::fs::File::open(&file).await?;
+            let (tx, rx) = tokio::sync::mpsc::channel(IMPORT_QUEUE_LEN);
+            // Parse while the node is still working on earlier entries
+            let reader = tokio::spawn(async move {
+                let mut lines = BufReader::new(file).lines();
+                let mut sent = 0u64;
+                let mut line_no = 0;
+                'lines: while let Some(line) = lines.next_line().await.map_err(|e| e.to_string())? {
+                    line_no += 1;
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let entry: KeyValue = serde_json::from_str(&line)
+                        .map_err(|e| format!("line {}: {}", line_no, e))?;
+                    // Values over the message limit go in several chunks
+                    for chunk in key_value_chunks(entry) {
+                        if tx.send(chunk).await.is_err() {
+                            break 'lines;
+                        }
+                    }
+                    sent += 1;
+                    if sent.is_multiple_of(BULK_PROGRESS_EVERY) {
+                        eprintln!("Sent {} entries", sent);
+                    }
+                }
+                Ok::<u64, String>(sent)
+            });
+            let stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+            let response = raw.import_chunks(Request::new(stream)).await?.into_inner();
+            println!(
+                "Imported {} locally, forwarded {}, failed {}",
+                response.imported, response.forwarded, response.failed
+            );
+            reader
+                .await?
+                .map_err(|e| format!("Import stopped early at {}", e))?;
+        }
+        Commands::Export { out } => {
+            let mut file = std::io::BufWriter::new(std::fs::File::create(&out)?);
+            let mut chunks = raw
+                .export_chunks(Request::new(Empty {}))
+                .await?
+                .into_inner();
+            // Values come in chunks, put back together one key at a time
+            let mut entries = KeyValueAssembler::new();
+            let mut written = 0u64;
+            let mut done = false;
+            while !done {
+                let entry = match chunks.message().await? {
+                    Some(chunk) => entries.push(chunk)?,
+                    None => {
+                        done = true;
+                        std::mem::take(&mut entries).finish()
+                    }
+                };
+                let Some(entry) = entry else { continue };
+                serde_json::to_writer(&mut file, &entry)?;
+                writeln!(file)?;
+                written += 1;
+                if written.is_multiple_of(BULK_PROGRESS_EVERY) {
+                    eprintln!("Wrote {} entries", written);
+                }
+            }
+            file.flush()?;
+            println!("Exported {} keys to {}", written, out.display());
+        }
+        Commands::Health => {
+            let response = raw.health(Request::new(Empty {})).await?;
+            println!("State: {:?}", response.into_inner().state());
+        }
+        Commands::ClusterHealth { max_nodes } => {
+            let response = raw
+                .get_cluster_health(Request::new(ClusterHealthRequest { max_nodes }))
+                .await?;
+            let report = response.into_inner();
+            println!("ID                    ADDRESS                STATE           KEYS");
+            for entry in &report.nodes {
+                let Some(node) = &entry.node else { continue };
+                let state = if entry.reachable {
+                    format!("{:?}", entry.state())
+                } else {
+                    "Unreachable".to_string()
+                };
+                println!(
+                    "{:<20}  {:<21}  {:<10}  {:>8}",
+                    node.id, node.address, state, entry.store_size
+                );
+            }
+            if !report.complete {
+                println!("(walk stopped before coming back around the ring)");
+            }
+        }
+        Commands::Stats => {
+            let response = raw.get_stats(Request::new(Empty {})).await?;
+            let stats = response.into_inner();
+            println!("Store size: {}", stats.store_size);
+            println!("Primary keys: {}", stats.primary_keys);
+            println!("Successor list length: {}", stats.successor_list_len);
+            println!("Has predecessor: {}", stats.has_predecessor);
+            println!("Distinct fingers: {}", stats.distinct_fingers);
+            println!("Uptime: {}ms", stats.uptime_ms);
+            println!("Stalest finger: {}ms", stats.stalest_finger_age_ms);
+            println!(
+                "Replication lag: p50 {}ms, p99 {}ms ({} acks)",
+                stats.replication_lag_p50_ms,
+                stats.replication_lag_p99_ms,
+                stats.replication_lag_samples
+            );
+            for op in &stats.op_latencies {
+                let count: u64 = op.buckets.iter().sum();
+                if count > 0 {
+                    println!(
+                        "{} latency: p50 <{}us, p99 <{}us ({} requests)",
+                        op.op,
+                        latency::percentile(&op.buckets, 0.50).as_micros(),
+                        latency::percentile(&op.buckets, 0.99).as_micros(),
+                        count
+                    );
+                }
+            }
+        }
+        Commands::Distribution => {
+            let response = raw.distribution(Request::new(Empty {})).await?;
+            let report = response.into_inner();
+            for load in &report.nodes {
+                if let Some(node) = &load.node {
+                    println!("{} ({}): {} keys", node.id, node.address, load.primary_keys);
+                }
+            }
+            println!("Gini coefficient: {:.3}", report.gini);
+            println!("Max to mean: {:.2}", report.max_to_mean);
+        }
+        Commands::Dump { addr, json } => {
+            let snapshot = match addr {
+                Some(addr) => {
+                    let mut other = DhtClient::new(&addr)?.raw();
+                    other.get_node_info(Request::new(Empty {})).await?
+                }
+                None => raw.get_node_info(Request::new(Empty {})).await?,
+            }
+            .into_inner();
+            if json {
+                println!("{}", serde_json::to_string_pretty(&snapshot)?);
+            } else {
+                print_snapshot(&snapshot);
+            }
+        }
+        Commands::Repl => println!("Already in a REPL session"),
+        Commands::Bench { .. } => println!("bench is only available as a top-level command"),
+    }
+
+    Ok(())
+}
+
+// Values are raw bytes on the wire; the CLI takes and shows them as UTF-8
+// text, or as base64 when --base64 is given
+fn encode_value(value: String, base64: bool) -> Result<Vec<u8>, base64::DecodeError> {
+    if base64 {
+        BASE64.decode(value)
+    } else {
+        Ok(value.into_bytes())
+    }
+}
+
+fn decode_value(value: &[u8], base64: bool) -> String {
+    if base64 {
+        BASE64.encode(value)
+    } else {
+        String::from_utf8_lossy(value).into_owned()
+    }
+}
+
+fn format_node(node: &NodeInfo) -> String {
+    format!("{} ({})", node.id, node.address)
+}
+
+fn print_snapshot(snapshot: &NodeState) {
+    println!("Node: {} ({})", snapshot.id, snapshot.address);
+    match &snapshot.predecessor {
+        Some(pred) => println!("Predecessor: {}", format_node(pred)),
+        None => println!("Predecessor: none"),
+    }
+
+    println!("Successors:");
+    for (i, succ) in snapshot.successors.iter().enumerate() {
+        println!("  {}: {}", i, format_node(succ));
+    }
+
+    // Consecutive fingers usually point at the same node, so print them as ranges
+    println!("Finger table:");
+    let mut start = 0;
+    for i in 1..=snapshot.finger_table.len() {
+        let run_ends = i == snapshot.finger_table.len()
+            || snapshot.finger_table[i].id != snapshot.finger_table[start].id;
+        if run_ends {
+            let finger = &snapshot.finger_table[start];
+            if start == i - 1 {
+                println!("  {}: {}", start, format_node(finger));
+            } else {
+                println!("  {}-{}: {}", start, i - 1, format_node(finger));
+            }
+            start = i;
+        }
+    }
+
+    println!(
+        "Stored keys: {} ({} primary, {} replica)",
+        snapshot.stored_keys.len(),
+        snapshot.primary_keys.len(),
+        snapshot.replica_keys.len()
+    );
+}
+
+#[derive(Default)]
+struct WorkerResult {
+    latencies: Vec<Duration>,
+    errors: usize,
+    misses: usize,
+}
+
+async fn run_worker(
+    node: String,
+    worker: usize,
+    ops: usize,
+    read_ratio: f64,
+) -> Result<WorkerResult, tonic::transport::Error> {
+    // Failures count as errors rather than being retried
+    let client = DhtClient::new(&node)?.with_retries(0);
+    let mut result = WorkerResult::default();
+    let mut written = 0;
+
+    for i in 0..ops {
+        // Spread reads evenly through the run instead of sampling randomly
+        let is_read = (i as f64 * read_ratio).floor() != ((i + 1) as f64 * read_ratio).floor();
+
+        let start = Instant::now();
+        let ok = if is_read && written > 0 {
+            let key = format!("bench_{}_{}", worker, i % written);
+            match client.get(&key).await {
+                Ok(value) => {
+                    if value.is_none() {
+                        result.misses += 1;
+                    }
+                    true
+                }
+                Err(_) => false,
+            }
+        } else {
+            let key = format!("bench_{}_{}", worker, written);
+            written += 1;
+            client
+                .put(&key, format!("value_{}", i).into_bytes())
+                .await
+                .is_ok()
+        };
+
+        if ok {
+            result.latencies.push(start.elapsed());
+        } else {
+            result.errors += 1;
+        }
+    }
+
+    Ok(result)
+}
+
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let idx = ((sorted.len() as f64 * p).ceil() as usize).clamp(1, sorted.len()) - 1;
+    sorted[idx]
+}
+
+/// The node's latency histogram for each operation, or None if its stats
+/// can't be read.
+async fn server_latencies(node: &str) -> Option<HashMap<String, Vec<u64>>> {
+    let mut raw = DhtClient::new(node).ok()?.raw();
+    let stats = raw.get_stats(Request::new(Empty {})).await.ok()?;
+    Some(
+        stats
+            .into_inner()
+            .op_latencies
+            .into_iter()
+            .map(|op| (op.op, op.buckets))
+            .collect(),
+    )
+}
+
+async fn run_bench(
+    node: String,
+    ops: usize,
+    concurrency: usize,
+    read_ratio: f64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let concurrency = concurrency.max(1);
+    let read_ratio = read_ratio.clamp(0.0, 1.0);
+    println!(
+        "Running {} ops with {} workers ({:.0}% reads) against {}",
+        ops,
+        concurrency,
+        read_ratio * 100.0,
+        node
+    );
+
+    // The histograms count since the node started, so only the difference
+    // covers this run
+    let server_before = server_latencies(&node).await;
+    let start = Instant::now();
+    let mut handles = Vec::with_capacity(concurrency);
+    for worker in 0..concurrency {
+        // Hand out the remainder one op at a time to the first workers
+        let worker_ops = ops / concurrency + usize::from(worker < ops % concurrency);
+        let node = node.clone();
+        handles.push(tokio::spawn(run_worker(
+            node, worker, worker_ops, read_ratio,
+        )));
+    }
+
+    let mut latencies = Vec::with_capacity(ops);
+    let mut errors = 0;
+    let mut misses = 0;
+    for handle in handles {
+        let result = handle.await??;
+        latencies.extend(result.latencies);
+        errors += result.errors;
+        misses += result.misses;
+    }
+    let elapsed = start.elapsed();
+    latencies.sort();
+
+    println!(
+        "Completed: {} ok, {} errors, {} misses",
+        latencies.len(),
+        errors,
+        misses
+    );
+    println!(
+        "Elapsed: {:.2}s, Throughput: {:.1} ops/s",
+        elapsed.as_secs_f64(),
+        latencies.len() as f64 / elapsed.as_secs_f64()
+    );
+    println!(
+        "Latency: p50={:.2}ms p90={:.2}ms p99={:.2}ms max={:.2}ms",
+        percentile(&latencies, 0.50).as_secs_f64() * 1000.0,
+        percentile(&latencies, 0.90).as_secs_f64() * 1000.0,
+        percentile(&latencies, 0.99).as_secs_f64() * 1000.0,
+        percentile(&latencies, 1.0).as_secs_f64() * 1000.0,
+    );
+
+    // What the node saw, to tell network and client overhead from the time
+    // spent serving the request
+    if let (Some(before), Some(after)) = (server_before, server_latencies(&node).await) {
+        for op in ["put", "get"] {
+            let (Some(before), Some(after)) = (before.get(op), after.get(op)) else {
+                continue;
+            };
+            let run: Vec<u64> = after
+                .iter()
+                .zip(before)
+                .map(|(after, before)| after.saturating_sub(*before))
+                .collect();
+            let count: u64 = run.iter().sum();
+            if count > 0 {
+                println!(
+                    "Server {} latency: p50<{:.2}ms p90<{:.2}ms p99<{:.2}ms ({} requests)",
+                    op,
+                    latency::percentile(&run, 0.50).as_secs_f64() * 1000.0,
+                    latency::percentile(&run, 0.90).as_secs_f64() * 1000.0,
+                    latency::percentile(&run, 0.99).as_secs_f64() * 1000.0,
+                    count
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_repl(client: &DhtClient, base64: bool) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Commands: put <key> <value>, get <key>, find <id>, stats, quit");
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+
+    loop {
+        print!("> ");
+        std::io::stdout().flush()?;
+
+        let Some(line) = lines.next_line().await? else {
+            break;
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [] => continue,
+            ["quit"] | ["exit"] => break,
+            _ => {}
+        }
+
+        match ReplLine::try_parse_from(words) {
+            Ok(parsed) => {
+                // A failed request shouldn't end the session
+                if let Err(e) = run_command(client, parsed.command, base64).await {
+                    println!("Error: {}", e);
+                }
+            }
+            Err(e) => println!("{}", e),
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Retries are logged as warnings; show them unless RUST_LOG says otherwise
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+    let cli = Cli::parse();
+
+    if let Commands::Bench {
+        ops,
+        concurrency,
+        read_ratio,
+    } = cli.command
+    {
+        return run_bench(cli.node, ops, concurrency, read_ratio).await;
+    }
+
+    // Dumping another node doesn't need the default node to be up
+    let node = match &cli.command {
+        Commands::Dump {
+            addr: Some(addr), ..
+        } => endpoint(addr),
+        _ => cli.node,
+    };
+    let client = DhtClient::new(&node)?
+        .with_retries(cli.retries)
+        .with_namespace(cli.namespace);
+
+    let result = match cli.command {
+        Commands::Repl => run_repl(&client, cli.base64).await,
+        command => run_command(&client, command, cli.base64).await,
+    };
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
     }
 
     Ok(())
@@ -2,7 +2,7 @@ import React, { useState, useEffect } from 'react';
 import ChordRing from './ChordRing';
 import Controls from './Controls';
 import NodeDetailsModal from './NodeDetailsModal';
-import { getState, leaveNode } from './api';
+import { drainNode, getState, leaveNode, subscribeState } from './api';
 import './App.css';
 
 function App() {
@@ -17,22 +17,23 @@ function App() {
   useEffect(() => {
     let active = true;
 
+    const applyNodes = (data) => {
+      if (!active) return;
+
+      setNodes(data);
+      // Update selected node if it exists. A functional update, so the
+      // socket doesn't have to be reopened whenever the selection changes
+      setSelectedNode(selected => {
+        if (!selected) return selected;
+        // Node might have left
+        return data.find(n => n.id === selected.id) || null;
+      });
+    };
+
     const fetchNodes = async () => {
       try {
         const res = await getState();
-        if (!active) return;
-
-        setNodes(res.data);
-        // Update selected node if it exists
-        if (selectedNode) {
-          const updated = res.data.find(n => n.id === selectedNode.id);
-          if (updated) {
-            setSelectedNode(updated);
-          } else {
-            // Node might have left
-            setSelectedNode(null);
-          }
-        }
+        applyNodes(res.data);
       } catch (e) {
         if (active) {
           console.error("Failed to fetch state", e);
@@ -40,13 +41,22 @@ function App() {
       }
     };
 
+    // Live updates are pushed over the WebSocket; fall back to polling if it drops
+    let interval = null;
+    const socket = subscribeState(applyNodes);
+    socket.onclose = () => {
+      if (active && !interval) {
+        interval = setInterval(fetchNodes, 1000);
+      }
+    };
+
     fetchNodes();
-    const interval = setInterval(fetchNodes, 1000);
     return () => {
       active = false;
-      clearInterval(interval);
+      socket.close();
+      if (interval) clearInterval(interval);
     };
-  }, [selectedNode]);
+  }, []);
 
   const handleNodeClick = (node) => {
     setSelectedNode(node);
@@ -76,11 +86,29 @@ function App() {
     }
   };
 
+  const handleDrainNode = async (id) => {
+    if (confirm(`Drain node ${id}? It will stop taking writes and hand its keys to its successor.`)) {
+      const idStr = id.toString();
+      addLog(`Draining node ${idStr}...`, 'info');
+      try {
+        const res = await drainNode(idStr);
+        if (res.data.success) {
+          addLog(`Node ${idStr}: ${res.data.message}`, 'success');
+        } else {
+          addLog(`Failed to drain node: ${res.data.message}`, 'error');
+        }
+      } catch (e) {
+        console.error(e);
+        addLog(`Failed to drain node ${id}`, 'error');
+      }
+    }
+  };
+
   return (
     <div className="app-container">
       <div className="sidebar">
         <h1>Chord DHT</h1>
-        <Controls onLog={addLog} />
+        <Controls onLog={addLog} nodes={nodes} />
 
         <div className="node-list">
           <h3>Nodes ({nodes.length})</h3>
@@ -93,7 +121,11 @@ function App() {
             >
               <div className="node-id">{node.id.toString().substring(0, 16)}...</div>
               <div className="node-addr">{node.address}</div>
-              <div className="node-keys">Keys: {node.stored_keys ? node.stored_keys.length : 0}</div>
+              <div className="node-keys">
+                Keys: <span className="key-primary">{node.primary_keys ? node.primary_keys.length : 0} primary</span>
+                {' / '}
+                <span className="key-replica">{node.replica_keys ? node.replica_keys.length : 0} replica</span>
+              </div>
             </div>
           ))}
         </div>
@@ -117,8 +149,10 @@ function App() {
       {selectedNode && (
         <NodeDetailsModal
           node={selectedNode}
+          nodes={nodes}
           onClose={handleCloseModal}
           onLeave={handleLeaveNode}
+          onDrain={handleDrainNode}
         />
       )}
     </div>
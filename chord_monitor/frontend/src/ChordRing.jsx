@@ -1,21 +1,30 @@
-import React, { useEffect, useRef } from 'react';
+import React, { useEffect, useRef, useState } from 'react';
+import { getArcs } from './api';
 
 const ChordRing = ({ nodes }) => {
     const canvasRef = useRef(null);
+    const [arcs, setArcs] = useState([]);
 
-    const MAX_ID = 18446744073709551615n; // 2^64 - 1
     const RING_COLOR = '#444';
     const NODE_COLOR = '#00d2ff';
+    const DEAD_NODE_COLOR = '#555';
+    const OVERLOADED_NODE_COLOR = '#ff5c5c';
     const NODE_RADIUS = 12;
     const FONT_COLOR = '#e0e0e0';
 
-    const mapIdToAngle = (id) => {
-        const idBigInt = BigInt(id);
-        const multiplier = 1000000000n;
-        const scaled = (idBigInt * multiplier) / MAX_ID;
-        const ratio = Number(scaled) / Number(multiplier);
-        return ratio * 2 * Math.PI - Math.PI / 2;
-    };
+    const ARC_COLORS = ['#00d2ff', '#7b61ff', '#00c48c', '#ff9f43'];
+
+    // Positions come from the monitor as fractions of the ring, starting at the top
+    const fractionToAngle = (fraction) => fraction * 2 * Math.PI - Math.PI / 2;
+
+    // Arcs are recomputed by the monitor whenever the node states change
+    useEffect(() => {
+        let active = true;
+        getArcs()
+            .then(res => { if (active) setArcs(res.data.arcs); })
+            .catch(e => console.error("Failed to fetch arcs", e));
+        return () => { active = false; };
+    }, [nodes]);
 
     useEffect(() => {
         const canvas = canvasRef.current;
@@ -51,17 +60,36 @@ const ChordRing = ({ nodes }) => {
             ctx.lineWidth = 4;
             ctx.stroke();
 
+            // Owned arcs, each ending at its node
+            const ends = new Map();
+            arcs.forEach((arc, i) => {
+                ends.set(arc.id, arc.end);
+                const start = fractionToAngle(arc.end - arc.length);
+                ctx.beginPath();
+                ctx.arc(centerX, centerY, radius, start, fractionToAngle(arc.end));
+                ctx.strokeStyle = arc.alive ? ARC_COLORS[i % ARC_COLORS.length] : DEAD_NODE_COLOR;
+                ctx.globalAlpha = 0.6;
+                ctx.lineWidth = 8;
+                ctx.stroke();
+                ctx.globalAlpha = 1;
+            });
+
             // Draw Nodes
             nodes.forEach(node => {
-                const angle = mapIdToAngle(node.id);
+                if (!ends.has(node.id)) return;
+                const angle = fractionToAngle(ends.get(node.id));
                 const x = centerX + radius * Math.cos(angle);
                 const y = centerY + radius * Math.sin(angle);
 
-                // Node circle
+                // Node circle (greyed out when the node stopped reporting, red
+                // when it holds more keys than its soft limit)
+                const color = node.alive === false
+                    ? DEAD_NODE_COLOR
+                    : node.overloaded ? OVERLOADED_NODE_COLOR : NODE_COLOR;
                 ctx.beginPath();
                 ctx.arc(x, y, NODE_RADIUS, 0, 2 * Math.PI);
-                ctx.fillStyle = NODE_COLOR;
-                ctx.shadowColor = NODE_COLOR;
+                ctx.fillStyle = color;
+                ctx.shadowColor = color;
                 ctx.shadowBlur = 10;
                 ctx.fill();
                 ctx.shadowBlur = 0;
@@ -85,7 +113,7 @@ const ChordRing = ({ nodes }) => {
         resize();
 
         return () => window.removeEventListener('resize', resize);
-    }, [nodes]);
+    }, [nodes, arcs]);
 
     return (
         <div style={{ width: '100%', height: '100%', minHeight: '400px' }}>
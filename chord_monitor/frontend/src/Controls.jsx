@@ -1,15 +1,17 @@
 import React, { useState } from 'react';
-import { addNode, putData, getData } from './api';
+import { addNode, putData, getData, stabilizeAll } from './api';
 
-const Controls = ({ onLog }) => {
+const Controls = ({ onLog, nodes }) => {
     const [isAdding, setIsAdding] = useState(false);
+    const [isStabilizing, setIsStabilizing] = useState(false);
+    const [entryNode, setEntryNode] = useState('');
     const [putKey, setPutKey] = useState('');
     const [putValue, setPutValue] = useState('');
     const [getKey, setGetKey] = useState('');
 
     const handleAddNode = async () => {
         setIsAdding(true);
-        onLog('Adding node...', 'info');
+        onLog('Adding node (waiting for it to join)...', 'info');
         try {
             const res = await addNode();
             if (res.data.success) {
@@ -20,7 +22,22 @@ const Controls = ({ onLog }) => {
         } catch (e) {
             onLog('Failed to add node: ' + e.message, 'error');
         } finally {
-            setTimeout(() => setIsAdding(false), 2000);
+            setIsAdding(false);
+        }
+    };
+
+    const handleStabilize = async () => {
+        setIsStabilizing(true);
+        try {
+            const res = await stabilizeAll();
+            const failed = res.data.nodes.filter(node => !node.success);
+            failed.forEach(node => onLog(`Stabilize failed on ${node.address}: ${node.message}`, 'error'));
+            const done = res.data.nodes.length - failed.length;
+            onLog(`Stabilized ${done}/${res.data.nodes.length} nodes`, failed.length ? 'error' : 'success');
+        } catch (e) {
+            onLog('Stabilize failed: ' + e.message, 'error');
+        } finally {
+            setIsStabilizing(false);
         }
     };
 
@@ -31,7 +48,7 @@ const Controls = ({ onLog }) => {
         }
         onLog(`Putting ${putKey}=${putValue}...`, 'info');
         try {
-            const res = await putData(putKey, putValue);
+            const res = await putData(putKey, putValue, entryNode);
             if (res.data.success) {
                 onLog(res.data.message, 'success');
             } else {
@@ -49,8 +66,14 @@ const Controls = ({ onLog }) => {
         }
         onLog(`Getting ${getKey}...`, 'info');
         try {
-            const res = await getData(getKey);
-            if (res.data.found) {
+            const res = await getData(getKey, entryNode);
+            if (res.data.error) {
+                const msg = res.data.unreachable
+                    ? `Could not reach the ring: ${res.data.error}`
+                    : `Get failed: ${res.data.error}`;
+                onLog(msg, 'error');
+                alert(msg);
+            } else if (res.data.found) {
                 const msg = `Found value: "${res.data.value}"`;
                 onLog(msg, 'success');
                 alert(msg);
@@ -71,6 +94,21 @@ const Controls = ({ onLog }) => {
                 <button onClick={handleAddNode} disabled={isAdding} className="btn primary">
                     {isAdding ? 'Adding...' : 'Add Node'}
                 </button>
+                <button onClick={handleStabilize} disabled={isStabilizing} className="btn secondary">
+                    {isStabilizing ? 'Stabilizing...' : 'Stabilize Now'}
+                </button>
+            </div>
+
+            <div className="control-group">
+                <h3>Entry Node</h3>
+                <select value={entryNode} onChange={(e) => setEntryNode(e.target.value)}>
+                    <option value="">Random node</option>
+                    {nodes.map(node => (
+                        <option key={node.id} value={node.id}>
+                            {node.id.toString().substring(0, 16)}... ({node.address})
+                        </option>
+                    ))}
+                </select>
             </div>
 
             <div className="control-group">
@@ -1,9 +1,20 @@
 import React from 'react';
 import './NodeDetailsModal.css';
 
-const NodeDetailsModal = ({ node, onClose, onLeave }) => {
+const NodeDetailsModal = ({ node, nodes = [], onClose, onLeave, onDrain }) => {
     if (!node) return null;
 
+    const primaryKeys = node.primary_keys || [];
+    const replicaKeys = node.replica_keys || [];
+    // Copies of each key held as replicas by live nodes, for coverage
+    const replicaCounts = {};
+    nodes.filter(n => n.alive).forEach(n => {
+        (n.replica_keys || []).forEach(k => {
+            replicaCounts[k] = (replicaCounts[k] || 0) + 1;
+        });
+    });
+    const replicated = primaryKeys.filter(k => replicaCounts[k] > 0).length;
+
     return (
         <div className="modal-overlay" onClick={onClose}>
             <div className="modal-content" onClick={e => e.stopPropagation()}>
@@ -18,6 +29,17 @@ const NodeDetailsModal = ({ node, onClose, onLeave }) => {
                     <div className="detail-row">
                         <strong>Address:</strong> <span>{node.address}</span>
                     </div>
+                    <div className="detail-row">
+                        <strong>Uptime:</strong> <span>{node.uptime_seconds}s</span>
+                    </div>
+                    <div className="detail-row">
+                        <strong>Version:</strong> <span>{node.crate_version || 'unknown'}</span>
+                    </div>
+                    {node.overloaded && (
+                        <div className="detail-row">
+                            <strong>Overloaded:</strong> <span>over its soft key limit; consider adding nodes</span>
+                        </div>
+                    )}
 
                     <div className="section">
                         <h3>Predecessor</h3>
@@ -65,15 +87,34 @@ const NodeDetailsModal = ({ node, onClose, onLeave }) => {
                     </div>
 
                     <div className="section">
-                        <h3>Stored Keys ({node.stored_keys ? node.stored_keys.length : 0})</h3>
+                        <h3>
+                            Primary Keys ({primaryKeys.length}, {replicated} replicated)
+                        </h3>
                         <ul className="list scrollable">
-                            {node.stored_keys && node.stored_keys.map((k, i) => (
-                                <li key={i}>{k}</li>
+                            {primaryKeys.map((k, i) => (
+                                <li key={i} className="key-primary">
+                                    {k}
+                                    <span className="replica-count">
+                                        {replicaCounts[k] || 0} replicas
+                                    </span>
+                                </li>
+                            ))}
+                        </ul>
+                    </div>
+
+                    <div className="section">
+                        <h3>Replica Keys ({replicaKeys.length})</h3>
+                        <ul className="list scrollable">
+                            {replicaKeys.map((k, i) => (
+                                <li key={i} className="key-replica">{k}</li>
                             ))}
                         </ul>
                     </div>
                 </div>
                 <div className="modal-footer">
+                    <button className="drain-button" onClick={() => onDrain(node.id)}>
+                        Drain
+                    </button>
                     <button className="leave-button" onClick={() => onLeave(node.id)}>
                         Leave Network
                     </button>
@@ -5,9 +5,24 @@ const api = axios.create({
 });
 
 export const getState = () => api.get('/state');
+// Each node's owned arc of the id space, as fractions of the ring
+export const getArcs = () => api.get('/arcs');
+export const getOwner = (key) => api.get('/owner', { params: { key } });
 export const addNode = () => api.post('/add_node');
-export const putData = (key, value) => api.post('/put', { key, value });
-export const getData = (key) => api.post('/get', { key });
+// nodeId is optional; when omitted the monitor picks a random entry node
+export const putData = (key, value, nodeId) => api.post('/put', { key, value, node_id: nodeId || undefined });
+export const getData = (key, nodeId) => api.post('/get', { key, node_id: nodeId || undefined });
 export const leaveNode = (id) => api.post('/leave_node', { id });
+export const drainNode = (id) => api.post('/drain_node', { id });
+// Forces one stabilization round on every node; returns per-node results
+export const stabilizeAll = () => api.post('/stabilize');
+
+// Opens a WebSocket that receives the full node state whenever a node reports.
+export const subscribeState = (onNodes) => {
+    const protocol = window.location.protocol === 'https:' ? 'wss' : 'ws';
+    const socket = new WebSocket(`${protocol}://${window.location.host}/api/ws`);
+    socket.onmessage = (event) => onNodes(JSON.parse(event.data));
+    return socket;
+};
 
 export default api;
@@ -1,39 +1,324 @@
 use axum::{
-    extract::State,
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::{
+        header::{ACCEPT, CONTENT_TYPE, VARY},
+        HeaderMap, HeaderValue, StatusCode,
+    },
+    response::{IntoResponse, Response as HttpResponse},
     routing::{get, post},
     Json, Router,
 };
+use chord_proto::addr::node_url;
 use chord_proto::chord::{
     chord_client::ChordClient,
     chord_monitor_server::{ChordMonitor, ChordMonitorServer},
-    Empty, GetRequest, NodeState, PutRequest,
+    Empty, GetRequest, HealthState, NodeInfo, NodeState, NodeStats, PutRequest, ViolationKind,
 };
+use chord_proto::ring::owner_of;
+use chord_proto::{hash_addr, MAX_KEY_BYTES, MAX_VALUE_BYTES};
+use clap::Parser;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::net::SocketAddr;
-use std::process::Command;
-use std::sync::{Arc, Mutex};
+use std::collections::{HashMap, HashSet};
+use std::net::{SocketAddr, TcpListener as StdTcpListener};
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::process::{Child, Command};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::net::TcpListener;
-use tonic::{transport::Server, Request, Response, Status};
+use tokio::sync::{broadcast, Mutex};
+use tonic::{transport::Server, Code, Request, Response, Status};
 use tower_http::cors::CorsLayer;
 
-#[derive(Debug, Default)]
+const UPDATES_CHANNEL_CAPACITY: usize = 16;
+
+// A node that hasn't reported for this long is shown as dead
+const NODE_STALE_TIMEOUT: Duration = Duration::from_secs(10);
+// ...and is forgotten entirely after this long (None keeps it forever)
+const NODE_EVICT_TIMEOUT: Option<Duration> = Some(Duration::from_secs(60));
+const LIVENESS_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+// How long add_node waits for a spawned node to build, join and report Ready
+const NODE_READY_TIMEOUT: Duration = Duration::from_secs(120);
+const NODE_READY_POLL_INTERVAL: Duration = Duration::from_millis(500);
+// Ports handed out to spawned nodes, starting above the default node port
+const NODE_PORT_RANGE: RangeInclusive<u16> = 5010..=5999;
+// Connecting to a node is retried this many times, waiting the base delay
+// and doubling it each time (at most 50 + 100 + 200 ms in total)
+const CONNECT_RETRIES: u32 = 3;
+const CONNECT_RETRY_BASE_DELAY: Duration = Duration::from_millis(50);
+// Successors holding a copy of a node's keys, matching the nodes' default
+const DEFAULT_REPLICA_COUNT: usize = 2;
+
+#[derive(Debug)]
+struct NodeRecord {
+    state: NodeState,
+    last_seen: Instant,
+    alive: bool,
+}
+
+/// Nodes are told apart by id and address together: two addresses can hash
+/// to the same id, and keying on the id alone would let one overwrite the
+/// other.
+type NodeKey = (u64, String);
+
+#[derive(Debug)]
 struct MonitorState {
-    nodes: HashMap<u64, NodeState>,
-    next_port: u16,
+    nodes: HashMap<NodeKey, NodeRecord>,
+    allocated_ports: HashSet<u16>,
+    updates: broadcast::Sender<Vec<NodeStateDto>>,
+    node_binary: Option<PathBuf>,
 }
 
 impl MonitorState {
-    fn new() -> Self {
+    fn new(node_binary: Option<PathBuf>) -> Self {
+        let (updates, _) = broadcast::channel(UPDATES_CHANNEL_CAPACITY);
         Self {
             nodes: HashMap::new(),
-            next_port: 5010, // Start allocating node ports from 5010 to avoid conflicts
+            allocated_ports: HashSet::new(),
+            updates,
+            node_binary,
+        }
+    }
+
+    /// Picks the lowest port in `NODE_PORT_RANGE` that isn't already handed
+    /// out and can currently be bound, and marks it as allocated.
+    fn allocate_port(&mut self) -> Option<u16> {
+        let port = NODE_PORT_RANGE.into_iter().find(|port| {
+            !self.allocated_ports.contains(port)
+                && StdTcpListener::bind(("127.0.0.1", *port)).is_ok()
+        })?;
+        self.allocated_ports.insert(port);
+        Some(port)
+    }
+
+    /// Returns the port of a node address to the pool.
+    fn release_port(&mut self, addr: &str) {
+        if let Some(port) = addr
+            .rsplit_once(':')
+            .and_then(|(_, port)| port.parse::<u16>().ok())
+        {
+            self.allocated_ports.remove(&port);
+        }
+    }
+
+    /// A node with this id, preferring a live one if the id is shared.
+    fn node_by_id(&self, id: u64) -> Option<(&NodeKey, &NodeRecord)> {
+        self.nodes
+            .iter()
+            .filter(|((node_id, _), _)| *node_id == id)
+            .max_by_key(|(_, record)| record.alive)
+    }
+
+    fn snapshot(&self) -> Vec<NodeStateDto> {
+        self.nodes.values().map(NodeStateDto::from_record).collect()
+    }
+
+    /// Orders the known nodes by id and checks each reported successor edge
+    /// against the next live node on the ring.
+    fn ring_view(&self) -> RingDto {
+        let mut records: Vec<&NodeRecord> = self.nodes.values().collect();
+        records.sort_by(|a, b| (a.state.id, &a.state.address).cmp(&(b.state.id, &b.state.address)));
+        let live_ids: Vec<u64> = records
+            .iter()
+            .filter(|record| record.alive)
+            .map(|record| record.state.id)
+            .collect();
+
+        let nodes: Vec<RingEntryDto> = records
+            .into_iter()
+            .map(|record| {
+                let id = record.state.id;
+                let successor = record.state.successors.first().cloned();
+                let expected_successor = if record.alive {
+                    live_ids
+                        .iter()
+                        .find(|&&other| other > id)
+                        .or_else(|| live_ids.first())
+                        .copied()
+                } else {
+                    None
+                };
+
+                let mut issues = Vec::new();
+                if !record.alive {
+                    issues.push("node stopped reporting".to_string());
+                }
+                match &successor {
+                    None => issues.push("no successor reported".to_string()),
+                    Some(succ) => match self.nodes.get(&(succ.id, succ.address.clone())) {
+                        None => issues.push(format!("successor {} is not a known node", succ.id)),
+                        Some(succ_record) if !succ_record.alive => {
+                            issues.push(format!("successor {} is dead", succ.id))
+                        }
+                        Some(_) => {
+                            if let Some(expected) = expected_successor {
+                                if expected != succ.id {
+                                    issues.push(format!(
+                                        "successor {} but expected {}",
+                                        succ.id, expected
+                                    ));
+                                }
+                            }
+                        }
+                    },
+                }
+
+                RingEntryDto {
+                    id: id.to_string(),
+                    address: record.state.address.clone(),
+                    alive: record.alive,
+                    successor: successor.map(Into::into),
+                    expected_successor: expected_successor.map(|id| id.to_string()),
+                    issues,
+                }
+            })
+            .collect();
+
+        RingDto {
+            consistent: nodes.iter().all(|node| node.issues.is_empty()),
+            nodes,
+        }
+    }
+
+    /// Each node's owned arc `(predecessor, id]` as a fraction of the id
+    /// space, using the reported predecessor or else the previous known node
+    /// by id. Arcs are sorted by where they start.
+    fn arcs_view(&self) -> ArcsDto {
+        let mut keys: Vec<&NodeKey> = self.nodes.keys().collect();
+        keys.sort_unstable();
+        let ids: Vec<u64> = keys.iter().map(|(id, _)| *id).collect();
+
+        let mut arcs: Vec<(u64, ArcDto)> = keys
+            .iter()
+            .enumerate()
+            .map(|(i, &key)| {
+                let id = key.0;
+                let record = &self.nodes[key];
+                let predecessor = record
+                    .state
+                    .predecessor
+                    .as_ref()
+                    .map(|pred| pred.id)
+                    .unwrap_or(ids[(i + ids.len() - 1) % ids.len()]);
+                let span = id.wrapping_sub(predecessor);
+                let replicas: Vec<String> = record
+                    .state
+                    .successors
+                    .iter()
+                    .filter(|succ| succ.id != id)
+                    .take(DEFAULT_REPLICA_COUNT)
+                    .map(|succ| succ.id.to_string())
+                    .collect();
+                let arc = ArcDto {
+                    id: id.to_string(),
+                    address: record.state.address.clone(),
+                    alive: record.alive,
+                    predecessor: predecessor.to_string(),
+                    start: id_fraction(predecessor),
+                    end: id_fraction(id),
+                    // A node that is its own predecessor owns everything
+                    length: if span == 0 { 1.0 } else { id_fraction(span) },
+                    wraps: predecessor >= id,
+                    replicas,
+                    replica_coverage: 0.0,
+                };
+                (predecessor, arc)
+            })
+            .collect();
+
+        let mut coverage: HashMap<String, f64> = HashMap::new();
+        for (_, arc) in &arcs {
+            for replica in &arc.replicas {
+                *coverage.entry(replica.clone()).or_default() += arc.length;
+            }
+        }
+        for (_, arc) in &mut arcs {
+            arc.replica_coverage = coverage.get(&arc.id).copied().unwrap_or(0.0).min(1.0);
+        }
+
+        arcs.sort_by_key(|(start, _)| *start);
+        ArcsDto {
+            arcs: arcs.into_iter().map(|(_, arc)| arc).collect(),
+        }
+    }
+
+    /// The node that owns `key` going by the live nodes' ids, without asking
+    /// the ring. Matches a lookup once the ring has stabilized.
+    fn owner_view(&self, key: String) -> OwnerDto {
+        let members: Vec<NodeInfo> = self
+            .nodes
+            .values()
+            .filter(|record| record.alive)
+            .map(|record| NodeInfo {
+                id: record.state.id,
+                address: record.state.address.clone(),
+                observer: record.state.observer,
+            })
+            .collect();
+        let key_id = hash_addr(&key);
+        OwnerDto {
+            key,
+            key_id: key_id.to_string(),
+            owner: owner_of(key_id, &members).map(Into::into),
+        }
+    }
+
+    /// Marks nodes that stopped reporting as dead and evicts long-gone ones.
+    /// Returns whether anything changed.
+    fn sweep_stale_nodes(&mut self) -> bool {
+        let mut changed = false;
+        for record in self.nodes.values_mut() {
+            if record.alive && record.last_seen.elapsed() > NODE_STALE_TIMEOUT {
+                println!(
+                    "Node {} stopped reporting, marking as dead",
+                    record.state.id
+                );
+                record.alive = false;
+                changed = true;
+            }
+        }
+        if let Some(evict_timeout) = NODE_EVICT_TIMEOUT {
+            let evicted: Vec<NodeKey> = self
+                .nodes
+                .iter()
+                .filter(|(_, record)| record.last_seen.elapsed() > evict_timeout)
+                .map(|(key, _)| key.clone())
+                .collect();
+            for key in evicted {
+                // A node the monitor started got its port from the pool
+                if let Some(record) = self.nodes.remove(&key) {
+                    self.release_port(&record.state.address);
+                    changed = true;
+                }
+            }
         }
+        changed
     }
 }
 
+/// Shared between the gRPC service and the HTTP handlers. An async mutex
+/// can't be poisoned, so a panicking handler can't wedge the monitor; every
+/// handler still copies out what it needs and drops the guard before it
+/// makes a call to a node.
 type SharedState = Arc<Mutex<MonitorState>>;
 
+/// Position of an id on the ring in [0, 1), exact to f64 precision.
+fn id_fraction(id: u64) -> f64 {
+    (id >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Prebuilt chord_node executable used by "add node". Debug builds fall
+    /// back to `cargo run` when this is not set.
+    #[arg(long, env = "CHORD_NODE_BINARY")]
+    node_binary: Option<PathBuf>,
+}
+
 struct MonitorService {
     state: SharedState,
 }
@@ -43,8 +328,17 @@ impl ChordMonitor for MonitorService {
     async fn report_state(&self, request: Request<NodeState>) -> Result<Response<Empty>, Status> {
         let node_state = request.into_inner();
         println!("Received state from node {}", node_state.id);
-        let mut state = self.state.lock().unwrap();
-        state.nodes.insert(node_state.id, node_state);
+        let mut state = self.state.lock().await;
+        state.nodes.insert(
+            (node_state.id, node_state.address.clone()),
+            NodeRecord {
+                state: node_state,
+                last_seen: Instant::now(),
+                alive: true,
+            },
+        );
+        // No subscribers is fine, there's just nobody to push to
+        let _ = state.updates.send(state.snapshot());
         Ok(Response::new(Empty {}))
     }
 }
@@ -53,17 +347,41 @@ impl ChordMonitor for MonitorService {
 struct ApiPutRequest {
     key: String,
     value: String,
+    node_id: Option<String>, // Entry node; a random node is used when absent
 }
 
 #[derive(Deserialize)]
 struct ApiGetRequest {
     key: String,
+    node_id: Option<String>, // Entry node; a random node is used when absent
+}
+
+// Optional body for add_node; without one the node's id is its address hash
+#[derive(Deserialize, Default)]
+struct ApiAddNodeRequest {
+    node_id: Option<String>, // u64 as string to avoid JS precision issues
+    id_seed: Option<String>,
 }
 
 #[derive(Serialize)]
 struct ApiGetResponse {
     found: bool,
     value: String,
+    /// Set when the lookup failed, as opposed to finding no value
+    error: Option<String>,
+    /// The failure was reaching the ring rather than an error from it
+    unreachable: bool,
+}
+
+impl ApiGetResponse {
+    fn failed(error: String, unreachable: bool) -> Self {
+        Self {
+            found: false,
+            value: String::new(),
+            error: Some(error),
+            unreachable,
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -72,9 +390,67 @@ struct ApiStatusResponse {
     message: String,
 }
 
+#[derive(Serialize)]
+struct ApiStabilizeResult {
+    id: String,
+    address: String,
+    success: bool,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct ApiStabilizeResponse {
+    /// One entry per known node, sorted by id
+    nodes: Vec<ApiStabilizeResult>,
+}
+
+#[derive(Serialize)]
+struct ApiSelfCheckResult {
+    id: String,
+    address: String,
+    reachable: bool,
+    violations: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct ApiSelfCheckResponse {
+    /// One entry per known node, sorted by id
+    nodes: Vec<ApiSelfCheckResult>,
+    healthy: bool,
+}
+
+#[derive(Serialize)]
+struct ApiHealResult {
+    id: String,
+    address: String,
+    reachable: bool,
+    /// What SelfCheck reported before any fix was applied
+    violations: Vec<String>,
+    actions: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct ApiHealResponse {
+    /// One entry per known node, sorted by id
+    nodes: Vec<ApiHealResult>,
+    issues_found: usize,
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let state = Arc::new(Mutex::new(MonitorState::new()));
+    let args = Args::parse();
+    let state = Arc::new(Mutex::new(MonitorState::new(args.node_binary)));
+
+    let sweep_state = state.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(LIVENESS_CHECK_INTERVAL).await;
+            let mut state = sweep_state.lock().await;
+            if state.sweep_stale_nodes() {
+                let _ = state.updates.send(state.snapshot());
+            }
+        }
+    });
 
     let grpc_state = state.clone();
     tokio::spawn(async move {
@@ -91,10 +467,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let app = Router::new()
         .route("/api/state", get(get_state))
+        .route("/api/ws", get(handle_ws))
+        .route("/api/ring", get(get_ring))
+        .route("/api/arcs", get(get_arcs))
+        .route("/api/owner", get(get_owner))
         .route("/api/put", post(handle_put))
         .route("/api/get", post(handle_get))
         .route("/api/add_node", post(handle_add_node))
         .route("/api/leave_node", post(handle_leave_node))
+        .route("/api/drain_node", post(handle_drain_node))
+        .route("/api/stabilize", post(handle_stabilize))
+        .route("/api/self_check", get(handle_self_check))
+        .route("/api/heal", post(handle_heal))
         .nest_service("/", tower_http::services::ServeDir::new("frontend/dist"))
         .layer(CorsLayer::permissive())
         .with_state(state);
@@ -107,14 +491,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, Debug)]
 struct NodeInfoDto {
     id: String,
     address: String,
 }
 
-impl From<chord_proto::chord::NodeInfo> for NodeInfoDto {
-    fn from(info: chord_proto::chord::NodeInfo) -> Self {
+impl From<NodeInfo> for NodeInfoDto {
+    fn from(info: NodeInfo) -> Self {
         Self {
             id: info.id.to_string(),
             address: info.address,
@@ -122,7 +506,7 @@ impl From<chord_proto::chord::NodeInfo> for NodeInfoDto {
     }
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, Debug)]
 struct NodeStateDto {
     id: String,
     address: String,
@@ -130,10 +514,83 @@ struct NodeStateDto {
     successors: Vec<NodeInfoDto>,
     finger_table: Vec<NodeInfoDto>,
     stored_keys: Vec<String>,
+    /// Keys in the node's own (predecessor, self] range
+    primary_keys: Vec<String>,
+    /// Copies the node holds for the nodes before it
+    replica_keys: Vec<String>,
+    stats: Option<NodeStats>,
+    uptime_seconds: u64,
+    crate_version: String,
+    /// Routes but stores no keys
+    observer: bool,
+    /// Stores more keys than its --max-keys-soft
+    overloaded: bool,
+    alive: bool,
+    /// Milliseconds since the node last reported its state
+    last_seen_ms: u64,
+}
+
+#[derive(Serialize)]
+struct RingEntryDto {
+    id: String,
+    address: String,
+    alive: bool,
+    /// Successor as reported by the node itself
+    successor: Option<NodeInfoDto>,
+    /// Next live node by id among all nodes known to the monitor
+    expected_successor: Option<String>,
+    issues: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct RingDto {
+    /// Nodes sorted by id
+    nodes: Vec<RingEntryDto>,
+    consistent: bool,
+}
+
+#[derive(Serialize)]
+struct ArcDto {
+    id: String,
+    address: String,
+    alive: bool,
+    /// Id the arc starts after
+    predecessor: String,
+    /// Ring positions of the predecessor and the node, in [0, 1)
+    start: f64,
+    end: f64,
+    /// Fraction of the id space owned, in (0, 1]
+    length: f64,
+    /// The arc crosses zero, so `end` is below `start`
+    wraps: bool,
+    /// Successors expected to hold copies of this arc's keys
+    replicas: Vec<String>,
+    /// Fraction of the id space this node holds replicas for
+    replica_coverage: f64,
+}
+
+#[derive(Serialize)]
+struct ArcsDto {
+    /// Sorted by start position
+    arcs: Vec<ArcDto>,
+}
+
+#[derive(Deserialize)]
+struct OwnerQuery {
+    key: String,
+}
+
+#[derive(Serialize)]
+struct OwnerDto {
+    key: String,
+    key_id: String,
+    /// None when no live node is known
+    owner: Option<NodeInfoDto>,
 }
 
-impl From<NodeState> for NodeStateDto {
-    fn from(state: NodeState) -> Self {
+impl NodeStateDto {
+    fn from_record(record: &NodeRecord) -> Self {
+        let state = record.state.clone();
         Self {
             id: state.id.to_string(),
             address: state.address,
@@ -141,18 +598,166 @@ impl From<NodeState> for NodeStateDto {
             successors: state.successors.into_iter().map(Into::into).collect(),
             finger_table: state.finger_table.into_iter().map(Into::into).collect(),
             stored_keys: state.stored_keys,
+            primary_keys: state.primary_keys,
+            replica_keys: state.replica_keys,
+            stats: state.stats,
+            uptime_seconds: state.uptime_seconds,
+            crate_version: state.crate_version,
+            observer: state.observer,
+            overloaded: state.overloaded,
+            alive: record.alive,
+            last_seen_ms: record.last_seen.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+/// How `/api/state` is encoded, picked from the request's `Accept` header.
+/// CBOR and MessagePack are much smaller than JSON for rings with many keys;
+/// JSON stays the default so browsers get something they can read.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StateFormat {
+    Json,
+    Cbor,
+    MessagePack,
+}
+
+impl StateFormat {
+    /// The media type in `Accept` we can produce with the highest q-value,
+    /// ties going to the one listed first. A wildcard stands for JSON, and a
+    /// type with q=0 is never picked. Anything else, or no header, gets JSON.
+    fn negotiate(headers: &HeaderMap) -> Self {
+        let Some(accept) = headers.get(ACCEPT).and_then(|v| v.to_str().ok()) else {
+            return Self::Json;
+        };
+        let mut best: Option<(Self, f32)> = None;
+        for range in accept.split(',') {
+            let mut params = range.split(';');
+            let media = params.next().unwrap_or("").trim().to_ascii_lowercase();
+            let format = match media.as_str() {
+                "application/json" | "application/*" | "*/*" => Self::Json,
+                "application/cbor" => Self::Cbor,
+                "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => {
+                    Self::MessagePack
+                }
+                _ => continue,
+            };
+            // A malformed q-value makes the range unusable rather than preferred
+            let q = params
+                .find_map(|param| {
+                    let (name, value) = param.split_once('=')?;
+                    name.trim()
+                        .eq_ignore_ascii_case("q")
+                        .then(|| value.trim().parse::<f32>().unwrap_or(0.0))
+                })
+                .unwrap_or(1.0);
+            if q > 0.0 && !best.is_some_and(|(_, best_q)| q <= best_q) {
+                best = Some((format, q));
+            }
+        }
+        best.map_or(Self::Json, |(format, _)| format)
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Cbor => "application/cbor",
+            Self::MessagePack => "application/msgpack",
+        }
+    }
+
+    /// Encodes `value` into a response. MessagePack keeps field names, like
+    /// the other two, so consumers don't depend on field order.
+    fn respond<T: Serialize>(self, value: &T) -> HttpResponse {
+        let body = match self {
+            Self::Json => serde_json::to_vec(value).map_err(|e| e.to_string()),
+            Self::Cbor => {
+                let mut body = Vec::new();
+                ciborium::into_writer(value, &mut body)
+                    .map(|()| body)
+                    .map_err(|e| e.to_string())
+            }
+            Self::MessagePack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+        };
+        match body {
+            Ok(body) => {
+                let mut response = body.into_response();
+                let headers = response.headers_mut();
+                headers.insert(CONTENT_TYPE, HeaderValue::from_static(self.content_type()));
+                headers.insert(VARY, HeaderValue::from_static("accept"));
+                response
+            }
+            Err(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to encode state: {}", e),
+            )
+                .into_response(),
         }
     }
 }
 
-async fn get_state(State(state): State<SharedState>) -> Json<Vec<NodeStateDto>> {
-    let state = state.lock().unwrap();
-    let nodes: Vec<NodeStateDto> = state.nodes.values().cloned().map(Into::into).collect();
-    Json(nodes)
+async fn get_state(State(state): State<SharedState>, headers: HeaderMap) -> HttpResponse {
+    let snapshot = state.lock().await.snapshot();
+    StateFormat::negotiate(&headers).respond(&snapshot)
+}
+
+async fn get_ring(State(state): State<SharedState>) -> Json<RingDto> {
+    let state = state.lock().await;
+    Json(state.ring_view())
+}
+
+async fn get_arcs(State(state): State<SharedState>) -> Json<ArcsDto> {
+    let state = state.lock().await;
+    Json(state.arcs_view())
+}
+
+async fn get_owner(
+    State(state): State<SharedState>,
+    Query(query): Query<OwnerQuery>,
+) -> Json<OwnerDto> {
+    let state = state.lock().await;
+    Json(state.owner_view(query.key))
+}
+
+async fn handle_ws(ws: WebSocketUpgrade, State(state): State<SharedState>) -> impl IntoResponse {
+    let (initial, updates) = {
+        let state = state.lock().await;
+        (state.snapshot(), state.updates.subscribe())
+    };
+    ws.on_upgrade(move |socket| forward_updates(socket, initial, updates))
+}
+
+async fn forward_updates(
+    mut socket: WebSocket,
+    initial: Vec<NodeStateDto>,
+    mut updates: broadcast::Receiver<Vec<NodeStateDto>>,
+) {
+    let mut nodes = initial;
+    loop {
+        let frame = match serde_json::to_string(&nodes) {
+            Ok(json) => json,
+            Err(e) => {
+                println!("Failed to serialize state update: {}", e);
+                return;
+            }
+        };
+        if socket.send(Message::Text(frame)).await.is_err() {
+            // Client went away
+            return;
+        }
+
+        nodes = loop {
+            match updates.recv().await {
+                Ok(nodes) => break nodes,
+                // A slow client only needs the latest state, so skip the backlog
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+        };
+    }
 }
 
 async fn get_any_node_address(state: SharedState) -> Option<String> {
-    let state = state.lock().unwrap();
+    let state = state.lock().await;
     if state.nodes.is_empty() {
         return None;
     }
@@ -163,27 +768,71 @@ async fn get_any_node_address(state: SharedState) -> Option<String> {
     state
         .nodes
         .values()
+        .filter(|n| n.alive)
         .choose(&mut rng)
-        .map(|n| n.address.clone())
+        .map(|n| n.state.address.clone())
 }
 
+async fn get_node_address(state: SharedState, node_id: Option<String>) -> Result<String, String> {
+    let Some(node_id) = node_id else {
+        return get_any_node_address(state)
+            .await
+            .ok_or_else(|| "No nodes available".to_string());
+    };
+    let node_id = node_id
+        .parse::<u64>()
+        .map_err(|_| "Invalid node ID".to_string())?;
+    let state = state.lock().await;
+    state
+        .node_by_id(node_id)
+        .map(|(_, node)| node.state.address.clone())
+        .ok_or_else(|| "Node not found".to_string())
+}
+
+/// Connects to a node, retrying with a short backoff so a node that is
+/// briefly busy doesn't fail the whole API call.
 async fn connect_to_node(addr: String) -> Result<ChordClient<tonic::transport::Channel>, String> {
-    let endpoint = format!("http://{}", addr);
-    ChordClient::connect(endpoint)
-        .await
-        .map_err(|e| format!("Connection error: {}", e))
+    let endpoint = node_url(&addr);
+    let mut attempt = 0;
+    loop {
+        match ChordClient::connect(endpoint.clone()).await {
+            Ok(client) => return Ok(client),
+            Err(e) if attempt < CONNECT_RETRIES => {
+                let delay = CONNECT_RETRY_BASE_DELAY * 2u32.pow(attempt);
+                attempt += 1;
+                println!(
+                    "Connecting to {} failed ({}), retrying in {}ms",
+                    addr,
+                    e,
+                    delay.as_millis()
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => return Err(format!("Connection error: {}", e)),
+        }
+    }
 }
 
 async fn handle_put(
     State(state): State<SharedState>,
     Json(payload): Json<ApiPutRequest>,
 ) -> Json<ApiStatusResponse> {
-    let node_addr = match get_any_node_address(state).await {
-        Some(addr) => addr,
-        None => {
+    if payload.key.len() > MAX_KEY_BYTES || payload.value.len() > MAX_VALUE_BYTES {
+        return Json(ApiStatusResponse {
+            success: false,
+            message: format!(
+                "Key or value too large (limits: {} byte key, {} byte value)",
+                MAX_KEY_BYTES, MAX_VALUE_BYTES
+            ),
+        });
+    }
+
+    let node_addr = match get_node_address(state, payload.node_id).await {
+        Ok(addr) => addr,
+        Err(e) => {
             return Json(ApiStatusResponse {
                 success: false,
-                message: "No nodes available".into(),
+                message: e,
             })
         }
     };
@@ -192,7 +841,8 @@ async fn handle_put(
         Ok(mut client) => {
             let request = Request::new(PutRequest {
                 key: payload.key,
-                value: payload.value,
+                value: payload.value.into_bytes(),
+                ..Default::default()
             });
             match client.put(request).await {
                 Ok(response) => {
@@ -226,80 +876,183 @@ async fn handle_get(
     State(state): State<SharedState>,
     Json(payload): Json<ApiGetRequest>,
 ) -> Json<ApiGetResponse> {
-    let node_addr = match get_any_node_address(state).await {
-        Some(addr) => addr,
-        None => {
-            return Json(ApiGetResponse {
-                found: false,
-                value: "No nodes available".into(),
-            })
-        }
+    let node_addr = match get_node_address(state, payload.node_id).await {
+        Ok(addr) => addr,
+        Err(e) => return Json(ApiGetResponse::failed(e, true)),
     };
 
     match connect_to_node(node_addr).await {
         Ok(mut client) => {
-            let request = Request::new(GetRequest { key: payload.key });
+            let request = Request::new(GetRequest {
+                key: payload.key,
+                ..Default::default()
+            });
             match client.get(request).await {
                 Ok(response) => {
                     let resp = response.into_inner();
                     Json(ApiGetResponse {
                         found: resp.found,
-                        value: resp.value,
+                        value: String::from_utf8_lossy(&resp.value).into_owned(),
+                        error: None,
+                        unreachable: false,
                     })
                 }
-                Err(e) => Json(ApiGetResponse {
-                    found: false,
-                    value: format!("RPC error: {}", e),
-                }),
+                Err(e) => Json(ApiGetResponse::failed(
+                    format!("RPC error: {}", e.message()),
+                    matches!(e.code(), Code::Unavailable | Code::DeadlineExceeded),
+                )),
             }
         }
-        Err(e) => Json(ApiGetResponse {
-            found: false,
-            value: e,
-        }),
+        Err(e) => Json(ApiGetResponse::failed(e, true)),
     }
 }
 
-async fn handle_add_node(State(state): State<SharedState>) -> Json<ApiStatusResponse> {
-    let (port, join_addr) = {
-        let mut state_guard = state.lock().unwrap();
-        let port = state_guard.next_port;
-        state_guard.next_port += 1;
+async fn handle_add_node(
+    State(state): State<SharedState>,
+    payload: Option<Json<ApiAddNodeRequest>>,
+) -> Json<ApiStatusResponse> {
+    let payload = payload.map(|Json(payload)| payload).unwrap_or_default();
+    let node_id = match payload.node_id.map(|id| id.parse::<u64>()).transpose() {
+        Ok(node_id) => node_id,
+        Err(_) => {
+            return Json(ApiStatusResponse {
+                success: false,
+                message: "Invalid node ID".into(),
+            })
+        }
+    };
+    if let Some(node_id) = node_id {
+        let state = state.lock().await;
+        if state
+            .node_by_id(node_id)
+            .is_some_and(|(_, node)| node.alive)
+        {
+            return Json(ApiStatusResponse {
+                success: false,
+                message: format!("Node {} is already running", node_id),
+            });
+        }
+    }
 
-        // If there are existing nodes, pick one to join
+    let (port, join_addr, node_binary) = {
+        let mut state_guard = state.lock().await;
+        let Some(port) = state_guard.allocate_port() else {
+            return Json(ApiStatusResponse {
+                success: false,
+                message: format!(
+                    "No free port in {}-{}",
+                    NODE_PORT_RANGE.start(),
+                    NODE_PORT_RANGE.end()
+                ),
+            });
+        };
+
+        // If there are existing live nodes, pick one to join
         let join_addr = state_guard
             .nodes
             .values()
-            .next()
-            .map(|first_node| first_node.address.clone());
-        (port, join_addr)
+            .find(|node| node.alive)
+            .map(|first_node| first_node.state.address.clone());
+        (port, join_addr, state_guard.node_binary.clone())
     };
 
-    let mut cmd = Command::new("cargo");
-    cmd.current_dir(".."); // Run from workspace root
-    cmd.arg("run")
-        .arg("--bin")
-        .arg("chord_node")
-        .arg("--")
-        .arg("--port")
+    let node_addr = format!("127.0.0.1:{}", port);
+    let Some(mut cmd) = node_command(node_binary) else {
+        state.lock().await.release_port(&node_addr);
+        return Json(ApiStatusResponse {
+            success: false,
+            message: "No node binary configured (set --node-binary or CHORD_NODE_BINARY)".into(),
+        });
+    };
+    cmd.arg("--port")
         .arg(port.to_string())
         .arg("--monitor")
         .arg("127.0.0.1:50051");
 
-    if let Some(join) = join_addr {
-        cmd.arg("--join").arg(join);
+    match join_addr {
+        Some(join) => cmd.arg("--join").arg(join),
+        None => cmd.arg("--create"),
+    };
+    if let Some(node_id) = node_id {
+        cmd.arg("--node-id").arg(node_id.to_string());
+    } else if let Some(seed) = payload.id_seed {
+        cmd.arg("--id-seed").arg(seed);
     }
 
     // Spawn in background
-    match cmd.spawn() {
-        Ok(_) => Json(ApiStatusResponse {
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            state.lock().await.release_port(&node_addr);
+            return Json(ApiStatusResponse {
+                success: false,
+                message: format!("Failed to spawn node: {}", e),
+            });
+        }
+    };
+
+    // Only report success once the node can actually serve traffic
+    match wait_for_ready(node_addr.clone(), &mut child).await {
+        Ok(()) => Json(ApiStatusResponse {
             success: true,
-            message: format!("Spawned node on port {}", port),
-        }),
-        Err(e) => Json(ApiStatusResponse {
-            success: false,
-            message: format!("Failed to spawn node: {}", e),
+            message: format!("Node on port {} is ready", port),
         }),
+        Err(e) => {
+            // A node that is still starting keeps its port
+            if let Ok(Some(_)) = child.try_wait() {
+                state.lock().await.release_port(&node_addr);
+            }
+            Json(ApiStatusResponse {
+                success: false,
+                message: e,
+            })
+        }
+    }
+}
+
+/// Builds the command that starts a node: the configured binary, or
+/// `cargo run` from the workspace root in debug builds.
+fn node_command(node_binary: Option<PathBuf>) -> Option<Command> {
+    match node_binary {
+        Some(path) => Some(Command::new(path)),
+        None if cfg!(debug_assertions) => {
+            let mut cmd = Command::new("cargo");
+            cmd.current_dir(".."); // Run from workspace root
+            cmd.arg("run").arg("--bin").arg("chord_node").arg("--");
+            Some(cmd)
+        }
+        None => None,
+    }
+}
+
+/// Polls a freshly spawned node's Health RPC until it reports Ready, the
+/// process exits, or `NODE_READY_TIMEOUT` passes.
+async fn wait_for_ready(addr: String, child: &mut Child) -> Result<(), String> {
+    let deadline = Instant::now() + NODE_READY_TIMEOUT;
+    loop {
+        if let Ok(Some(status)) = child.try_wait() {
+            return Err(format!(
+                "Node on {} exited before becoming ready ({})",
+                addr, status
+            ));
+        }
+
+        if let Ok(mut client) = connect_to_node(addr.clone()).await {
+            if let Ok(resp) = client.health(Request::new(Empty {})).await {
+                if resp.into_inner().state() == HealthState::Ready {
+                    return Ok(());
+                }
+            }
+        }
+
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "Node on {} was not ready after {}s",
+                addr,
+                NODE_READY_TIMEOUT.as_secs()
+            ));
+        }
+        tokio::time::sleep(NODE_READY_POLL_INTERVAL).await;
     }
 }
 
@@ -322,10 +1075,10 @@ async fn handle_leave_node(
         }
     };
 
-    let node_addr = {
-        let state = state.lock().unwrap();
-        if let Some(node) = state.nodes.get(&node_id) {
-            node.address.clone()
+    let (node_key, node_addr) = {
+        let state = state.lock().await;
+        if let Some((key, node)) = state.node_by_id(node_id) {
+            (key.clone(), node.state.address.clone())
         } else {
             return Json(ApiStatusResponse {
                 success: false,
@@ -338,9 +1091,11 @@ async fn handle_leave_node(
         Ok(mut client) => {
             match client.leave(Request::new(Empty {})).await {
                 Ok(_) => {
-                    // Remove from state
-                    let mut state = state.lock().unwrap();
-                    state.nodes.remove(&node_id);
+                    // Remove from state and free its port for the next node
+                    let mut state = state.lock().await;
+                    if let Some(record) = state.nodes.remove(&node_key) {
+                        state.release_port(&record.state.address);
+                    }
 
                     Json(ApiStatusResponse {
                         success: true,
@@ -359,3 +1114,593 @@ async fn handle_leave_node(
         }),
     }
 }
+
+/// Drains a node. Unlike leave, the node keeps running (and reporting), so
+/// it stays in the state until it is stopped.
+async fn handle_drain_node(
+    State(state): State<SharedState>,
+    Json(payload): Json<ApiLeaveRequest>,
+) -> Json<ApiStatusResponse> {
+    let node_id = match payload.id.parse::<u64>() {
+        Ok(id) => id,
+        Err(_) => {
+            return Json(ApiStatusResponse {
+                success: false,
+                message: "Invalid node ID".into(),
+            })
+        }
+    };
+
+    let node_addr = {
+        let state = state.lock().await;
+        if let Some((_, node)) = state.node_by_id(node_id) {
+            node.state.address.clone()
+        } else {
+            return Json(ApiStatusResponse {
+                success: false,
+                message: "Node not found".into(),
+            });
+        }
+    };
+
+    match connect_to_node(node_addr).await {
+        Ok(mut client) => match client.drain(Request::new(Empty {})).await {
+            Ok(resp) => Json(ApiStatusResponse {
+                success: true,
+                message: format!(
+                    "Node drained ({} keys handed off), safe to stop",
+                    resp.into_inner().keys_transferred
+                ),
+            }),
+            Err(e) => Json(ApiStatusResponse {
+                success: false,
+                message: format!("RPC error: {}", e),
+            }),
+        },
+        Err(e) => Json(ApiStatusResponse {
+            success: false,
+            message: e,
+        }),
+    }
+}
+
+/// Runs `call` against every known node at once and collects the outcomes,
+/// sorted by node id.
+async fn on_every_node<T, F, Fut>(
+    state: &SharedState,
+    call: F,
+) -> Vec<(u64, String, Result<T, String>)>
+where
+    T: Send + 'static,
+    F: Fn(ChordClient<tonic::transport::Channel>) -> Fut + Clone + Send + 'static,
+    Fut: std::future::Future<Output = Result<T, Status>> + Send + 'static,
+{
+    let targets: Vec<(u64, String)> = {
+        let state = state.lock().await;
+        state
+            .nodes
+            .values()
+            .map(|node| (node.state.id, node.state.address.clone()))
+            .collect()
+    };
+
+    let mut calls = tokio::task::JoinSet::new();
+    for (id, address) in targets {
+        let call = call.clone();
+        calls.spawn(async move {
+            let outcome = match connect_to_node(address.clone()).await {
+                Ok(client) => call(client).await.map_err(|e| format!("RPC error: {}", e)),
+                Err(e) => Err(e),
+            };
+            (id, address, outcome)
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = calls.join_next().await {
+        if let Ok(result) = joined {
+            results.push(result);
+        }
+    }
+    results.sort_by_key(|(id, _, _)| *id);
+    results
+}
+
+/// Runs one forced stabilization round on every known node at once, so the
+/// ring converges without waiting for the nodes' own timers.
+async fn handle_stabilize(State(state): State<SharedState>) -> Json<ApiStabilizeResponse> {
+    let results = on_every_node(&state, |mut client| async move {
+        client.force_stabilize(Request::new(Empty {})).await
+    })
+    .await;
+
+    Json(ApiStabilizeResponse {
+        nodes: results
+            .into_iter()
+            .map(|(id, address, outcome)| {
+                let (success, message) = match outcome {
+                    Ok(_) => (true, "Stabilized".to_string()),
+                    Err(e) => (false, e),
+                };
+                ApiStabilizeResult {
+                    id: id.to_string(),
+                    address,
+                    success,
+                    message,
+                }
+            })
+            .collect(),
+    })
+}
+
+/// Asks every known node to check its own invariants.
+async fn handle_self_check(State(state): State<SharedState>) -> Json<ApiSelfCheckResponse> {
+    let results = on_every_node(&state, |mut client| async move {
+        client.self_check(Request::new(Empty {})).await
+    })
+    .await;
+
+    let nodes: Vec<ApiSelfCheckResult> = results
+        .into_iter()
+        .map(|(id, address, outcome)| {
+            let (reachable, violations) = match outcome {
+                Ok(resp) => (true, resp.into_inner().violations),
+                Err(e) => (false, vec![e]),
+            };
+            ApiSelfCheckResult {
+                id: id.to_string(),
+                address,
+                reachable,
+                violations,
+            }
+        })
+        .collect();
+    Json(ApiSelfCheckResponse {
+        healthy: nodes
+            .iter()
+            .all(|node| node.reachable && node.violations.is_empty()),
+        nodes,
+    })
+}
+
+/// Runs SelfCheck on every known node and applies the matching fix where
+/// something is broken: Rebalance for misplaced primary keys and
+/// ForceStabilize for bad routing state.
+async fn handle_heal(State(state): State<SharedState>) -> Json<ApiHealResponse> {
+    let results = on_every_node(&state, |mut client| async move {
+        let report = client
+            .self_check(Request::new(Empty {}))
+            .await?
+            .into_inner();
+        let has = |kind: ViolationKind| report.details.iter().any(|v| v.kind == kind as i32);
+        let misplaced = has(ViolationKind::MisplacedKey);
+        // Nodes older than `details` only send descriptions; stabilizing is
+        // the safe fix for whatever they found
+        let routing = has(ViolationKind::Routing)
+            || (report.details.is_empty() && !report.violations.is_empty());
+        let violations = report.violations;
+
+        let mut actions = Vec::new();
+        if misplaced {
+            actions.push(match client.rebalance(Request::new(Empty {})).await {
+                Ok(resp) => format!("Rebalance moved {} keys", resp.into_inner().keys_moved),
+                Err(e) => format!("Rebalance failed: {}", e),
+            });
+        }
+        if routing {
+            actions.push(match client.force_stabilize(Request::new(Empty {})).await {
+                Ok(_) => "ForceStabilize ran".to_string(),
+                Err(e) => format!("ForceStabilize failed: {}", e),
+            });
+        }
+        Ok((violations, actions))
+    })
+    .await;
+
+    let nodes: Vec<ApiHealResult> = results
+        .into_iter()
+        .map(|(id, address, outcome)| {
+            let (reachable, violations, actions) = match outcome {
+                Ok((violations, actions)) => (true, violations, actions),
+                Err(e) => (false, vec![e], Vec::new()),
+            };
+            ApiHealResult {
+                id: id.to_string(),
+                address,
+                reachable,
+                violations,
+                actions,
+            }
+        })
+        .collect();
+    Json(ApiHealResponse {
+        issues_found: nodes.iter().map(|node| node.violations.len()).sum(),
+        nodes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(id: u64) -> NodeState {
+        NodeState {
+            id,
+            address: format!("127.0.0.1:{}", 6000 + id),
+            ..Default::default()
+        }
+    }
+
+    fn format_for(accept: &str) -> StateFormat {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, accept.parse().unwrap());
+        StateFormat::negotiate(&headers)
+    }
+
+    #[test]
+    fn test_state_format_ranks_by_q_value() {
+        assert_eq!(StateFormat::negotiate(&HeaderMap::new()), StateFormat::Json);
+        assert_eq!(format_for("application/cbor"), StateFormat::Cbor);
+        // Listed first, but less preferred
+        assert_eq!(
+            format_for("application/json;q=0.5, application/cbor"),
+            StateFormat::Cbor
+        );
+        assert_eq!(
+            format_for("application/cbor; q=0.2, application/msgpack; q=0.9"),
+            StateFormat::MessagePack
+        );
+        // Ties go to the first listed
+        assert_eq!(
+            format_for("application/msgpack;q=0.8, application/cbor;q=0.8"),
+            StateFormat::MessagePack
+        );
+        // q=0 refuses a type
+        assert_eq!(
+            format_for("application/cbor;q=0, application/msgpack;q=0.1"),
+            StateFormat::MessagePack
+        );
+        assert_eq!(format_for("application/cbor;q=0"), StateFormat::Json);
+        // A wildcard is answered with the default
+        assert_eq!(format_for("application/cbor;q=0.5, */*"), StateFormat::Json);
+        assert_eq!(
+            format_for("text/html, application/cbor;Q=0.3"),
+            StateFormat::Cbor
+        );
+        assert_eq!(format_for("application/cbor;q=abc"), StateFormat::Json);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_reports_and_api_calls() {
+        let state: SharedState = Arc::new(Mutex::new(MonitorState::new(None)));
+        let service = Arc::new(MonitorService {
+            state: state.clone(),
+        });
+
+        // A handler that panics mid-update must not wedge everyone else
+        let poisoner = state.clone();
+        let panicked = tokio::spawn(async move {
+            let _guard = poisoner.lock().await;
+            panic!("handler failed while holding the state");
+        })
+        .await;
+        assert!(panicked.is_err());
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for i in 0..200u64 {
+            let service = service.clone();
+            tasks.spawn(async move {
+                service
+                    .report_state(Request::new(report(i % 20)))
+                    .await
+                    .expect("ReportState failed");
+            });
+            let state = state.clone();
+            tasks.spawn(async move {
+                let _ = get_state(State(state.clone()), HeaderMap::new()).await;
+                let _ = get_ring(State(state.clone())).await;
+                let _ = get_arcs(State(state.clone())).await;
+                let _ = get_any_node_address(state).await;
+            });
+        }
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while let Some(task) = tasks.join_next().await {
+                task.unwrap();
+            }
+        })
+        .await
+        .expect("Monitor stopped answering");
+
+        assert_eq!(state.lock().await.nodes.len(), 20);
+    }
+
+    #[test]
+    fn test_evicted_node_gives_back_its_port() {
+        let mut state = MonitorState::new(None);
+        let port = state.allocate_port().unwrap();
+        let gone = NodeState {
+            id: 7,
+            address: format!("127.0.0.1:{}", port),
+            ..Default::default()
+        };
+        let evict_timeout = NODE_EVICT_TIMEOUT.unwrap();
+        state.nodes.insert(
+            (gone.id, gone.address.clone()),
+            NodeRecord {
+                state: gone,
+                last_seen: Instant::now() - evict_timeout - Duration::from_secs(1),
+                alive: false,
+            },
+        );
+
+        assert!(state.sweep_stale_nodes());
+        assert!(state.nodes.is_empty());
+        assert!(!state.allocated_ports.contains(&port));
+        assert_eq!(state.allocate_port(), Some(port));
+    }
+
+    /// Serves `node` on an ephemeral port, as the nodes' own tests do.
+    async fn serve_node(id: u64) -> Arc<chord_node::Node> {
+        use chord_proto::chord::chord_server::ChordServer;
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let node = Arc::new(chord_node::Node::new(id, addr));
+        let server = ChordServer::new((*node).clone());
+        tokio::spawn(async move {
+            Server::builder()
+                .add_service(server)
+                .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener))
+                .await
+                .unwrap();
+        });
+        node
+    }
+
+    #[tokio::test]
+    async fn test_heal_rebalances_misplaced_primaries() {
+        use chord_proto::chord::chord_server::Chord;
+
+        let a = serve_node(1 << 62).await;
+        let b = serve_node(3 << 62).await;
+        b.join(a.addr.clone()).await.unwrap();
+        for _ in 0..10 {
+            for node in [&a, &b] {
+                node.stabilize().await;
+                node.fix_fingers().await;
+                node.check_predecessor().await;
+            }
+        }
+
+        // A key b owns, left on a by a predecessor that is out of date
+        let key = (0..)
+            .map(|i| format!("stray_{}", i))
+            .find(|k| chord_node::Node::is_in_range_inclusive(hash_addr(k), a.id, b.id))
+            .unwrap();
+        a.replicate(Request::new(PutRequest {
+            key: key.clone(),
+            value: b"v".to_vec(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+        let self_info = |node: &chord_node::Node| NodeInfo {
+            id: node.id,
+            address: node.addr.clone(),
+            observer: false,
+        };
+        a.set_neighbors(Some(self_info(&a)), vec![self_info(&b)])
+            .await;
+
+        let state: SharedState = Arc::new(Mutex::new(MonitorState::new(None)));
+        let service = MonitorService {
+            state: state.clone(),
+        };
+        for node in [&a, &b] {
+            service
+                .report_state(Request::new(node.snapshot().await))
+                .await
+                .unwrap();
+        }
+
+        let Json(healed) = handle_heal(State(state)).await;
+        let a_result = healed
+            .nodes
+            .iter()
+            .find(|node| node.address == a.addr)
+            .unwrap();
+        assert!(
+            a_result
+                .violations
+                .iter()
+                .any(|v| v.starts_with("primary key")),
+            "{:?}",
+            a_result.violations
+        );
+        assert!(
+            a_result
+                .actions
+                .contains(&"Rebalance moved 1 keys".to_string()),
+            "{:?}",
+            a_result.actions
+        );
+        assert_eq!(b.scan_local_prefix("stray_").await, vec![key]);
+    }
+
+    #[tokio::test]
+    async fn test_heal_stabilizes_broken_routing() {
+        let a = serve_node(1 << 62).await;
+        let b = serve_node(3 << 62).await;
+        b.join(a.addr.clone()).await.unwrap();
+        for _ in 0..10 {
+            for node in [&a, &b] {
+                node.stabilize().await;
+                node.fix_fingers().await;
+                node.check_predecessor().await;
+            }
+        }
+
+        let self_info = |node: &chord_node::Node| NodeInfo {
+            id: node.id,
+            address: node.addr.clone(),
+            observer: false,
+        };
+        a.set_neighbors(
+            Some(self_info(&a)),
+            vec![self_info(&b), self_info(&a), self_info(&b)],
+        )
+        .await;
+
+        let state: SharedState = Arc::new(Mutex::new(MonitorState::new(None)));
+        let service = MonitorService {
+            state: state.clone(),
+        };
+        for node in [&a, &b] {
+            service
+                .report_state(Request::new(node.snapshot().await))
+                .await
+                .unwrap();
+        }
+
+        let Json(healed) = handle_heal(State(state)).await;
+        let a_result = healed
+            .nodes
+            .iter()
+            .find(|node| node.address == a.addr)
+            .unwrap();
+        assert!(!a_result.violations.is_empty());
+        assert_eq!(
+            a_result.actions,
+            vec!["ForceStabilize ran".to_string()],
+            "{:?}",
+            a_result.violations
+        );
+        // Stabilizing rebuilt a's successor list; its predecessor waits on b
+        let left = a.self_check().await;
+        assert!(
+            left.iter().all(|v| !v.description.contains("successor")),
+            "{:?}",
+            left
+        );
+    }
+
+    fn info(id: u64) -> NodeInfo {
+        NodeInfo {
+            id,
+            address: format!("127.0.0.1:{}", 6000 + id % 1000),
+            observer: false,
+        }
+    }
+
+    /// A report from node `id` with the given predecessor and successors.
+    fn linked_report(id: u64, predecessor: Option<u64>, successors: &[u64]) -> NodeState {
+        NodeState {
+            id,
+            address: info(id).address,
+            predecessor: predecessor.map(info),
+            successors: successors.iter().copied().map(info).collect(),
+            ..Default::default()
+        }
+    }
+
+    async fn arcs_for(reports: Vec<NodeState>) -> ArcsDto {
+        let state: SharedState = Arc::new(Mutex::new(MonitorState::new(None)));
+        let service = MonitorService {
+            state: state.clone(),
+        };
+        for report in reports {
+            service.report_state(Request::new(report)).await.unwrap();
+        }
+        let state = state.lock().await;
+        state.arcs_view()
+    }
+
+    #[tokio::test]
+    async fn test_arc_wrapping_past_zero() {
+        let (a, b) = (1u64 << 62, 3u64 << 62);
+        let view = arcs_for(vec![
+            linked_report(a, Some(b), &[b]),
+            linked_report(b, Some(a), &[a]),
+        ])
+        .await;
+
+        let ids: Vec<&str> = view.arcs.iter().map(|arc| arc.id.as_str()).collect();
+        assert_eq!(ids, vec![b.to_string(), a.to_string()]);
+        let (b_arc, a_arc) = (&view.arcs[0], &view.arcs[1]);
+
+        assert!(!b_arc.wraps);
+        assert_eq!((b_arc.start, b_arc.end, b_arc.length), (0.25, 0.75, 0.5));
+        // (b, a] crosses zero, so it ends below where it starts
+        assert!(a_arc.wraps);
+        assert_eq!(a_arc.predecessor, b.to_string());
+        assert_eq!((a_arc.start, a_arc.end, a_arc.length), (0.75, 0.25, 0.5));
+
+        assert_eq!(a_arc.replicas, vec![b.to_string()]);
+        assert_eq!(b_arc.replicas, vec![a.to_string()]);
+        assert_eq!(a_arc.replica_coverage, 0.5);
+        assert_eq!(b_arc.replica_coverage, 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_arc_without_reported_predecessor_wraps_to_last_node() {
+        let (a, b) = (1u64 << 62, 3u64 << 62);
+        let view = arcs_for(vec![
+            linked_report(a, None, &[b]),
+            linked_report(b, None, &[a]),
+        ])
+        .await;
+
+        // The lowest id falls back to the highest one as its predecessor
+        let a_arc = view
+            .arcs
+            .iter()
+            .find(|arc| arc.id == a.to_string())
+            .unwrap();
+        assert_eq!(a_arc.predecessor, b.to_string());
+        assert!(a_arc.wraps);
+        assert_eq!(a_arc.length, 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_single_node_owns_the_full_ring() {
+        let id = 5u64 << 60;
+        for predecessor in [Some(id), None] {
+            let view = arcs_for(vec![linked_report(id, predecessor, &[id])]).await;
+            assert_eq!(view.arcs.len(), 1);
+            let arc = &view.arcs[0];
+            assert_eq!(arc.length, 1.0);
+            assert_eq!(arc.start, arc.end);
+            assert!(arc.wraps);
+            // Its only successor is itself, so nothing holds copies
+            assert!(arc.replicas.is_empty());
+            assert_eq!(arc.replica_coverage, 0.0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_colliding_ids_are_kept_apart() {
+        let state: SharedState = Arc::new(Mutex::new(MonitorState::new(None)));
+        let service = MonitorService {
+            state: state.clone(),
+        };
+
+        let first = report(7);
+        let twin = NodeState {
+            address: "127.0.0.1:7999".to_string(),
+            ..report(7)
+        };
+        for node in [first.clone(), twin.clone(), first] {
+            service.report_state(Request::new(node)).await.unwrap();
+        }
+
+        let state = state.lock().await;
+        assert_eq!(state.nodes.len(), 2);
+        let mut addresses: Vec<String> = state
+            .snapshot()
+            .into_iter()
+            .map(|node| node.address)
+            .collect();
+        addresses.sort();
+        assert_eq!(addresses, vec!["127.0.0.1:6007", "127.0.0.1:7999"]);
+        assert_eq!(state.arcs_view().arcs.len(), 2);
+    }
+}
@@ -18,7 +18,7 @@ def run_benchmarks():
 
 def parse_scalability(output):
     print("Parsing Scalability Benchmark...")
-    match = re.search(r"=== Benchmark 1: Scalability .*?===\n(Nodes,Avg_Hops\n[\s\S]*?)(?=\n===|$)", output)
+    match = re.search(r"=== Benchmark 1: Scalability .*?===\n(Nodes,Avg_Hops[^\n]*\n[\s\S]*?)(?=\n===|$)", output)
     if match:
         data_str = match.group(1)
         lines = [line for line in data_str.split('\n') if line.strip() and (line.startswith('Nodes') or line[0].isdigit())]
@@ -123,7 +123,7 @@ def plot_latency_cdf(df, output_dir):
 
 def parse_concurrent_throughput(output):
     print("Parsing Concurrent Throughput Benchmark...")
-    match = re.search(r"=== Benchmark 3: Concurrent Throughput .*?===\n[\s\S]*?(Clients,Ops_Per_Sec\n[\s\S]*?)(?=\n===|$)", output)
+    match = re.search(r"=== Benchmark 3: Concurrent Throughput .*?===\n[\s\S]*?(Clients,Ops_Per_Sec[^\n]*\n[\s\S]*?)(?=\n===|$)", output)
     if match:
         data_str = match.group(1)
         lines = [line for line in data_str.split('\n') if line.strip() and (line.startswith('Clients') or line[0].isdigit())]
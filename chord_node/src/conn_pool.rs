@@ -0,0 +1,55 @@
+use crate::transport::Transport;
+use chord_proto::chord::chord_client::ChordClient;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tonic::transport::Channel;
+use tonic::Status;
+
+/// Open channels to peers, keyed by endpoint, so repeated RPCs to the same
+/// node share one HTTP/2 connection instead of dialing every time. A caller
+/// that sees an RPC fail evicts the channel, so the next call dials afresh.
+#[derive(Debug, Default)]
+pub struct ConnectionPool {
+    transport: Transport,
+    channels: Mutex<HashMap<String, Channel>>,
+    dials: AtomicU64,
+}
+
+impl ConnectionPool {
+    pub fn new(transport: Transport) -> Self {
+        Self {
+            transport,
+            ..Self::default()
+        }
+    }
+
+    /// A client for `endpoint`, reusing its channel if one is open.
+    pub async fn client(&self, endpoint: &str) -> Result<ChordClient<Channel>, Status> {
+        if let Some(channel) = self.channels.lock().unwrap().get(endpoint) {
+            return Ok(ChordClient::new(channel.clone()));
+        }
+
+        let channel = self.transport.channel(endpoint).await?;
+        self.dials.fetch_add(1, Ordering::Relaxed);
+        // Another task may have dialed the same peer meanwhile; keep one
+        let channel = self
+            .channels
+            .lock()
+            .unwrap()
+            .entry(endpoint.to_string())
+            .or_insert(channel)
+            .clone();
+        Ok(ChordClient::new(channel))
+    }
+
+    /// Drops the channel to `endpoint`, e.g. after an RPC on it failed.
+    pub fn evict(&self, endpoint: &str) {
+        self.channels.lock().unwrap().remove(endpoint);
+    }
+
+    /// How many connections have been dialed so far.
+    pub fn dials(&self) -> u64 {
+        self.dials.load(Ordering::Relaxed)
+    }
+}
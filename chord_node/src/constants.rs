@@ -1,14 +1,115 @@
-pub const FINGER_TABLE_SIZE: usize = 64;
+// One finger per bit of the id space
+pub const FINGER_TABLE_SIZE: usize = crate::ring::ID_BITS as usize;
 pub const REPLICATION_COUNT: usize = 2;
 pub const SUCCESSOR_LIST_LIMIT: usize = 5;
 pub const DEFAULT_PORT: u16 = 5000;
 pub const LOCALHOST: &str = "127.0.0.1";
+pub use chord_proto::{MAX_KEY_BYTES, MAX_VALUE_BYTES, VALUE_CHUNK_SIZE};
+// Check replicas after a successful get and push the value to any that lag behind
+pub const READ_REPAIR_ENABLED: bool = true;
+
+// Anti-entropy: replicas are compared through hash trees with 2^depth leaves,
+// and only differing buckets are resent unless more than this fraction differ
+pub const MERKLE_TREE_DEPTH: u32 = 6;
+pub const ANTI_ENTROPY_FULL_PUSH_FRACTION: f64 = 0.5;
+
+// WatchChanges subscribers that fall this many events behind are disconnected
+pub const CHANGE_EVENTS_CAPACITY: usize = 1024;
 
 // Intervals
 pub const STABILIZATION_INTERVAL_MS: u64 = 1000;
 pub const FIX_FINGERS_INTERVAL_MS: u64 = 1000;
 pub const CHECK_PREDECESSOR_INTERVAL_MS: u64 = 1000;
 pub const MAINTAIN_REPLICATION_INTERVAL_MS: u64 = 1000;
+// Each interval is randomly stretched or shrunk by up to this percentage
+pub const MAINTENANCE_JITTER_PERCENT: u64 = 20;
+
+// fix_fingers refreshes the stalest finger, except for this fraction of
+// rounds where it picks one at random
+pub const FIX_FINGERS_RANDOM_PICK_PROBABILITY: f64 = 0.1;
+
+// Idempotent puts: retries carrying an already applied request_id are ignored
+// if they arrive within this window (and the id hasn't been pushed out by newer ones)
+pub const IDEMPOTENCY_WINDOW_MS: u64 = 60_000;
+pub const IDEMPOTENCY_CACHE_SIZE: usize = 10_000;
+
+// Lookup cache: how many recently resolved owners a node remembers and for
+// how long. A cached owner is confirmed with its predecessor before use
+pub const LOOKUP_CACHE_SIZE: usize = 1024;
+pub const LOOKUP_CACHE_TTL_MS: u64 = 2000;
+
+// A ping result is reused for this long by other checks of the same node.
+// Kept well under the maintenance intervals so failures are still noticed
+// on the next round
+pub const LIVENESS_CACHE_TTL_MS: u64 = 100;
+
+// Backpressure: at most this many lookups/puts/gets are forwarded to other
+// nodes at once (and as many more for requests forwarded to us); extra ones
+// wait up to the timeout, then fail with resource_exhausted
+pub const MAX_CONCURRENT_FORWARDS: usize = 64;
+pub const FORWARD_QUEUE_TIMEOUT_MS: u64 = 2000;
+
+// At most this many replicas are sent at once; the rest wait their turn.
+// Replicas go over pooled connections, so this also bounds concurrent dials
+pub const MAX_CONCURRENT_REPLICATIONS: usize = 16;
+
+// Replication lag percentiles are taken over this many recent replica acks
+pub const REPLICATION_LAG_WINDOW: usize = 1024;
+
+// Unchanged state is re-reported to the monitor this often, well inside the
+// monitor's stale timeout; changes are reported on the next maintenance round
+pub const MONITOR_REPORT_HEARTBEAT_MS: u64 = 3000;
+
+// A report that takes longer than this counts as failed
+pub const MONITOR_TIMEOUT_MS: u64 = 500;
+// After a failed report, reporting pauses this long, doubling per failure
+// in a row up to the max
+pub const MONITOR_RETRY_BASE_MS: u64 = 1000;
+pub const MONITOR_RETRY_MAX_MS: u64 = 30_000;
+
+// A write refused by a draining node carries its successor's address under
+// this metadata key
+pub const REDIRECT_METADATA_KEY: &str = "chord-redirect";
+
+// Values built with Append carry this metadata key; their value is an
+// encoded ValueList
+pub const LIST_METADATA_KEY: &str = "chord-list";
+
+// Drain and leave resend keys to a successor every interval until its digest
+// confirms the handoff, giving up after the timeout
+pub const HANDOFF_RETRY_INTERVAL_MS: u64 = 200;
+pub const HANDOFF_TIMEOUT_MS: u64 = 10_000;
+// A leaving node pings each successor this many times, waiting the backoff
+// (doubled per try) in between, before handing its keys to the next one
+pub const LEAVE_TRANSFER_ATTEMPTS: u32 = 3;
+pub const LEAVE_TRANSFER_BACKOFF_MS: u64 = 100;
+
+// Import hands keys to each owner in batches of at most this many keys or
+// bytes, so it doesn't hold a whole owner's share in memory
+pub const IMPORT_BATCH_KEYS: usize = 500;
+pub const IMPORT_BATCH_BYTES: usize = 2 * 1024 * 1024;
+// A node receiving a streamed key transfer stores it in batches of about
+// this many bytes instead of buffering the whole transfer
+pub const TRANSFER_BATCH_BYTES: usize = 2 * 1024 * 1024;
+// Export walks the ring at most this many keys ahead of the client
+pub const EXPORT_BUFFER_LEN: usize = 256;
 
 // Delays
 pub const LEAVE_EXIT_DELAY_MS: u64 = 100;
+
+// Retries
+pub const FIND_SUCCESSOR_RETRY_LIMIT: usize = 1;
+// A node whose hashed id is already taken rehashes with a salt and rejoins,
+// at most this many times
+pub const ID_COLLISION_RETRIES: u32 = 8;
+
+// Forwards a lookup may take before it is aborted. A healthy ring needs
+// O(log n) hops, far below this
+pub const MAX_LOOKUP_HOPS: u32 = 2 * FINGER_TABLE_SIZE as u32;
+
+// A cluster health walk visits at most this many nodes, so a successor chain
+// that never comes back around can't keep it going
+pub const CLUSTER_HEALTH_MAX_NODES: usize = 1024;
+
+// Outbound RPCs taking at least this long are logged as slow
+pub const SLOW_RPC_THRESHOLD_MS: u64 = 500;
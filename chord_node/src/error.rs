@@ -0,0 +1,98 @@
+use chord_proto::PROTOCOL_VERSION;
+use std::fmt;
+use tonic::{Code, Status};
+
+/// Why joining a ring failed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JoinError {
+    /// The seed node could not be contacted.
+    SeedUnreachable { seed: String, reason: String },
+    /// The seed answered, but with an error or an unusable successor.
+    BadResponse { seed: String, reason: String },
+    /// This node already has neighbours other than itself.
+    AlreadyJoined,
+    /// The seed is this node, so joining would only make a ring of one.
+    SelfJoin { seed: String },
+    /// The seed speaks a protocol version we can't talk to, or it refused ours.
+    IncompatibleVersion { seed: String, version: u32 },
+    /// Another node, at `address`, already has our id.
+    IdCollision { id: u64, address: String },
+}
+
+impl JoinError {
+    /// Classifies a failed RPC to `seed`: transport failures and unavailable
+    /// nodes are unreachable, anything else is a bad response.
+    pub fn from_status(seed: &str, status: Status) -> Self {
+        let seed = seed.to_string();
+        let reason = status.message().to_string();
+        match status.code() {
+            Code::Unavailable | Code::DeadlineExceeded => Self::SeedUnreachable { seed, reason },
+            _ => Self::BadResponse { seed, reason },
+        }
+    }
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SeedUnreachable { seed, reason } => write!(
+                f,
+                "could not reach seed node {} ({}); check that it is running and the address is right",
+                seed, reason
+            ),
+            Self::BadResponse { seed, reason } => write!(
+                f,
+                "seed node {} could not place us in the ring ({}); try another seed",
+                seed, reason
+            ),
+            Self::AlreadyJoined => write!(f, "node is already part of a ring"),
+            Self::SelfJoin { seed } => write!(
+                f,
+                "seed node {} is this node; join through another node, or create a new ring instead",
+                seed
+            ),
+            Self::IncompatibleVersion { seed, version } => write!(
+                f,
+                "seed node {} speaks protocol version {}, which can't be mixed with ours ({}); upgrade the older node",
+                seed, version, PROTOCOL_VERSION
+            ),
+            Self::IdCollision { id, address } => write!(
+                f,
+                "node {} already has id {}; start this node with another id",
+                address, id
+            ),
+        }
+    }
+}
+
+impl std::error::Error for JoinError {}
+
+/// A node configuration that can't work.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    /// Replicas go to successors, so we must track at least as many
+    /// successors as the configured replication count.
+    SuccessorListTooShort {
+        successor_list_len: usize,
+        replication_count: usize,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SuccessorListTooShort {
+                successor_list_len,
+                replication_count,
+            } => write!(
+                f,
+                "successor list length {} is too short to hold {} replicas; use at least {}",
+                successor_list_len,
+                replication_count,
+                (*replication_count).max(1)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
@@ -0,0 +1,83 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Bounded record of recently applied request ids, used to make retried puts
+/// no-ops. Ids are forgotten once they are older than `window` or when more
+/// than `capacity` newer ids have been recorded, so deduplication is only
+/// guaranteed for retries that arrive within the window. Replicas and nodes
+/// taking over keys receive the ids too, so a retry still matches after the
+/// key changes owner.
+#[derive(Debug)]
+pub struct RecentRequests {
+    capacity: usize,
+    window: Duration,
+    seen: HashMap<String, Instant>,
+    order: VecDeque<(String, Instant)>,
+}
+
+impl RecentRequests {
+    pub fn new(capacity: usize, window: Duration) -> Self {
+        Self {
+            capacity,
+            window,
+            seen: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Whether `id` was recorded within the dedup window.
+    pub fn contains(&mut self, id: &str) -> bool {
+        self.evict_expired();
+        self.seen.contains_key(id)
+    }
+
+    pub fn insert(&mut self, id: String) {
+        self.evict_expired();
+        while self.seen.len() >= self.capacity {
+            if !self.pop_oldest() {
+                break;
+            }
+        }
+        let now = Instant::now();
+        self.seen.insert(id.clone(), now);
+        self.order.push_back((id, now));
+    }
+
+    /// The ids still within the dedup window, so they can travel with keys
+    /// to a node that takes them over.
+    pub fn ids(&self) -> Vec<String> {
+        self.seen
+            .iter()
+            .filter(|(_, at)| at.elapsed() <= self.window)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+
+    fn evict_expired(&mut self) {
+        while let Some((_, at)) = self.order.front() {
+            if at.elapsed() <= self.window {
+                break;
+            }
+            self.pop_oldest();
+        }
+    }
+
+    fn pop_oldest(&mut self) -> bool {
+        let Some((id, at)) = self.order.pop_front() else {
+            return false;
+        };
+        // Only forget the id if it wasn't re-recorded since
+        if self.seen.get(&id) == Some(&at) {
+            self.seen.remove(&id);
+        }
+        true
+    }
+}
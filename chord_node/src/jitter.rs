@@ -0,0 +1,31 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::Duration;
+
+/// Spreads maintenance intervals by up to `percent` either way, so nodes
+/// started together drift apart instead of stabilizing in lockstep. Seeded
+/// from the node id, so a node always gets the same sequence.
+#[derive(Debug, Clone)]
+pub struct Jitter {
+    rng: StdRng,
+    percent: u64,
+}
+
+impl Jitter {
+    pub fn new(seed: u64, percent: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            percent: percent.min(100),
+        }
+    }
+
+    /// `base_ms` moved by a random amount within ±`percent`.
+    pub fn apply(&mut self, base_ms: u64) -> Duration {
+        let spread = base_ms * self.percent / 100;
+        if spread == 0 {
+            return Duration::from_millis(base_ms);
+        }
+        let offset = self.rng.gen_range(0..=2 * spread);
+        Duration::from_millis(base_ms - spread + offset)
+    }
+}
@@ -0,0 +1,49 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// The most recent `capacity` lag samples, for rolling percentiles.
+#[derive(Debug)]
+pub struct LagWindow {
+    capacity: usize,
+    samples: VecDeque<Duration>,
+}
+
+impl LagWindow {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            samples: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Adds a sample, dropping the oldest once the window is full.
+    pub fn record(&mut self, lag: Duration) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(lag);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// The sample at fraction `p` (0.0 - 1.0) of the sorted window, or zero
+    /// if there are no samples yet.
+    pub fn percentile(&self, p: f64) -> Duration {
+        if self.samples.is_empty() {
+            return Duration::ZERO;
+        }
+        let mut sorted: Vec<Duration> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+        let idx = ((sorted.len() as f64 * p).ceil() as usize).clamp(1, sorted.len()) - 1;
+        sorted[idx]
+    }
+}
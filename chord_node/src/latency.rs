@@ -0,0 +1,67 @@
+use chord_proto::chord::OpLatency;
+use chord_proto::latency::{bucket_for, LATENCY_BUCKETS};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// The client-facing requests whose serving time a node records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Put,
+    Get,
+    Append,
+    FindSuccessor,
+}
+
+impl Operation {
+    pub const ALL: [Operation; 4] = [
+        Operation::Put,
+        Operation::Get,
+        Operation::Append,
+        Operation::FindSuccessor,
+    ];
+
+    /// The name the operation is reported under in `NodeStats`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Operation::Put => "put",
+            Operation::Get => "get",
+            Operation::Append => "append",
+            Operation::FindSuccessor => "find_successor",
+        }
+    }
+}
+
+/// A latency histogram per operation, counted since the node started.
+/// Counters are atomic so recording never waits on the state lock.
+#[derive(Debug)]
+pub struct LatencyHistograms {
+    counts: [[AtomicU64; LATENCY_BUCKETS]; Operation::ALL.len()],
+}
+
+impl Default for LatencyHistograms {
+    fn default() -> Self {
+        Self {
+            counts: std::array::from_fn(|_| std::array::from_fn(|_| AtomicU64::new(0))),
+        }
+    }
+}
+
+impl LatencyHistograms {
+    pub fn record(&self, op: Operation, elapsed: Duration) {
+        self.counts[op as usize][bucket_for(elapsed)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The bucket counts of every operation, in the order of `Operation::ALL`.
+    pub fn snapshot(&self) -> Vec<OpLatency> {
+        Operation::ALL
+            .iter()
+            .map(|&op| OpLatency {
+                op: op.name().to_string(),
+                buckets: self.counts[op as usize]
+                    .iter()
+                    .map(|count| count.load(Ordering::Relaxed))
+                    .collect(),
+            })
+            .collect()
+    }
+}
@@ -1,3 +1,19 @@
+pub mod conn_pool;
 pub mod constants;
+pub mod error;
+pub mod idempotency;
+pub mod jitter;
+pub mod lag;
+pub mod latency;
+pub mod liveness;
+pub mod lookup_cache;
+pub mod merkle;
+pub mod monitor_link;
 pub mod node;
-pub use node::Node;
+pub mod ring;
+pub mod store;
+pub mod transport;
+pub use error::{ConfigError, JoinError};
+pub use node::{Node, StoredValue};
+pub use store::{KvStore, LruStore, MemoryStore};
+pub use transport::{InMemoryNetwork, Transport};
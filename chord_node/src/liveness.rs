@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Recent ping results by address, so maintenance and replication running
+/// close together share one ping instead of each sending their own. Both
+/// answers are remembered; any failed RPC to an address forgets it, so the
+/// next check pings again.
+#[derive(Debug)]
+pub struct LivenessCache {
+    ttl: Duration,
+    entries: HashMap<String, (bool, Instant)>,
+}
+
+impl LivenessCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Whether `addr` answered its last ping, if that was within the TTL.
+    pub fn get(&self, addr: &str) -> Option<bool> {
+        self.entries
+            .get(addr)
+            .filter(|(_, checked_at)| checked_at.elapsed() < self.ttl)
+            .map(|(alive, _)| *alive)
+    }
+
+    /// Remembers the outcome of a ping to `addr`, dropping expired entries.
+    pub fn record(&mut self, addr: &str, alive: bool) {
+        let ttl = self.ttl;
+        self.entries
+            .retain(|_, (_, checked_at)| checked_at.elapsed() < ttl);
+        self.entries
+            .insert(addr.to_string(), (alive, Instant::now()));
+    }
+
+    pub fn invalidate(&mut self, addr: &str) {
+        self.entries.remove(addr);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
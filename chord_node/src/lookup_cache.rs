@@ -0,0 +1,97 @@
+use chord_proto::chord::NodeInfo;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::ring::is_in_range_inclusive;
+
+// Ids are grouped into 2^BUCKET_BITS buckets by their top bits
+const BUCKET_BITS: u32 = 16;
+
+/// Short-lived memory of recent lookups. Resolving id `x` to owner `o` means
+/// no node sits in `[x, o]`, so every id in that span has the same owner;
+/// an entry remembers that span for the bucket `x` falls in. Entries expire
+/// after the TTL and are dropped whenever our neighbours change; callers
+/// still confirm an entry with its owner before trusting it.
+#[derive(Debug)]
+pub struct LookupCache {
+    capacity: usize,
+    ttl: Duration,
+    entries: HashMap<u64, CachedOwner>,
+}
+
+#[derive(Debug)]
+struct CachedOwner {
+    from: u64,
+    owner: NodeInfo,
+    cached_at: Instant,
+}
+
+impl LookupCache {
+    /// A capacity of zero disables the cache.
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            entries: HashMap::new(),
+        }
+    }
+
+    fn bucket(id: u64) -> u64 {
+        id >> (64 - BUCKET_BITS)
+    }
+
+    /// The cached owner of `id`, if a fresh entry covers it.
+    pub fn get(&self, id: u64) -> Option<NodeInfo> {
+        let entry = self.entries.get(&Self::bucket(id))?;
+        // [from, owner] is (from - 1, owner]
+        let covers = is_in_range_inclusive(id, entry.from.wrapping_sub(1), entry.owner.id);
+        (covers && entry.cached_at.elapsed() < self.ttl).then(|| entry.owner.clone())
+    }
+
+    /// Remembers that `owner` is the successor of `id`.
+    pub fn insert(&mut self, id: u64, owner: NodeInfo) {
+        if self.capacity == 0 {
+            return;
+        }
+        let bucket = Self::bucket(id);
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&bucket) {
+            let ttl = self.ttl;
+            self.entries.retain(|_, e| e.cached_at.elapsed() < ttl);
+            if self.entries.len() >= self.capacity {
+                let oldest = self
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, e)| e.cached_at)
+                    .map(|(bucket, _)| *bucket);
+                if let Some(oldest) = oldest {
+                    self.entries.remove(&oldest);
+                }
+            }
+        }
+        self.entries.insert(
+            bucket,
+            CachedOwner {
+                from: id,
+                owner,
+                cached_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Forgets every entry pointing at `node_id`, e.g. once it stops answering.
+    pub fn remove_node(&mut self, node_id: u64) {
+        self.entries.retain(|_, e| e.owner.id != node_id);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
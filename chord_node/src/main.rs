@@ -1,16 +1,20 @@
 use chord_proto::chord::chord_server::ChordServer;
 use clap::Parser;
 
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 use tokio::time::{sleep, Duration};
 use tonic::transport::Server;
 
 use chord_node::constants::{
-    CHECK_PREDECESSOR_INTERVAL_MS, DEFAULT_PORT, FIX_FINGERS_INTERVAL_MS, LOCALHOST,
-    MAINTAIN_REPLICATION_INTERVAL_MS, STABILIZATION_INTERVAL_MS,
+    CHECK_PREDECESSOR_INTERVAL_MS, DEFAULT_PORT, FIX_FINGERS_INTERVAL_MS, ID_COLLISION_RETRIES,
+    LEAVE_TRANSFER_ATTEMPTS, LEAVE_TRANSFER_BACKOFF_MS, LOCALHOST, LOOKUP_CACHE_SIZE,
+    LOOKUP_CACHE_TTL_MS, MAINTAIN_REPLICATION_INTERVAL_MS, MAINTENANCE_JITTER_PERCENT,
+    MAX_CONCURRENT_REPLICATIONS, MONITOR_REPORT_HEARTBEAT_MS, REPLICATION_COUNT,
+    STABILIZATION_INTERVAL_MS, SUCCESSOR_LIST_LIMIT,
 };
-use chord_node::Node;
+use chord_node::jitter::Jitter;
+use chord_node::{JoinError, LruStore, Node};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -19,16 +23,142 @@ struct Args {
     #[arg(short, long, default_value_t = DEFAULT_PORT)]
     port: u16,
 
-    /// Address of a node to join
+    /// Local address to listen on (e.g. 0.0.0.0 for every interface)
+    #[arg(long, default_value = LOCALHOST)]
+    bind: IpAddr,
+
+    /// Host other nodes should use to reach this one, on the same port: an
+    /// IPv4 or IPv6 address or a DNS name. It is what the ring stores and
+    /// what the node id is hashed from, so set it to the public address when
+    /// listening behind NAT or in a container.
+    #[arg(long, default_value = LOCALHOST)]
+    advertise: String,
+
+    /// Start a new ring with this node as its only member
+    #[arg(long, conflicts_with = "join", required_unless_present = "join")]
+    create: bool,
+
+    /// Address of a node to join. If the join fails the node exits rather
+    /// than starting an isolated ring of its own.
     #[arg(short, long)]
     join: Option<String>,
 
     /// Monitor address
     #[arg(short, long)]
     monitor: Option<String>,
+
+    /// Re-send an unchanged state to the monitor at most this often (ms).
+    /// Changes are still reported on the next maintenance round.
+    #[arg(long, default_value_t = MONITOR_REPORT_HEARTBEAT_MS)]
+    report_interval_ms: u64,
+
+    /// Fixed node id. By default the id is the hash of the listen address, so
+    /// a node restarted on another port lands elsewhere on the ring and no
+    /// longer owns the keys in its persisted store. Keep the id to keep them.
+    /// A hashed id that is already taken is rehashed with a salt; a fixed one
+    /// fails the join instead.
+    #[arg(long, conflicts_with = "id_seed")]
+    node_id: Option<u64>,
+
+    /// Derive the node id from this string instead of the listen address
+    #[arg(long)]
+    id_seed: Option<String>,
+
+    /// Number of successors to track; must cover the replication count
+    #[arg(long, default_value_t = SUCCESSOR_LIST_LIMIT)]
+    successor_list_len: usize,
+
+    /// Successors that get a copy of each key by default; 0 turns replication
+    /// off, so a key is lost when its owner fails
+    #[arg(long, default_value_t = REPLICATION_COUNT)]
+    replication_count: usize,
+
+    /// Randomly vary each maintenance interval by up to this percentage, so
+    /// nodes started together don't run maintenance in lockstep
+    #[arg(long, default_value_t = MAINTENANCE_JITTER_PERCENT, value_parser = clap::value_parser!(u64).range(0..=100))]
+    jitter_percent: u64,
+
+    /// Cap the store at this many keys, evicting the least recently used
+    /// keys this node is primary for first
+    #[arg(long)]
+    max_keys: Option<usize>,
+
+    /// Cap the store at this many bytes of keys and values, evicting like
+    /// --max-keys
+    #[arg(long)]
+    max_bytes: Option<usize>,
+
+    /// Report the node to the monitor as overloaded once it stores more than
+    /// this many keys. Nothing is evicted; it's a hint to add nodes.
+    #[arg(long)]
+    max_keys_soft: Option<usize>,
+
+    /// How many recently resolved lookups to remember (0 disables the cache)
+    #[arg(long, default_value_t = LOOKUP_CACHE_SIZE)]
+    lookup_cache_size: usize,
+
+    /// How long a remembered lookup is trusted (ms)
+    #[arg(long, default_value_t = LOOKUP_CACHE_TTL_MS)]
+    lookup_cache_ttl_ms: u64,
+
+    /// How many replicas may be in flight at once; the rest queue
+    #[arg(long, default_value_t = MAX_CONCURRENT_REPLICATIONS)]
+    replication_concurrency: usize,
+
+    /// How many times to try reaching each successor when leaving before
+    /// handing the keys to the next one
+    #[arg(long, default_value_t = LEAVE_TRANSFER_ATTEMPTS)]
+    leave_attempts: u32,
+
+    /// Wait before the second try to reach a successor when leaving, doubled
+    /// for each try after it (ms)
+    #[arg(long, default_value_t = LEAVE_TRANSFER_BACKOFF_MS)]
+    leave_backoff_ms: u64,
+
+    /// Route lookups and run maintenance, but never store keys. The ids this
+    /// node would own belong to the next node after it that stores keys, so
+    /// that node's owned range reaches back past it.
+    #[arg(long)]
+    observer: bool,
 }
 
-use chord_proto::hash_addr;
+use chord_proto::addr::host_port;
+use chord_proto::NodeIdSource;
+
+/// Builds the node with id `id` as configured, exiting on a configuration
+/// that can't work.
+fn build_node(args: &Args, id: u64, addr_str: &str) -> Arc<Node> {
+    let mut node =
+        match Node::with_successor_list_len(id, addr_str.to_string(), args.successor_list_len)
+            .and_then(|node| node.with_replication_count(args.replication_count))
+        {
+            Ok(node) => node,
+            Err(e) => {
+                eprintln!("Invalid configuration: {}", e);
+                std::process::exit(1);
+            }
+        };
+    if args.max_keys.is_some() || args.max_bytes.is_some() {
+        node = node.with_store(Box::new(LruStore::new(args.max_keys, args.max_bytes)));
+    }
+    if let Some(limit) = args.max_keys_soft {
+        node = node.with_max_keys_soft(limit);
+    }
+    if args.observer {
+        node = node.as_observer();
+    }
+    Arc::new(
+        node.with_lookup_cache(
+            args.lookup_cache_size,
+            Duration::from_millis(args.lookup_cache_ttl_ms),
+        )
+        .with_replication_concurrency(args.replication_concurrency)
+        .with_leave_retries(
+            args.leave_attempts,
+            Duration::from_millis(args.leave_backoff_ms),
+        ),
+    )
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -38,42 +168,77 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     env_logger::init();
     let args = Args::parse();
 
-    let addr_str = format!("{}:{}", LOCALHOST, args.port);
-    let addr: SocketAddr = addr_str.parse()?;
-    let id = hash_addr(&addr_str);
+    let addr = SocketAddr::new(args.bind, args.port);
+    let addr_str = host_port(&args.advertise, args.port);
+    let id_source = match (args.node_id, &args.id_seed) {
+        (Some(id), _) => NodeIdSource::Fixed(id),
+        (None, Some(seed)) => NodeIdSource::Hashed(seed.clone()),
+        (None, None) => NodeIdSource::Hashed(addr_str.clone()),
+    };
+    let mut id = id_source.id(0);
 
+    let mut node = build_node(&args, id, &addr_str);
     println!("Node starting at {} with ID {}", addr_str, id);
 
-    let node = Node::new(id, addr_str.clone());
-    let node = Arc::new(node);
-
-    // Join if requested
-    if let Some(join_addr) = args.join {
+    // Settle into a ring before serving or running maintenance, so a node
+    // that can't join never answers as a ring of its own
+    if let Some(join_addr) = args.join.clone() {
         println!("Joining ring via {}", join_addr);
-        node.join(join_addr).await?;
+        let mut salt = 0;
+        loop {
+            match node.join(join_addr.clone()).await {
+                Ok(()) => break,
+                Err(JoinError::IdCollision { address, .. })
+                    if !id_source.is_fixed() && salt < ID_COLLISION_RETRIES =>
+                {
+                    salt += 1;
+                    id = id_source.id(salt);
+                    println!("ID collides with node {}, retrying with ID {}", address, id);
+                    node = build_node(&args, id, &addr_str);
+                }
+                Err(e) => {
+                    eprintln!("Failed to join: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
         println!("Joined successfully");
+    } else {
+        node.create().await?;
+        println!("Created a new ring");
     }
 
     // Background tasks
     let node_clone = node.clone();
-    let monitor_addr = args.monitor.clone();
+    let mut jitter = Jitter::new(id, args.jitter_percent);
     tokio::spawn(async move {
         loop {
-            sleep(Duration::from_millis(STABILIZATION_INTERVAL_MS)).await;
+            sleep(jitter.apply(STABILIZATION_INTERVAL_MS)).await;
             node_clone.stabilize().await;
-            sleep(Duration::from_millis(FIX_FINGERS_INTERVAL_MS)).await;
+            sleep(jitter.apply(FIX_FINGERS_INTERVAL_MS)).await;
             node_clone.fix_fingers().await;
-            sleep(Duration::from_millis(CHECK_PREDECESSOR_INTERVAL_MS)).await;
+            sleep(jitter.apply(CHECK_PREDECESSOR_INTERVAL_MS)).await;
             node_clone.check_predecessor().await;
-            sleep(Duration::from_millis(MAINTAIN_REPLICATION_INTERVAL_MS)).await;
+            sleep(jitter.apply(MAINTAIN_REPLICATION_INTERVAL_MS)).await;
             node_clone.maintain_replication().await;
-
-            if let Some(ref m_addr) = monitor_addr {
-                node_clone.report_to_monitor(m_addr.clone()).await;
-            }
         }
     });
 
+    // Reporting runs on its own, so a slow or dead monitor never holds up
+    // maintenance
+    if let Some(monitor_addr) = args.monitor.clone() {
+        let node_clone = node.clone();
+        let report_interval = Duration::from_millis(args.report_interval_ms);
+        tokio::spawn(async move {
+            loop {
+                sleep(Duration::from_millis(STABILIZATION_INTERVAL_MS)).await;
+                node_clone
+                    .report_to_monitor(monitor_addr.clone(), report_interval)
+                    .await;
+            }
+        });
+    }
+
     println!("Server listening on {}", addr);
 
     Server::builder()
@@ -0,0 +1,102 @@
+use sha1::{Digest, Sha1};
+
+pub type Hash = [u8; 20];
+
+/// Fixed-shape hash tree over the `(key, version)` pairs of an id interval.
+/// The interval is cut into `2^depth` equal buckets; each leaf hashes the
+/// entries of one bucket and every inner node hashes its two children. Two
+/// nodes holding the same versions of the same keys get the same root, and
+/// differing leaves pinpoint the buckets that need to be resent.
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    start: u64,
+    end: u64,
+    leaves: Vec<Hash>,
+}
+
+impl MerkleTree {
+    /// Builds the tree for the interval `(start, end]` (the whole ring when
+    /// `start == end`). Entries are `(key id, key, updated_at)`; the caller
+    /// is responsible for only passing keys inside the interval.
+    pub fn build<'a>(
+        start: u64,
+        end: u64,
+        depth: u32,
+        entries: impl IntoIterator<Item = (u64, &'a str, u64)>,
+    ) -> Self {
+        let bucket_count = 1usize << depth;
+        let mut buckets: Vec<Vec<(&str, u64)>> = vec![Vec::new(); bucket_count];
+        for (key_id, key, updated_at) in entries {
+            buckets[Self::bucket_of(start, end, bucket_count, key_id)].push((key, updated_at));
+        }
+
+        let leaves = buckets
+            .into_iter()
+            .map(|mut bucket| {
+                // Hash in a canonical order so store iteration order doesn't matter
+                bucket.sort_unstable();
+                let mut hasher = Sha1::new();
+                for (key, updated_at) in bucket {
+                    hasher.update((key.len() as u64).to_be_bytes());
+                    hasher.update(key.as_bytes());
+                    hasher.update(updated_at.to_be_bytes());
+                }
+                hasher.finalize().into()
+            })
+            .collect();
+
+        Self { start, end, leaves }
+    }
+
+    pub fn leaves(&self) -> &[Hash] {
+        &self.leaves
+    }
+
+    pub fn root(&self) -> Hash {
+        let mut level = self.leaves.clone();
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .map(|pair| {
+                    let mut hasher = Sha1::new();
+                    for child in pair {
+                        hasher.update(child);
+                    }
+                    hasher.finalize().into()
+                })
+                .collect();
+        }
+        level.first().copied().unwrap_or_default()
+    }
+
+    /// The bucket a key id falls into.
+    pub fn bucket(&self, key_id: u64) -> usize {
+        Self::bucket_of(self.start, self.end, self.leaves.len(), key_id)
+    }
+
+    /// Indices of the buckets whose leaves differ from `other`. A leaf list
+    /// of the wrong shape counts as every bucket differing.
+    pub fn diff(&self, other: &[Vec<u8>]) -> Vec<usize> {
+        if other.len() != self.leaves.len() {
+            return (0..self.leaves.len()).collect();
+        }
+        self.leaves
+            .iter()
+            .zip(other)
+            .enumerate()
+            .filter(|(_, (ours, theirs))| ours.as_slice() != theirs.as_slice())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn bucket_of(start: u64, end: u64, bucket_count: usize, key_id: u64) -> usize {
+        // Position within the interval, scaled to the bucket count. A zero
+        // width means the interval spans the whole ring.
+        let offset = key_id.wrapping_sub(start).wrapping_sub(1) as u128;
+        let width = match end.wrapping_sub(start) {
+            0 => 1u128 << 64,
+            width => width as u128,
+        };
+        ((offset * bucket_count as u128) / width).min(bucket_count as u128 - 1) as usize
+    }
+}
@@ -0,0 +1,68 @@
+use crate::constants::{MONITOR_RETRY_BASE_MS, MONITOR_RETRY_MAX_MS, MONITOR_TIMEOUT_MS};
+use chord_proto::addr::node_url;
+use chord_proto::chord::chord_monitor_client::ChordMonitorClient;
+use std::time::{Duration, Instant};
+use tonic::transport::{Channel, Endpoint};
+
+/// The connection a node reports to the monitor over. The channel is opened
+/// lazily and kept between reports. After a failed report, reports are
+/// skipped with exponential backoff, so a monitor that is down costs an
+/// occasional attempt rather than a dial on every round.
+#[derive(Debug, Default)]
+pub struct MonitorLink {
+    client: Option<(String, ChordMonitorClient<Channel>)>,
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl MonitorLink {
+    /// A client for the monitor at `addr`, or None while backing off.
+    pub fn client(&mut self, addr: &str) -> Option<ChordMonitorClient<Channel>> {
+        if self.retry_at.is_some_and(|at| Instant::now() < at) {
+            return None;
+        }
+        if let Some((cached, client)) = &self.client {
+            if cached == addr {
+                return Some(client.clone());
+            }
+        }
+        let timeout = Duration::from_millis(MONITOR_TIMEOUT_MS);
+        let channel = match Endpoint::from_shared(node_url(addr)) {
+            Ok(endpoint) => endpoint
+                .connect_timeout(timeout)
+                .timeout(timeout)
+                .connect_lazy(),
+            Err(_) => {
+                self.failed();
+                return None;
+            }
+        };
+        let client = ChordMonitorClient::new(channel);
+        self.client = Some((addr.to_string(), client.clone()));
+        Some(client)
+    }
+
+    pub fn succeeded(&mut self) {
+        self.failures = 0;
+        self.retry_at = None;
+    }
+
+    /// Backs off before the next attempt, doubling with each failure in a
+    /// row. The channel is dropped so the next attempt dials afresh.
+    pub fn failed(&mut self) -> Duration {
+        let backoff = Duration::from_millis(
+            MONITOR_RETRY_BASE_MS
+                .saturating_mul(1 << self.failures.min(16))
+                .min(MONITOR_RETRY_MAX_MS),
+        );
+        self.failures += 1;
+        self.retry_at = Some(Instant::now() + backoff);
+        self.client = None;
+        backoff
+    }
+
+    /// Reports that failed in a row.
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+}
@@ -1,40 +1,467 @@
+use chord_proto::addr::{node_url, normalize_addr};
 use chord_proto::chord::{
-    chord_server::Chord, Empty, FindSuccessorRequest, GetRequest, GetResponse, NodeInfo,
-    NodeState as ProtoNodeState, PutRequest, PutResponse, SuccessorList, TransferKeysRequest,
+    chord_server::Chord, AppendRequest, ChangeEvent, ChangeOp, ClusterHealthRequest,
+    ClusterHealthResponse, DeleteNamespaceRequest, DeleteRangeRequest, DeleteRangeResponse,
+    DistributionResponse, DrainResponse, Empty, FindPredecessorRequest, FindSuccessorRequest,
+    GetRequest, GetResponse, Handshake, HealthResponse, HealthState, IdRange, ImportResponse,
+    KeyTransferChunk, KeyValue, LocalDeletePrefixRequest, LocalDeleteRangeRequest, Metadata,
+    NodeHealth, NodeInfo, NodeLoad, NodeState as ProtoNodeState, NodeStats, PutRequest,
+    PutResponse, RebalanceResponse, ReplicaLocations, ReplicaVersion, ScanPrefixRequest,
+    ScanPrefixResponse, SelfCheckResponse, SuccessorList, SyncDigestRequest, SyncDigestResponse,
+    TracedLookupRequest, TracedLookupResponse, TransferKeysRequest, TransferKeysResponse,
+    ValueChunk, ValueList, Violation, ViolationKind,
+};
+use chord_proto::chunk::{key_value_chunks, KeyValueAssembler};
+use chord_proto::{
+    distribution, hash_addr, namespaced_key, CHUNKED_EXPORT_PROTOCOL_VERSION, MAX_METADATA_BYTES,
+    METADATA_PROTOCOL_VERSION, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION,
+    STREAMING_TRANSFER_PROTOCOL_VERSION,
 };
-use chord_proto::hash_addr;
 use log::{debug, error, info, warn};
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tonic::{Request, Response, Status};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::{broadcast, mpsc, RwLock, Semaphore, SemaphorePermit};
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::{BroadcastStream, ReceiverStream};
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status, Streaming};
 
+use crate::conn_pool::ConnectionPool;
 use crate::constants::{
-    FINGER_TABLE_SIZE, LEAVE_EXIT_DELAY_MS, REPLICATION_COUNT, SUCCESSOR_LIST_LIMIT,
+    ANTI_ENTROPY_FULL_PUSH_FRACTION, CHANGE_EVENTS_CAPACITY, CLUSTER_HEALTH_MAX_NODES,
+    EXPORT_BUFFER_LEN, FIND_SUCCESSOR_RETRY_LIMIT, FINGER_TABLE_SIZE,
+    FIX_FINGERS_RANDOM_PICK_PROBABILITY, FORWARD_QUEUE_TIMEOUT_MS, HANDOFF_RETRY_INTERVAL_MS,
+    HANDOFF_TIMEOUT_MS, IDEMPOTENCY_CACHE_SIZE, IDEMPOTENCY_WINDOW_MS, IMPORT_BATCH_BYTES,
+    IMPORT_BATCH_KEYS, LEAVE_EXIT_DELAY_MS, LEAVE_TRANSFER_ATTEMPTS, LEAVE_TRANSFER_BACKOFF_MS,
+    LIST_METADATA_KEY, LIVENESS_CACHE_TTL_MS, LOOKUP_CACHE_SIZE, LOOKUP_CACHE_TTL_MS,
+    MAX_CONCURRENT_FORWARDS, MAX_CONCURRENT_REPLICATIONS, MAX_KEY_BYTES, MAX_LOOKUP_HOPS,
+    MAX_VALUE_BYTES, MERKLE_TREE_DEPTH, READ_REPAIR_ENABLED, REDIRECT_METADATA_KEY,
+    REPLICATION_COUNT, REPLICATION_LAG_WINDOW, SLOW_RPC_THRESHOLD_MS, SUCCESSOR_LIST_LIMIT,
+    TRANSFER_BATCH_BYTES, VALUE_CHUNK_SIZE,
 };
+use crate::error::{ConfigError, JoinError};
+use crate::idempotency::RecentRequests;
+use crate::lag::LagWindow;
+use crate::latency::{LatencyHistograms, Operation};
+use crate::liveness::LivenessCache;
+use crate::lookup_cache::LookupCache;
+use crate::merkle::MerkleTree;
+use crate::monitor_link::MonitorLink;
+use crate::ring::{is_in_range, is_in_range_inclusive, ID_SPACE};
+use crate::store::{KvStore, MemoryStore};
+use crate::transport::Transport;
 
 #[derive(Debug, Clone)]
 pub struct Node {
     pub id: u64,
     pub addr: String,
     pub state: Arc<RwLock<NodeState>>,
+    pub started_at: Instant,
+    successor_list_len: usize,
+    replication_count: usize,
+    forward_permits: Arc<Semaphore>,
+    /// Forwarding slots for requests other nodes forwarded to us
+    hop_permits: Arc<Semaphore>,
+    forwards_rejected: Arc<AtomicU64>,
+    replication_permits: Arc<Semaphore>,
+    connections: Arc<ConnectionPool>,
+    replication_lag: Arc<std::sync::Mutex<LagWindow>>,
+    latencies: Arc<LatencyHistograms>,
+    liveness: Arc<std::sync::Mutex<LivenessCache>>,
+    monitor: Arc<std::sync::Mutex<MonitorLink>>,
+    transport: Transport,
+    changes: broadcast::Sender<ChangeEvent>,
+    leave_attempts: u32,
+    leave_backoff: Duration,
+    observer: bool,
+    max_keys_soft: Option<usize>,
 }
 
 #[derive(Debug)]
 pub struct NodeState {
     pub predecessor: Option<NodeInfo>,
     pub finger_table: Vec<NodeInfo>,
+    /// When fix_fingers last refreshed each finger; None until the first time
+    pub finger_last_fixed: Vec<Option<Instant>>,
     pub successor_list: Vec<NodeInfo>,
-    pub store: HashMap<String, String>,
+    /// The last successor list that had other nodes in it, leaving out
+    /// nodes that said they were departing. Empty for a node that has always
+    /// been alone, or whose successors all left on purpose.
+    pub last_known_successors: Vec<NodeInfo>,
+    /// Nodes that told us they left or drained, until they say hello again
+    pub departed: HashSet<u64>,
+    pub store: Box<dyn KvStore>,
+    pub applied_requests: RecentRequests,
+    pub leaving: bool,
+    /// Set by Drain: writes for our keys are refused while they are handed off
+    pub draining: bool,
+    /// Drain finished and the successors confirmed they hold our keys
+    pub drained: bool,
+    /// Fingerprint of the last snapshot sent to the monitor and when it was sent
+    pub last_report: Option<(u64, Instant)>,
+    /// Recently resolved owners, cleared whenever our neighbours change
+    pub lookup_cache: LookupCache,
+    /// Protocol version each peer reported in Hello, by node id
+    pub peer_versions: HashMap<u64, u32>,
+    /// While our predecessor is an observer, where the range it would have
+    /// owned starts. We own that range too; None until the observer told us.
+    pub observer_range_start: Option<u64>,
+}
+
+impl NodeState {
+    /// Where the ids we own start: at our predecessor, or, when that is an
+    /// observer, where its range starts, since we store its keys as well.
+    /// `self_id` (the whole ring) without a predecessor.
+    pub fn owned_start(&self, self_id: u64) -> u64 {
+        match &self.predecessor {
+            Some(pred) if pred.observer => self.observer_range_start.unwrap_or(pred.id),
+            Some(pred) => pred.id,
+            None => self_id,
+        }
+    }
+
+    /// The successors that can hold our keys: the successor list without us
+    /// and without observers. Replicas and handoffs only go to these.
+    pub fn storage_successors(&self, self_id: u64) -> Vec<NodeInfo> {
+        self.successor_list
+            .iter()
+            .filter(|s| s.id != self_id && !s.observer)
+            .cloned()
+            .collect()
+    }
+
+    /// The finger table with repeated nodes collapsed, farthest finger first.
+    /// Most slots point at the same few nodes, so routing only needs these.
+    pub fn distinct_fingers(&self) -> Vec<NodeInfo> {
+        let mut seen = HashSet::new();
+        self.finger_table
+            .iter()
+            .rev()
+            .filter(|finger| !finger.address.is_empty() && seen.insert(finger.id))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Routing state captured for a single lookup, see [`Node::route_snapshot`].
+struct RouteSnapshot {
+    successor: NodeInfo,
+    /// The first hop to try, see [`Node::closest_preceding_node`]
+    closest: Option<NodeInfo>,
+    successor_list: Vec<NodeInfo>,
+}
+
+/// A stored value along with the time (ms since the UNIX epoch) it was last written,
+/// how many successors it should be replicated to, and the metadata written with it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredValue {
+    pub value: Vec<u8>,
+    pub updated_at: u64,
+    pub replication_factor: usize,
+    pub metadata: HashMap<String, String>,
+}
+
+impl StoredValue {
+    pub fn new(value: Vec<u8>) -> Self {
+        Self {
+            value,
+            updated_at: now_millis(),
+            replication_factor: REPLICATION_COUNT,
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn to_put_request(&self, key: String) -> PutRequest {
+        PutRequest {
+            key,
+            value: self.value.clone(),
+            updated_at: self.updated_at,
+            replication_factor: self.replication_factor as u32,
+            metadata: self.metadata.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// The items of a value built with Append, or None for a plain value.
+    pub fn list_items(&self) -> Option<Result<Vec<Vec<u8>>, prost::DecodeError>> {
+        use prost::Message;
+        self.metadata
+            .contains_key(LIST_METADATA_KEY)
+            .then(|| ValueList::decode(self.value.as_slice()).map(|list| list.items))
+    }
+
+    fn to_key_value(&self, key: String) -> KeyValue {
+        KeyValue {
+            key,
+            value: self.value.clone(),
+            updated_at: self.updated_at,
+            replication_factor: self.replication_factor as u32,
+            metadata: self.metadata.clone(),
+        }
+    }
+}
+
+/// Which keys a node gives up when it learns of a new predecessor.
+#[derive(Debug, Clone, Copy)]
+enum Handover {
+    /// Keys in `(start, new_pred]` move to the new predecessor
+    Move { start: u64 },
+    /// Keys outside `(start, self]` that the new predecessor owns are
+    /// copied, and kept here too
+    CopyOutside { start: u64 },
+}
+
+/// Keys we hold as primary that the ring routes elsewhere, by owner id,
+/// and the keys whose owner lookup failed.
+#[derive(Debug, Default)]
+struct MisplacedKeys {
+    by_owner: HashMap<u64, (NodeInfo, Vec<String>)>,
+    unresolved: Vec<(String, Status)>,
+}
+
+/// Imported keys waiting to be handed to one owner.
+struct ImportBatch {
+    owner: NodeInfo,
+    keys: HashMap<String, StoredValue>,
+    bytes: usize,
+}
+
+impl ImportBatch {
+    fn new(owner: NodeInfo) -> Self {
+        Self {
+            owner,
+            keys: HashMap::new(),
+            bytes: 0,
+        }
+    }
+
+    fn push(&mut self, key: String, entry: StoredValue) {
+        self.bytes += key.len() + entry.value.len();
+        self.keys.insert(key, entry);
+    }
+
+    fn is_full(&self) -> bool {
+        self.keys.len() >= IMPORT_BATCH_KEYS || self.bytes >= IMPORT_BATCH_BYTES
+    }
+}
+
+/// Resolves a requested replication factor: 0 means the node's `default`
+/// (which may itself be 0), and we can't replicate to more successors than
+/// we track.
+pub fn replication_factor(requested: u32, default: usize, successor_list_len: usize) -> usize {
+    if requested == 0 {
+        default
+    } else {
+        (requested as usize).min(successor_list_len)
+    }
+}
+
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Builds a `WatchChanges` event; deletes carry no value and are versioned
+/// by the time they happened.
+fn change_event(op: ChangeOp, key: &str, entry: Option<&StoredValue>) -> ChangeEvent {
+    ChangeEvent {
+        op: op as i32,
+        key: key.to_string(),
+        value: entry.map(|e| e.value.clone()).unwrap_or_default(),
+        version: entry.map(|e| e.updated_at).unwrap_or_else(now_millis),
+    }
+}
+
+/// Replaces a request's key with its stored key, clearing the namespace.
+/// Returns the namespace and key as the client sent them, which is what a
+/// forwarded request carries: the next hop applies the namespace itself.
+fn resolve_namespace(namespace: &mut String, key: &mut String) -> Result<(String, String), String> {
+    let stored = namespaced_key(namespace, key)?;
+    Ok((std::mem::take(namespace), std::mem::replace(key, stored)))
+}
+
+/// Checks a write against the size limits, describing the violation if any.
+pub fn validate_put(req: &PutRequest) -> Result<(), String> {
+    if req.key.len() > MAX_KEY_BYTES {
+        return Err(format!(
+            "Key is {} bytes, the limit is {}",
+            req.key.len(),
+            MAX_KEY_BYTES
+        ));
+    }
+    if req.value.len() > MAX_VALUE_BYTES {
+        return Err(format!(
+            "Value is {} bytes, the limit is {}",
+            req.value.len(),
+            MAX_VALUE_BYTES
+        ));
+    }
+    let metadata_bytes: usize = req.metadata.iter().map(|(k, v)| k.len() + v.len()).sum();
+    if metadata_bytes > MAX_METADATA_BYTES {
+        return Err(format!(
+            "Metadata is {} bytes, the limit is {}",
+            metadata_bytes, MAX_METADATA_BYTES
+        ));
+    }
+    Ok(())
+}
+
+/// The keys of a transfer that the receiver confirmed storing. A response
+/// whose counts don't add up to what was sent (e.g. from a receiver that
+/// doesn't report them) confirms nothing.
+fn confirmed_keys(sent: Vec<String>, response: &TransferKeysResponse) -> Vec<String> {
+    if response.accepted_count as usize + response.rejected.len() != sent.len() {
+        return Vec::new();
+    }
+    let rejected: HashSet<&String> = response.rejected.iter().collect();
+    sent.into_iter().filter(|k| !rejected.contains(k)).collect()
+}
+
+/// Splits a put into chunks of at most `VALUE_CHUNK_SIZE` bytes.
+pub fn value_chunks(req: PutRequest) -> Vec<ValueChunk> {
+    let mut chunks: Vec<ValueChunk> = req
+        .value
+        .chunks(VALUE_CHUNK_SIZE)
+        .map(|data| ValueChunk {
+            data: data.to_vec(),
+            ..Default::default()
+        })
+        .collect();
+    if chunks.is_empty() {
+        chunks.push(ValueChunk::default());
+    }
+    chunks[0].key = req.key;
+    chunks[0].updated_at = req.updated_at;
+    chunks[0].replication_factor = req.replication_factor;
+    chunks[0].metadata = req.metadata;
+    chunks[0].visited = req.visited;
+    chunks[0].namespace = req.namespace;
+    chunks[0].request_id = req.request_id;
+    chunks[0].found = true;
+    chunks
+}
+
+/// Reassembles a chunked put. The key and timestamp come from the first chunk.
+async fn collect_chunks(mut stream: Streaming<ValueChunk>) -> Result<PutRequest, Status> {
+    let first = stream
+        .message()
+        .await?
+        .ok_or_else(|| Status::invalid_argument("Empty value stream"))?;
+    let mut value = first.data;
+    while let Some(chunk) = stream.message().await? {
+        // Stop reading as soon as the value is too large rather than buffering it all
+        if value.len() + chunk.data.len() > MAX_VALUE_BYTES {
+            return Err(Status::invalid_argument(format!(
+                "Value exceeds the {} byte limit",
+                MAX_VALUE_BYTES
+            )));
+        }
+        value.extend_from_slice(&chunk.data);
+    }
+    Ok(PutRequest {
+        key: first.key,
+        value,
+        updated_at: first.updated_at,
+        replication_factor: first.replication_factor,
+        metadata: first.metadata,
+        visited: first.visited,
+        namespace: first.namespace,
+        request_id: first.request_id,
+    })
+}
+
+/// A key transfer as a stream: `request_ids` first, then each key split
+/// into chunks as by `value_chunks`. Chunks are only built as the stream
+/// is read, so the values aren't copied all at once.
+fn transfer_stream(
+    entries: HashMap<String, StoredValue>,
+    request_ids: Vec<String>,
+) -> impl Stream<Item = KeyTransferChunk> + Send + 'static {
+    let ids = (!request_ids.is_empty()).then_some(KeyTransferChunk {
+        chunk: None,
+        request_ids,
+    });
+    let chunks = entries
+        .into_iter()
+        .flat_map(|(key, entry)| value_chunks(entry.to_put_request(key)))
+        .map(|chunk| KeyTransferChunk {
+            chunk: Some(chunk),
+            request_ids: Vec::new(),
+        });
+    tokio_stream::iter(ids.into_iter().chain(chunks))
+}
+
+/// A key transfer in a single message, for peers from before streamed
+/// transfers. Values over the gRPC message limit can't be sent this way.
+fn transfer_keys_request(
+    entries: HashMap<String, StoredValue>,
+    request_ids: Vec<String>,
+) -> TransferKeysRequest {
+    let mut keys = HashMap::with_capacity(entries.len());
+    let mut updated_at = HashMap::with_capacity(entries.len());
+    let mut replication_factor = HashMap::with_capacity(entries.len());
+    let mut metadata = HashMap::new();
+    for (k, entry) in entries {
+        updated_at.insert(k.clone(), entry.updated_at);
+        replication_factor.insert(k.clone(), entry.replication_factor as u32);
+        if !entry.metadata.is_empty() {
+            metadata.insert(
+                k.clone(),
+                Metadata {
+                    entries: entry.metadata,
+                },
+            );
+        }
+        keys.insert(k, entry.value);
+    }
+    TransferKeysRequest {
+        keys,
+        updated_at,
+        replication_factor,
+        metadata,
+        request_ids,
+    }
 }
 
 impl Node {
+    /// A node reachable at `addr`, kept in its normalized form so peers
+    /// compare and dial it consistently.
     pub fn new(id: u64, addr: String) -> Self {
+        Self::build(id, addr, SUCCESSOR_LIST_LIMIT)
+    }
+
+    /// A node that tracks `successor_list_len` successors instead of the
+    /// default. It has to be able to reach every default replica;
+    /// `with_replication_count` checks a different count against it.
+    pub fn with_successor_list_len(
+        id: u64,
+        addr: String,
+        successor_list_len: usize,
+    ) -> Result<Self, ConfigError> {
+        if successor_list_len == 0 || successor_list_len < REPLICATION_COUNT {
+            return Err(ConfigError::SuccessorListTooShort {
+                successor_list_len,
+                replication_count: REPLICATION_COUNT,
+            });
+        }
+        Ok(Self::build(id, addr, successor_list_len))
+    }
+
+    fn build(id: u64, addr: String, successor_list_len: usize) -> Self {
+        let addr = normalize_addr(&addr);
         let mut finger_table = Vec::with_capacity(FINGER_TABLE_SIZE);
         // Initially finger table points to self
         let self_info = NodeInfo {
             id,
             address: addr.clone(),
+            observer: false,
         };
         for _ in 0..FINGER_TABLE_SIZE {
             finger_table.push(self_info.clone());
@@ -46,411 +473,3519 @@ impl Node {
             state: Arc::new(RwLock::new(NodeState {
                 predecessor: None,
                 finger_table,
+                finger_last_fixed: vec![None; FINGER_TABLE_SIZE],
                 successor_list: vec![self_info], // Successor list initially contains self
-                store: HashMap::new(),
+                last_known_successors: Vec::new(),
+                departed: HashSet::new(),
+                store: Box::new(MemoryStore::new()),
+                applied_requests: RecentRequests::new(
+                    IDEMPOTENCY_CACHE_SIZE,
+                    Duration::from_millis(IDEMPOTENCY_WINDOW_MS),
+                ),
+                leaving: false,
+                draining: false,
+                drained: false,
+                last_report: None,
+                lookup_cache: LookupCache::new(
+                    LOOKUP_CACHE_SIZE,
+                    Duration::from_millis(LOOKUP_CACHE_TTL_MS),
+                ),
+                peer_versions: HashMap::new(),
+                observer_range_start: None,
             })),
+            started_at: Instant::now(),
+            successor_list_len,
+            forward_permits: Arc::new(Semaphore::new(MAX_CONCURRENT_FORWARDS)),
+            hop_permits: Arc::new(Semaphore::new(MAX_CONCURRENT_FORWARDS)),
+            forwards_rejected: Arc::default(),
+            replication_permits: Arc::new(Semaphore::new(MAX_CONCURRENT_REPLICATIONS)),
+            connections: Arc::new(ConnectionPool::new(Transport::Tcp)),
+            replication_lag: Arc::new(std::sync::Mutex::new(LagWindow::new(
+                REPLICATION_LAG_WINDOW,
+            ))),
+            latencies: Arc::default(),
+            liveness: Arc::new(std::sync::Mutex::new(LivenessCache::new(
+                Duration::from_millis(LIVENESS_CACHE_TTL_MS),
+            ))),
+            monitor: Arc::default(),
+            transport: Transport::Tcp,
+            changes: broadcast::channel(CHANGE_EVENTS_CAPACITY).0,
+            leave_attempts: LEAVE_TRANSFER_ATTEMPTS,
+            leave_backoff: Duration::from_millis(LEAVE_TRANSFER_BACKOFF_MS),
+            observer: false,
+            max_keys_soft: None,
+            replication_count: REPLICATION_COUNT,
+        }
+    }
+
+    /// Replaces the default in-memory store. Only possible before the node
+    /// is shared (e.g. served or cloned).
+    pub fn with_store(mut self, store: Box<dyn KvStore>) -> Self {
+        Arc::get_mut(&mut self.state)
+            .expect("store replaced after the node was shared")
+            .get_mut()
+            .store = store;
+        self
+    }
+
+    /// Resizes the lookup cache (0 turns it off) and sets how long entries
+    /// are trusted. Like `with_store`, only before the node is shared.
+    pub fn with_lookup_cache(mut self, capacity: usize, ttl: Duration) -> Self {
+        Arc::get_mut(&mut self.state)
+            .expect("lookup cache replaced after the node was shared")
+            .get_mut()
+            .lookup_cache = LookupCache::new(capacity, ttl);
+        self
+    }
+
+    /// Sets how long a ping result is reused before the address is pinged
+    /// again.
+    pub fn with_liveness_ttl(mut self, ttl: Duration) -> Self {
+        self.liveness = Arc::new(std::sync::Mutex::new(LivenessCache::new(ttl)));
+        self
+    }
+
+    /// Caps how many replicas this node sends at once (at least one). Like
+    /// `with_store`, only before the node is shared.
+    pub fn with_replication_concurrency(mut self, limit: usize) -> Self {
+        self.replication_permits = Arc::new(Semaphore::new(limit.max(1)));
+        self
+    }
+
+    /// Caps how many forwards (at least one) this node has in flight at
+    /// once, for its own requests and, separately, for requests other nodes
+    /// forwarded to it. Like `with_store`, only before the node is shared.
+    pub fn with_forward_limit(mut self, limit: usize) -> Self {
+        self.forward_permits = Arc::new(Semaphore::new(limit.max(1)));
+        self.hop_permits = Arc::new(Semaphore::new(limit.max(1)));
+        self
+    }
+
+    /// Sets how many times (at least once) a leaving node tries to reach each
+    /// successor, and the first wait between tries, before moving on to the
+    /// next one.
+    pub fn with_leave_retries(mut self, attempts: u32, backoff: Duration) -> Self {
+        self.leave_attempts = attempts.max(1);
+        self.leave_backoff = backoff;
+        self
+    }
+
+    /// Sets how many successors get a copy of a key written without its own
+    /// replication factor. 0 runs the node without replicas: a key lives
+    /// only on its owner and is gone if that node fails, as for a cache.
+    pub fn with_replication_count(mut self, count: usize) -> Result<Self, ConfigError> {
+        if count > self.successor_list_len {
+            return Err(ConfigError::SuccessorListTooShort {
+                successor_list_len: self.successor_list_len,
+                replication_count: count,
+            });
+        }
+        self.replication_count = count;
+        Ok(self)
+    }
+
+    /// Reports the node as overloaded to the monitor once it stores more
+    /// than `limit` keys. Unlike a store capacity nothing is evicted; it is
+    /// only an early warning that the ring needs more nodes.
+    pub fn with_max_keys_soft(mut self, limit: usize) -> Self {
+        self.max_keys_soft = Some(limit);
+        self
+    }
+
+    /// Makes this node an observer: it routes lookups and runs maintenance
+    /// like any other, but never stores keys. Its peers see the flag in its
+    /// `NodeInfo`, leave it out of their replicas and hand the ids it would
+    /// own to the next node that stores keys. Like `with_store`, only before
+    /// the node is shared.
+    pub fn as_observer(mut self) -> Self {
+        self.observer = true;
+        let me = self.self_info();
+        let state = Arc::get_mut(&mut self.state)
+            .expect("observer set after the node was shared")
+            .get_mut();
+        state.finger_table.fill(me.clone());
+        state.successor_list = vec![me];
+        self
+    }
+
+    /// Reaches peers through `transport` instead of TCP. Like `with_store`,
+    /// only before the node is shared.
+    pub fn with_transport(mut self, transport: Transport) -> Self {
+        self.connections = Arc::new(ConnectionPool::new(transport.clone()));
+        self.transport = transport;
+        self
+    }
+
+    /// How many requests were refused with `resource_exhausted` because no
+    /// forwarding slot freed up in time.
+    pub fn forwards_rejected(&self) -> u64 {
+        self.forwards_rejected.load(Ordering::Relaxed)
+    }
+
+    /// How many connections replication has dialed since the node started.
+    pub fn replication_dials(&self) -> u64 {
+        self.connections.dials()
+    }
+
+    /// How many successors we track (and so the most replicas a key can have).
+    pub fn successor_list_len(&self) -> usize {
+        self.successor_list_len
+    }
+
+    /// How many replicas a key written without its own factor gets.
+    pub fn replication_count(&self) -> usize {
+        self.replication_count
+    }
+
+    fn self_info(&self) -> NodeInfo {
+        NodeInfo {
+            id: self.id,
+            address: self.addr.clone(),
+            observer: self.observer,
+        }
+    }
+
+    pub fn is_observer(&self) -> bool {
+        self.observer
+    }
+
+    /// Returns the current successor. The successor list should never be empty,
+    /// but if it is, self is re-inserted as the fallback so the node keeps running
+    /// and stabilization can repair the ring.
+    pub async fn successor(&self) -> NodeInfo {
+        if let Some(successor) = self.state.read().await.successor_list.first() {
+            return successor.clone();
+        }
+        let mut state = self.state.write().await;
+        self.successor_or_self(&mut state)
+    }
+
+    fn successor_or_self(&self, state: &mut NodeState) -> NodeInfo {
+        if let Some(successor) = state.successor_list.first() {
+            return successor.clone();
+        }
+        warn!(
+            "Node {}: Successor list was empty, falling back to self",
+            self.id
+        );
+        let me = self.self_info();
+        state.successor_list.push(me.clone());
+        me
+    }
+
+    /// The interval `(predecessor, self]` this node is responsible for. It
+    /// reaches back further while the predecessor is an observer, whose ids
+    /// we own as well. An observer reports the range it would own, so the
+    /// node after it can take that over.
+    pub async fn responsible_range(&self) -> IdRange {
+        let start = self.state.read().await.owned_start(self.id);
+        IdRange {
+            start,
+            end: self.id,
+            whole_ring: start == self.id,
+        }
+    }
+
+    /// Readiness derived from the routing state: ready once we have a
+    /// predecessor and a successor other than ourselves, or once a lone node
+    /// has stabilized onto itself.
+    pub async fn health(&self) -> HealthState {
+        let state = self.state.read().await;
+        if state.leaving {
+            return HealthState::Leaving;
+        }
+        if state.drained {
+            return HealthState::Drained;
+        }
+        if state.draining {
+            return HealthState::Draining;
+        }
+        let has_other_successor = state.successor_list.iter().any(|s| s.id != self.id);
+        match &state.predecessor {
+            None if has_other_successor => HealthState::Joining,
+            None => HealthState::Starting,
+            Some(pred) if pred.id == self.id && !has_other_successor => HealthState::Ready,
+            Some(_) if has_other_successor => HealthState::Ready,
+            Some(_) => HealthState::Joining,
+        }
+    }
+
+    /// Checks the invariants a healthy node keeps: a non-empty successor
+    /// list without duplicates that only names us when we are alone, a
+    /// predecessor that agrees with that, primary keys that a lookup routes
+    /// to us, and a finger for every slot. Returns what is broken.
+    pub async fn self_check(&self) -> Vec<Violation> {
+        // Our predecessor is what makes a key primary here, so whether it
+        // really belongs to us has to come from the ring
+        let misplaced = self.misplaced_primaries().await;
+        let state = self.state.read().await;
+        let mut violations = Vec::new();
+        let violation = |kind: ViolationKind, description: String| Violation {
+            kind: kind as i32,
+            description,
+        };
+        let routing = |description: String| violation(ViolationKind::Routing, description);
+
+        let successors = &state.successor_list;
+        if successors.is_empty() {
+            violations.push(routing("successor list is empty".to_string()));
+        } else if successors.len() > 1 && successors.iter().any(|s| s.id == self.id) {
+            violations.push(routing(
+                "successor list names us alongside other nodes".to_string(),
+            ));
+        }
+        let mut seen = HashSet::new();
+        for succ in successors {
+            if !seen.insert(succ.id) {
+                violations.push(routing(format!("successor {} is listed twice", succ.id)));
+            }
+        }
+
+        let alone = successors.iter().all(|s| s.id == self.id);
+        match &state.predecessor {
+            Some(pred) if pred.id == self.id && !alone => violations.push(routing(
+                "predecessor is us but the ring has other nodes".to_string(),
+            )),
+            Some(pred) if pred.id != self.id && alone => violations.push(routing(format!(
+                "predecessor is {} but we have no successor besides ourselves",
+                pred.id
+            ))),
+            _ => {}
+        }
+
+        let mut keys: Vec<(&String, u64)> = misplaced
+            .by_owner
+            .values()
+            .flat_map(|(owner, keys)| keys.iter().map(move |key| (key, owner.id)))
+            .collect();
+        keys.sort();
+        for (key, owner) in keys {
+            violations.push(violation(
+                ViolationKind::MisplacedKey,
+                format!(
+                    "primary key '{}' (id {}) is owned by {}",
+                    key,
+                    hash_addr(key),
+                    owner
+                ),
+            ));
+        }
+        for (key, e) in &misplaced.unresolved {
+            violations.push(routing(format!(
+                "could not look up the owner of primary key '{}': {}",
+                key,
+                e.message()
+            )));
+        }
+
+        if state.finger_table.len() != FINGER_TABLE_SIZE {
+            violations.push(routing(format!(
+                "finger table has {} entries, expected {}",
+                state.finger_table.len(),
+                FINGER_TABLE_SIZE
+            )));
+        }
+        for (i, finger) in state.finger_table.iter().enumerate() {
+            if finger.address.is_empty() {
+                violations.push(routing(format!("finger {} has no address", i)));
+            }
+        }
+
+        violations
+    }
+
+    /// Keys we hold as primary, but that a lookup routes to another node,
+    /// grouped by that node. Such keys are left behind when our predecessor
+    /// is out of date, e.g. after heavy churn. Keys are checked in ring
+    /// order, so one lookup settles every key up to the owner it finds:
+    /// a healthy node needs a single lookup, which finds us. A key whose
+    /// owner can't be found is reported instead of failing the others.
+    async fn misplaced_primaries(&self) -> MisplacedKeys {
+        let (pred_id, mut primaries) = {
+            let state = self.state.read().await;
+            let pred_id = state.owned_start(self.id);
+            let primaries: Vec<(u64, String)> = state
+                .store
+                .keys()
+                .into_iter()
+                .map(|key| (hash_addr(&key), key))
+                .filter(|(id, _)| is_in_range_inclusive(*id, pred_id, self.id))
+                .collect();
+            (pred_id, primaries)
+        };
+        primaries.sort_unstable_by_key(|(id, _)| ID_SPACE.distance(pred_id, *id));
+
+        let mut misplaced = MisplacedKeys::default();
+        // The owner of the last key looked up owns every id from there to it
+        let mut owner: Option<NodeInfo> = None;
+        for (id, key) in primaries {
+            let known = owner
+                .as_ref()
+                .filter(|owner| is_in_range_inclusive(id, pred_id, owner.id));
+            let key_owner = match known {
+                Some(owner) => owner.clone(),
+                None => match self.find_owner(id).await {
+                    Ok(found) => {
+                        owner = Some(found.clone());
+                        found
+                    }
+                    Err(e) => {
+                        misplaced.unresolved.push((key, e));
+                        continue;
+                    }
+                },
+            };
+            if key_owner.id == self.id {
+                // Every key after this one is ours as well
+                break;
+            }
+            misplaced
+                .by_owner
+                .entry(key_owner.id)
+                .or_insert_with(|| (key_owner, Vec::new()))
+                .1
+                .push(key);
+        }
+        misplaced
+    }
+
+    /// Sends misplaced primary keys to the node a lookup routes them to.
+    /// Once the owner confirms a key, our copy is removed unless we are
+    /// among the owner's replicas for it, in which case it stays as a
+    /// replica. Returns how many keys moved.
+    pub async fn rebalance_internal(&self) -> Result<u64, Status> {
+        let misplaced = self.misplaced_primaries().await;
+        if let Some((key, e)) = misplaced.unresolved.first() {
+            warn!(
+                "Node {}: Leaving {} keys in place, e.g. '{}', whose owner lookup failed: {}",
+                self.id,
+                misplaced.unresolved.len(),
+                key,
+                e
+            );
+        }
+        let mut moved = 0;
+        for (owner, keys) in misplaced.by_owner.into_values() {
+            let keys: HashMap<String, StoredValue> = {
+                let state = self.state.read().await;
+                keys.into_iter()
+                    .filter_map(|key| state.store.get(&key).map(|entry| (key, entry)))
+                    .collect()
+            };
+            let factors: HashMap<String, usize> = keys
+                .iter()
+                .map(|(key, entry)| (key.clone(), entry.replication_factor))
+                .collect();
+            let sent: Vec<String> = keys.keys().cloned().collect();
+            let addr = node_url(&owner.address);
+            let response = self.transfer_keys_rpc(&owner, keys).await?;
+            let confirmed = confirmed_keys(sent, &response);
+
+            // Without the owner's successors we can't tell replicas apart,
+            // and a stray copy is better than a lost one
+            let replicas: Option<Vec<NodeInfo>> = match self.get_successor_list_rpc(addr).await {
+                Ok(list) => Some(
+                    list.successors
+                        .into_iter()
+                        .filter(|s| s.id != owner.id && !s.observer)
+                        .collect(),
+                ),
+                Err(e) => {
+                    warn!(
+                        "Node {}: Keeping rebalanced keys, no successor list from {}: {}",
+                        self.id, owner.id, e
+                    );
+                    None
+                }
+            };
+            if let Some(replicas) = replicas {
+                let mut state = self.state.write().await;
+                for key in &confirmed {
+                    let factor = factors.get(key).copied().unwrap_or(self.replication_count);
+                    if replicas.iter().take(factor).any(|s| s.id == self.id) {
+                        continue;
+                    }
+                    state.store.delete(key);
+                    let _ = self.changes.send(change_event(ChangeOp::Delete, key, None));
+                }
+            }
+            info!(
+                "Node {}: Rebalanced {} keys to their owner {}",
+                self.id,
+                confirmed.len(),
+                owner.id
+            );
+            moved += confirmed.len() as u64;
+        }
+        Ok(moved)
+    }
+
+    /// Runs `request`, recording how long it took under `op` whether or not
+    /// it succeeded.
+    async fn timed<T>(&self, op: Operation, request: impl Future<Output = T>) -> T {
+        let started = Instant::now();
+        let result = request.await;
+        self.latencies.record(op, started.elapsed());
+        result
+    }
+
+    pub async fn stats(&self) -> NodeStats {
+        let state = self.state.read().await;
+        let distinct_fingers = state.distinct_fingers();
+        let uptime = self.started_at.elapsed();
+        // A finger that was never fixed is as stale as the node is old
+        let stalest_finger_age = state
+            .finger_last_fixed
+            .iter()
+            .map(|fixed| fixed.map_or(uptime, |at| at.elapsed()))
+            .max()
+            .unwrap_or_default();
+        let pred_id = state.owned_start(self.id);
+        let primary_keys = state
+            .store
+            .keys()
+            .iter()
+            .filter(|key| is_in_range_inclusive(hash_addr(key), pred_id, self.id))
+            .count();
+        let lag = self.replication_lag.lock().unwrap();
+        NodeStats {
+            store_size: state.store.len() as u64,
+            successor_list_len: state.successor_list.len() as u64,
+            has_predecessor: state.predecessor.is_some(),
+            distinct_fingers: distinct_fingers.len() as u64,
+            uptime_ms: uptime.as_millis() as u64,
+            stalest_finger_age_ms: stalest_finger_age.as_millis() as u64,
+            replication_lag_p50_ms: lag.percentile(0.50).as_millis() as u64,
+            replication_lag_p99_ms: lag.percentile(0.99).as_millis() as u64,
+            replication_lag_samples: lag.len() as u64,
+            op_latencies: self.latencies.snapshot(),
+            primary_keys: primary_keys as u64,
+        }
+    }
+
+    /// Shorthand for [`crate::ring::is_in_range_inclusive`].
+    pub fn is_in_range_inclusive(id: u64, start: u64, end: u64) -> bool {
+        is_in_range_inclusive(id, start, end)
+    }
+
+    pub async fn find_successor_internal(&self, id: u64) -> Result<NodeInfo, Status> {
+        self.find_successor_bounded(id, MAX_LOOKUP_HOPS).await
+    }
+
+    /// The node that stores `id`: its successor, or, when that is an
+    /// observer, the first node after it that stores keys.
+    pub async fn find_owner(&self, id: u64) -> Result<NodeInfo, Status> {
+        let first = self.find_successor_internal(id).await?;
+        let mut owner = first.clone();
+        while owner.observer {
+            owner = self
+                .find_successor_internal(owner.id.wrapping_add(1))
+                .await?;
+            if owner.id == first.id {
+                return Err(Status::unavailable("every node in the ring is an observer"));
+            }
+        }
+        Ok(owner)
+    }
+
+    /// Like `find_successor_internal`, but the lookup may be forwarded at
+    /// most `max_hops - 1` more times; a node that would have to forward it
+    /// with no hops left fails it with `aborted`.
+    pub async fn find_successor_bounded(&self, id: u64, max_hops: u32) -> Result<NodeInfo, Status> {
+        let mut attempt = 0;
+        loop {
+            match self.find_successor_once(id, max_hops, false).await {
+                Err(e)
+                    if e.code() == tonic::Code::Unavailable
+                        && attempt < FIND_SUCCESSOR_RETRY_LIMIT =>
+                {
+                    attempt += 1;
+                    // Our successor list is probably stale (e.g. still lists a dead node).
+                    // Refresh it with an immediate stabilization round and retry.
+                    self.state.write().await.lookup_cache.clear();
+                    warn!(
+                        "Node {}: Lookup for id {} failed ({}), stabilizing and retrying",
+                        self.id,
+                        id,
+                        e.message()
+                    );
+                    self.stabilize().await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Resolves `id` through the fingers alone, for inspecting routing
+    /// without disturbing it. Unlike `find_successor_bounded`, neither this
+    /// node nor any node the lookup is forwarded to reads, fills or prunes
+    /// its lookup cache, marks a failed hop down in its liveness cache, runs
+    /// a stabilization round after a failure, or counts the request in its
+    /// latency histograms. A cache hiding a routing bug can't hide it here.
+    pub async fn find_successor_dry_run(&self, id: u64, max_hops: u32) -> Result<NodeInfo, Status> {
+        self.find_successor_once(id, max_hops, true).await
+    }
+
+    async fn find_successor_once(
+        &self,
+        id: u64,
+        max_hops: u32,
+        dry_run: bool,
+    ) -> Result<NodeInfo, Status> {
+        let route = self.route_snapshot(id).await;
+
+        if is_in_range_inclusive(id, self.id, route.successor.id) {
+            return Ok(route.successor);
+        }
+
+        if !dry_run {
+            if let Some(owner) = self.cached_owner(id).await {
+                return Ok(owner);
+            }
+        }
+
+        // If no finger precedes the id, fall back to successor
+        let Some(closest) = route.closest.clone() else {
+            return Ok(route.successor);
+        };
+
+        // Whoever we forward to gets one hop less, and 0 would mean the default
+        let hops_left = max_hops.saturating_sub(1);
+        if hops_left == 0 {
+            warn!(
+                "Node {}: Lookup for id {} ran out of hops, the ring may be malformed",
+                self.id, id
+            );
+            return Err(Status::aborted("hop limit exceeded"));
+        }
+
+        // The closest preceding finger makes the most progress, so the other
+        // fingers are only ranked if it can't be reached
+        if let Some(info) = self.lookup_via(&closest, id, hops_left, dry_run).await? {
+            return Ok(info);
+        }
+        debug!(
+            "Node {}: Closest finger {} failed for id {}, trying the others",
+            self.id, closest.id, id
+        );
+        for candidate in self.finger_candidates(id).await {
+            if candidate.id == closest.id {
+                continue;
+            }
+            if let Some(info) = self.lookup_via(&candidate, id, hops_left, dry_run).await? {
+                return Ok(info);
+            }
         }
+
+        // If all fingers failed, try successor list as fallback
+        // This helps if the best finger (likely immediate successor) is dead.
+        // We try to find *any* live node in our successor list to forward the query to.
+        // Even if they are not strictly "closest preceding", they are better than failing.
+        // And in a small ring, they are likely the next best hop.
+        for succ in route.successor_list {
+            // Skip if we already tried it (it was in candidates)
+            if succ.id == self.id {
+                continue;
+            }
+
+            let client_addr = node_url(&succ.address);
+            debug!(
+                "Node {}: Fallback: trying successor {} for id {}",
+                self.id, succ.id, id
+            );
+            match self
+                .find_successor_rpc(client_addr, id, hops_left, dry_run)
+                .await
+            {
+                Ok(info) => return Ok(info),
+                Err(e) if e.code() == tonic::Code::Aborted => return Err(e),
+                Err(e) => {
+                    warn!(
+                        "Node {}: Fallback successor {} failed: {}",
+                        self.id, succ.id, e
+                    );
+                }
+            }
+        }
+
+        Err(Status::unavailable("All candidates and successors failed"))
+    }
+
+    /// Asks `hop` to resolve `id`, caching the answer unless this is a dry
+    /// run. None if `hop` failed, so another candidate can be tried; a lookup
+    /// that ran out of hops is passed back instead, since every other route
+    /// would run out too.
+    async fn lookup_via(
+        &self,
+        hop: &NodeInfo,
+        id: u64,
+        max_hops: u32,
+        dry_run: bool,
+    ) -> Result<Option<NodeInfo>, Status> {
+        let client_addr = node_url(&hop.address);
+        match self
+            .find_successor_rpc(client_addr, id, max_hops, dry_run)
+            .await
+        {
+            Ok(info) if dry_run => Ok(Some(info)),
+            Ok(info) => {
+                self.state
+                    .write()
+                    .await
+                    .lookup_cache
+                    .insert(id, info.clone());
+                Ok(Some(info))
+            }
+            Err(e) if e.code() == tonic::Code::Aborted => Err(e),
+            Err(e) => {
+                warn!(
+                    "Node {}: Failed to contact candidate {} ({}) for id {}: {}",
+                    self.id, hop.id, hop.address, id, e
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    /// A cached owner for `id`, used only if its current predecessor shows
+    /// it still owns `id`. That catches both an owner that died and a node
+    /// that joined inside the cached span since the lookup.
+    async fn cached_owner(&self, id: u64) -> Option<NodeInfo> {
+        let owner = self.state.read().await.lookup_cache.get(id)?;
+        let addr = node_url(&owner.address);
+        let reason = match self.get_predecessor_rpc(addr).await {
+            Ok(pred) if is_in_range_inclusive(id, pred.id, owner.id) => return Some(owner),
+            Ok(pred) => format!("its predecessor is now {}", pred.id),
+            Err(e) => e.to_string(),
+        };
+        debug!(
+            "Node {}: Dropping cached owner {} of id {}: {}",
+            self.id, owner.id, id, reason
+        );
+        self.state.write().await.lookup_cache.remove_node(owner.id);
+        None
+    }
+
+    /// Resolves `id` like `find_successor_internal`, recording every node
+    /// the request passes through after those already in `path`. The lookup
+    /// cache is skipped so the trace shows the route fingers actually take.
+    pub async fn find_successor_traced_internal(
+        &self,
+        id: u64,
+        mut path: Vec<NodeInfo>,
+    ) -> Result<TracedLookupResponse, Status> {
+        let looped = path.iter().any(|n| n.id == self.id);
+        path.push(self.self_info());
+        if looped {
+            warn!("Node {}: Traced lookup for id {} looped", self.id, id);
+            return Ok(TracedLookupResponse {
+                successor: None,
+                path,
+            });
+        }
+
+        let route = self.route_snapshot(id).await;
+        if is_in_range_inclusive(id, self.id, route.successor.id) || route.closest.is_none() {
+            return Ok(TracedLookupResponse {
+                successor: Some(route.successor),
+                path,
+            });
+        }
+
+        let mut hops = self.finger_candidates(id).await;
+        hops.extend(route.successor_list);
+
+        for hop in hops {
+            if hop.id == self.id {
+                continue;
+            }
+
+            let client_addr = node_url(&hop.address);
+            match self
+                .find_successor_traced_rpc(client_addr, id, path.clone())
+                .await
+            {
+                Ok(response) => return Ok(response),
+                Err(e) => {
+                    warn!(
+                        "Node {}: Failed to contact {} ({}) for traced lookup of id {}: {}",
+                        self.id, hop.id, hop.address, id, e
+                    );
+                }
+            }
+        }
+
+        Err(Status::unavailable("All candidates and successors failed"))
+    }
+
+    /// Finds the node immediately preceding `id` on the ring, i.e. the node
+    /// whose successor owns `id`.
+    pub async fn find_predecessor_internal(&self, id: u64) -> Result<NodeInfo, Status> {
+        let route = self.route_snapshot(id).await;
+        if is_in_range_inclusive(id, self.id, route.successor.id) {
+            return Ok(self.self_info());
+        }
+
+        // Same hop order as find_successor: closest preceding fingers first,
+        // then anything in the successor list
+        let mut hops = self.finger_candidates(id).await;
+        hops.extend(route.successor_list);
+
+        for hop in hops {
+            if hop.id == self.id {
+                continue;
+            }
+
+            let client_addr = node_url(&hop.address);
+            match self.find_predecessor_rpc(client_addr, id).await {
+                Ok(info) => return Ok(info),
+                Err(e) => {
+                    warn!(
+                        "Node {}: Failed to contact {} ({}) for predecessor of id {}: {}",
+                        self.id, hop.id, hop.address, id, e
+                    );
+                }
+            }
+        }
+
+        Err(Status::unavailable("All candidates and successors failed"))
+    }
+
+    /// The classic Chord routing step: the finger that most closely precedes
+    /// `id` going clockwise from us, or ourselves if none lies in between.
+    pub async fn closest_preceding_node(&self, id: u64) -> NodeInfo {
+        let state = self.state.read().await;
+        self.closest_preceding_finger(&state, id)
+            .unwrap_or_else(|| self.self_info())
+    }
+
+    /// One pass over the finger table. Fingers can be stale, so this takes
+    /// the farthest one in range rather than the first from the top.
+    fn closest_preceding_finger(&self, state: &NodeState, id: u64) -> Option<NodeInfo> {
+        state
+            .finger_table
+            .iter()
+            .filter(|finger| !finger.address.is_empty() && is_in_range(finger.id, self.id, id))
+            .max_by_key(|finger| finger.id.wrapping_sub(self.id))
+            .cloned()
+    }
+
+    /// Copies what a lookup for `id` routes on under a single read lock, so
+    /// the lookup works from one consistent view even if stabilization
+    /// rewrites the successor list or fingers while its RPCs are in flight.
+    /// The other fingers are left out; see `finger_candidates`.
+    async fn route_snapshot(&self, id: u64) -> RouteSnapshot {
+        let state = self.state.read().await;
+        let successor = state
+            .successor_list
+            .first()
+            .cloned()
+            .unwrap_or_else(|| self.self_info());
+
+        RouteSnapshot {
+            successor,
+            closest: self.closest_preceding_finger(&state, id),
+            successor_list: state.successor_list.clone(),
+        }
+    }
+
+    /// Fingers strictly between us and `id`, closest to `id` first, with
+    /// repeated nodes collapsed. Only needed once the closest preceding
+    /// finger fails (or to rank every hop), so it reads the fingers as they
+    /// are then rather than from the lookup's snapshot.
+    async fn finger_candidates(&self, id: u64) -> Vec<NodeInfo> {
+        let state = self.state.read().await;
+        let mut candidates: Vec<NodeInfo> = state
+            .finger_table
+            .iter()
+            .filter(|finger| !finger.address.is_empty() && is_in_range(finger.id, self.id, id))
+            .cloned()
+            .collect();
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.id.wrapping_sub(self.id)));
+        // Repeats of a node sort next to each other
+        candidates.dedup_by_key(|c| c.id);
+        candidates
+    }
+
+    /// Starts a new ring with this node as its only member, settled on
+    /// itself right away instead of on its first stabilization.
+    pub async fn create(&self) -> Result<(), JoinError> {
+        let mut state = self.state.write().await;
+        let has_predecessor = state.predecessor.as_ref().is_some_and(|p| p.id != self.id);
+        if has_predecessor || state.successor_list.iter().any(|s| s.id != self.id) {
+            return Err(JoinError::AlreadyJoined);
+        }
+        state.predecessor = Some(self.self_info());
+        Ok(())
+    }
+
+    /// Joins the ring `join_addr` belongs to. On failure nothing changes:
+    /// the node is still alone and unsettled, not a ring of its own.
+    pub async fn join(&self, join_addr: String) -> Result<(), JoinError> {
+        {
+            let state = self.state.read().await;
+            let has_predecessor = state.predecessor.as_ref().is_some_and(|p| p.id != self.id);
+            if has_predecessor || state.successor_list.iter().any(|s| s.id != self.id) {
+                return Err(JoinError::AlreadyJoined);
+            }
+        }
+        if normalize_addr(&join_addr) == self.addr {
+            return Err(JoinError::SelfJoin { seed: join_addr });
+        }
+
+        self.handshake(&join_addr).await?;
+        let endpoint = node_url(&join_addr);
+        let info = self
+            .find_successor_rpc(endpoint, self.id, 0, false)
+            .await
+            .map_err(|e| JoinError::from_status(&join_addr, e))?;
+        if info.address.is_empty() {
+            return Err(JoinError::BadResponse {
+                seed: join_addr,
+                reason: format!("successor {} has no address", info.id),
+            });
+        }
+        // Reached through another spelling of our own address, we are alone
+        // and find ourselves. Any other node with our id is a hash collision:
+        // ids alone could no longer tell us apart.
+        if info.id == self.id && normalize_addr(&info.address) == self.addr {
+            return Err(JoinError::SelfJoin { seed: join_addr });
+        }
+        if info.id == self.id {
+            return Err(JoinError::IdCollision {
+                id: info.id,
+                address: info.address,
+            });
+        }
+        // The successor is the node we'll talk to most, so it has to speak
+        // our protocol too (and learn our version)
+        if info.address != join_addr {
+            self.handshake(&info.address).await?;
+        }
+
+        let mut state = self.state.write().await;
+        match state.successor_list.first_mut() {
+            Some(successor) => *successor = info.clone(),
+            None => state.successor_list.push(info.clone()),
+        }
+        drop(state);
+
+        self.pull_owned_keys(&info).await;
+        Ok(())
+    }
+
+    /// Copies the keys we now own from our new successor, so we hold them
+    /// right away instead of after it notices us. It keeps its copies until
+    /// it hands the range over on notify, so a failed pull loses nothing.
+    async fn pull_owned_keys(&self, successor: &NodeInfo) {
+        if self.observer {
+            return;
+        }
+        // Until we notify it, our successor's predecessor is the node we were
+        // inserted after; with no such node the successor is alone
+        let addr = node_url(&successor.address);
+        let start = match self.get_predecessor_rpc(addr.clone()).await {
+            Ok(pred) if pred.id != self.id => pred.id,
+            _ => successor.id,
+        };
+        // An observer holds none of them; the node storing its range does
+        let source = if successor.observer {
+            match self.find_owner(successor.id).await {
+                Ok(owner) => owner,
+                Err(_) => return,
+            }
+        } else {
+            successor.clone()
+        };
+        match self.pull_keys_rpc(&source, start, self.id).await {
+            Ok(response) if response.accepted_count == 0 => {}
+            Ok(response) => info!(
+                "Node {}: Pulled {} keys in ({}, {}] from {}",
+                self.id, response.accepted_count, start, self.id, source.id
+            ),
+            Err(e) => warn!(
+                "Node {}: Failed to pull keys from {}, waiting for the handover: {}",
+                self.id, source.id, e
+            ),
+        }
+    }
+
+    /// Exchanges protocol versions with the node at `addr` and remembers its
+    /// version. A node from before the handshake counts as version 1.
+    async fn handshake(&self, addr: &str) -> Result<(), JoinError> {
+        let endpoint = node_url(addr);
+        let peer = match self.hello_rpc(endpoint).await {
+            Ok(peer) => peer,
+            Err(e) if e.code() == tonic::Code::Unimplemented => Handshake {
+                version: 1,
+                min_version: 1,
+                node: None,
+            },
+            Err(e) => return Err(JoinError::from_status(addr, e)),
+        };
+        if peer.version < MIN_PROTOCOL_VERSION || PROTOCOL_VERSION < peer.min_version {
+            return Err(JoinError::IncompatibleVersion {
+                seed: addr.to_string(),
+                version: peer.version,
+            });
+        }
+        if peer.version != PROTOCOL_VERSION {
+            warn!(
+                "Node {}: Peer {} speaks protocol version {}, we speak {}",
+                self.id, addr, peer.version, PROTOCOL_VERSION
+            );
+        }
+        if let Some(node) = peer.node {
+            self.state
+                .write()
+                .await
+                .peer_versions
+                .insert(node.id, peer.version);
+        }
+        Ok(())
+    }
+
+    /// The protocol version `peer_id` reported, if it has said hello.
+    pub async fn peer_version(&self, peer_id: u64) -> Option<u32> {
+        self.state.read().await.peer_versions.get(&peer_id).copied()
+    }
+
+    /// `req` as it should go to `peer_id`: metadata is left out for peers
+    /// known to predate it. Peers we never heard from get everything, since
+    /// they ignore fields they don't know.
+    async fn put_for_peer(&self, peer_id: u64, mut req: PutRequest) -> PutRequest {
+        let version = self.peer_version(peer_id).await;
+        if version.is_some_and(|v| v < METADATA_PROTOCOL_VERSION) {
+            req.metadata.clear();
+        }
+        req
+    }
+
+    /// Joins like `join`, then hands any keys already in our store (e.g.
+    /// loaded from a previous run) that we no longer own to their owners.
+    pub async fn join_with_handoff(&self, join_addr: String) -> Result<(), JoinError> {
+        self.join(join_addr).await?;
+
+        // Until we notify it, our successor's predecessor is the node we were
+        // inserted after, so the keys we own now are (that node, self]. With
+        // no such node the successor is alone and we own (successor, self].
+        let successor = self.successor().await;
+        let successor_addr = node_url(&successor.address);
+        let pred_id = match self.get_predecessor_rpc(successor_addr.clone()).await {
+            Ok(pred) if pred.id != self.id => pred.id,
+            _ => successor.id,
+        };
+
+        let mut foreign: Vec<(String, StoredValue)> = Vec::new();
+        self.state
+            .read()
+            .await
+            .store
+            .for_each_entry(&mut |key, entry| {
+                if !is_in_range_inclusive(hash_addr(key), pred_id, self.id) {
+                    foreign.push((key.to_string(), entry.clone()));
+                }
+            });
+        if foreign.is_empty() {
+            return Ok(());
+        }
+
+        // Our fingers are still empty, so resolve owners through the successor
+        let mut by_owner: HashMap<u64, (NodeInfo, HashMap<String, StoredValue>)> = HashMap::new();
+        for (key, entry) in foreign {
+            let owner = self
+                .find_successor_rpc(successor_addr.clone(), hash_addr(&key), 0, false)
+                .await
+                .map_err(|e| JoinError::from_status(&successor.address, e))?;
+            if owner.id == self.id {
+                continue;
+            }
+            by_owner
+                .entry(owner.id)
+                .or_insert_with(|| (owner, HashMap::new()))
+                .1
+                .insert(key, entry);
+        }
+
+        for (owner, keys) in by_owner.into_values() {
+            info!(
+                "Node {}: Handing off {} keys to {} after join",
+                self.id,
+                keys.len(),
+                owner.id
+            );
+            let sent: Vec<String> = keys.keys().cloned().collect();
+            let handed_off = match self.transfer_keys_rpc(&owner, keys).await {
+                Ok(response) => confirmed_keys(sent, &response),
+                Err(e) => {
+                    // Keep the keys; stabilization and replication can still move them later
+                    warn!(
+                        "Node {}: Failed to hand off keys to {}: {}",
+                        self.id, owner.id, e
+                    );
+                    continue;
+                }
+            };
+            let mut state = self.state.write().await;
+            for key in handed_off {
+                state.store.delete(&key);
+                let _ = self
+                    .changes
+                    .send(change_event(ChangeOp::Delete, &key, None));
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn stabilize(&self) {
+        let successor = self.successor().await;
+
+        let successor_addr = node_url(&successor.address);
+        let x_result = self.get_predecessor_rpc(successor_addr.clone()).await;
+
+        match x_result {
+            Ok(x) => {
+                let should_update = if x.id != 0 || !x.address.is_empty() {
+                    is_in_range(x.id, self.id, successor.id)
+                } else {
+                    false
+                };
+
+                if should_update {
+                    let mut state = self.state.write().await;
+                    // Ensure successor hasn't changed while we were waiting for RPC
+                    if let Some(current) = state.successor_list.first_mut() {
+                        if current.id == successor.id {
+                            *current = x;
+                            state.lookup_cache.clear();
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                // NotFound means the successor doesn't have a predecessor yet - this is OK
+                // Only treat Unavailable/transport errors as dead nodes
+                if e.code() == tonic::Code::NotFound {
+                    // Successor is alive but has no predecessor yet, continue normally
+                } else {
+                    warn!("Node {}: Successor {} failed: {}", self.id, successor.id, e);
+                    if !self.drop_dead_successors().await {
+                        return;
+                    }
+                }
+            }
+        }
+
+        let successor = self.successor().await;
+
+        let successor_addr = node_url(&successor.address);
+        let me = self.self_info();
+
+        if let Err(e) = self.notify_rpc(successor_addr.clone(), me).await {
+            warn!(
+                "Node {}: Failed to notify successor {}: {}",
+                self.id, successor.id, e
+            );
+        }
+
+        let _ = self.update_successor_list(successor_addr).await;
+    }
+
+    /// Called once the first successor has failed: pings the rest of the
+    /// list in order and drops the whole dead prefix in one go, promoting the
+    /// first live successor (or ourselves if none answer). Returns whether a
+    /// live successor other than us was found.
+    async fn drop_dead_successors(&self) -> bool {
+        let successors = self.state.read().await.successor_list.clone();
+        let mut dead = 1;
+        for succ in successors.iter().skip(1) {
+            if succ.id == self.id {
+                break;
+            }
+            match self.ping_cached(node_url(&succ.address)).await {
+                Ok(()) => break,
+                Err(e) => {
+                    warn!("Node {}: Successor {} failed: {}", self.id, succ.id, e);
+                    dead += 1;
+                }
+            }
+        }
+
+        let mut state = self.state.write().await;
+        // The list may have changed while we were pinging; only trim what we checked
+        let unchanged = state
+            .successor_list
+            .iter()
+            .zip(&successors[..dead])
+            .take_while(|(current, checked)| current.id == checked.id)
+            .count();
+        if unchanged == 0 {
+            return false;
+        }
+        state.successor_list.drain(..unchanged);
+        state.lookup_cache.clear();
+        match state.successor_list.first() {
+            Some(next) if next.id != self.id => {
+                info!(
+                    "Node {}: Removed {} dead successors, promoting {}",
+                    self.id, unchanged, next.id
+                );
+                true
+            }
+            _ => {
+                // Nobody left to take over, so we're on our own until someone notifies us
+                info!(
+                    "Node {}: Removed {} dead successors, falling back to self",
+                    self.id, unchanged
+                );
+                state.successor_list = vec![self.self_info()];
+                false
+            }
+        }
+    }
+
+    /// The finger to refresh next: usually the one fixed longest ago (never
+    /// fixed ones first), occasionally a random one.
+    async fn next_finger_to_fix(&self) -> usize {
+        use rand::Rng;
+        if rand::thread_rng().gen_bool(FIX_FINGERS_RANDOM_PICK_PROBABILITY) {
+            return rand::thread_rng().gen_range(0..FINGER_TABLE_SIZE);
+        }
+        let state = self.state.read().await;
+        state
+            .finger_last_fixed
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, fixed)| **fixed)
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    }
+
+    pub async fn fix_fingers(&self) {
+        let i = self.next_finger_to_fix().await;
+        // Count the attempt even if it fails, so an unreachable finger doesn't
+        // keep getting picked ahead of the others
+        self.state.write().await.finger_last_fixed[i] = Some(Instant::now());
+
+        // finger[i] should point to successor of (n + 2^i) mod 2^ID_BITS
+        let target = ID_SPACE.finger_target(self.id, i);
+
+        if let Ok(successor) = self.find_successor_internal(target).await {
+            // Don't let a dead node linger in the finger table as a routing candidate
+            if successor.id != self.id {
+                let addr = node_url(&successor.address);
+                if let Err(e) = self.ping_cached(addr).await {
+                    debug!(
+                        "Node {}: Finger {} candidate {} unreachable, keeping old entry: {}",
+                        self.id, i, successor.id, e
+                    );
+                    return;
+                }
+            }
+            let mut state = self.state.write().await;
+            state.finger_table[i] = successor;
+        }
+    }
+
+    pub async fn check_predecessor(&self) {
+        let Some(predecessor) = self.state.read().await.predecessor.clone() else {
+            return;
+        };
+        let addr = node_url(&predecessor.address);
+        if self.ping_cached(addr.clone()).await.is_ok() {
+            if predecessor.observer {
+                self.refresh_observer_range(addr, &predecessor).await;
+            }
+            return;
+        }
+        let mut state = self.state.write().await;
+        // A new predecessor may have notified us while we were pinging
+        if state
+            .predecessor
+            .as_ref()
+            .is_some_and(|p| p.id == predecessor.id)
+        {
+            state.predecessor = None;
+            state.lookup_cache.clear();
+        }
+    }
+
+    /// Asks our observer predecessor where the range it would own starts,
+    /// since that range is ours. An observer that doesn't know its own
+    /// predecessor yet reports the whole ring, which tells us nothing.
+    async fn refresh_observer_range(&self, addr: String, observer: &NodeInfo) {
+        let Ok(range) = self.owned_range_rpc(addr).await else {
+            return;
+        };
+        if range.whole_ring {
+            return;
+        }
+        let mut state = self.state.write().await;
+        if state
+            .predecessor
+            .as_ref()
+            .is_some_and(|p| p.id == observer.id)
+        {
+            state.observer_range_start = Some(range.start);
+        }
+    }
+
+    /// Anti-entropy for the keys we are primary for. Each successor gets the
+    /// keys whose replication factor reaches it; we compare hash trees with
+    /// it first and only push the buckets that differ.
+    pub async fn maintain_replication(&self) {
+        let state = self.state.read().await;
+        let pred_id = state.owned_start(self.id);
+        let mut primary: HashMap<String, StoredValue> = HashMap::new();
+        state.store.for_each_entry(&mut |key, entry| {
+            if is_in_range_inclusive(hash_addr(key), pred_id, self.id) {
+                primary.insert(key.to_string(), entry.clone());
+            }
+        });
+        let successors: Vec<NodeInfo> = state.storage_successors(self.id);
+        drop(state);
+
+        // Replicas sit on the live successors, so a dead one is skipped
+        // rather than counted against the replication factor
+        let needed = primary
+            .values()
+            .map(|entry| entry.replication_factor)
+            .max()
+            .unwrap_or(0);
+        // Nothing we own is replicated, as when replication is turned off
+        if needed == 0 {
+            return;
+        }
+        let successors = self.live_successors(successors, needed).await;
+
+        for (i, succ) in successors.into_iter().enumerate() {
+            // Each key carries its own replication factor
+            let min_replication_factor = i + 1;
+            let keys: HashMap<String, StoredValue> = primary
+                .iter()
+                .filter(|(_, entry)| entry.replication_factor >= min_replication_factor)
+                .map(|(key, entry)| (key.clone(), entry.clone()))
+                .collect();
+            if keys.is_empty() {
+                continue;
+            }
+
+            let node = self.clone();
+            tokio::spawn(async move {
+                node.sync_replica(succ, pred_id, min_replication_factor, keys)
+                    .await
+            });
+        }
+    }
+
+    /// Brings one replica up to date with `keys`, our primary keys in
+    /// `(pred_id, self]` that it should hold.
+    async fn sync_replica(
+        &self,
+        replica: NodeInfo,
+        pred_id: u64,
+        min_replication_factor: usize,
+        keys: HashMap<String, StoredValue>,
+    ) {
+        let tree = MerkleTree::build(
+            pred_id,
+            self.id,
+            MERKLE_TREE_DEPTH,
+            keys.iter()
+                .map(|(key, entry)| (hash_addr(key), key.as_str(), entry.updated_at)),
+        );
+        let endpoint = node_url(&replica.address);
+        let request = SyncDigestRequest {
+            start_id: pred_id,
+            end_id: self.id,
+            min_replication_factor: min_replication_factor as u32,
+            root: tree.root().to_vec(),
+        };
+
+        let to_push: Vec<(String, StoredValue)> =
+            match self.sync_digest_rpc(endpoint.clone(), request).await {
+                Ok(resp) if resp.leaves.is_empty() => return,
+                Ok(resp) => {
+                    let differing = tree.diff(&resp.leaves);
+                    let fraction = differing.len() as f64 / tree.leaves().len() as f64;
+                    if fraction > ANTI_ENTROPY_FULL_PUSH_FRACTION {
+                        keys.into_iter().collect()
+                    } else {
+                        keys.into_iter()
+                            .filter(|(key, _)| differing.contains(&tree.bucket(hash_addr(key))))
+                            .collect()
+                    }
+                }
+                Err(e) => {
+                    debug!(
+                        "Node {}: Digest exchange with {} failed, pushing everything: {}",
+                        self.id, replica.id, e
+                    );
+                    keys.into_iter().collect()
+                }
+            };
+
+        debug!(
+            "Node {}: Anti-entropy pushing {} keys to {}",
+            self.id,
+            to_push.len(),
+            replica.id
+        );
+        for (key, entry) in to_push {
+            let req = self
+                .put_for_peer(replica.id, entry.to_put_request(key))
+                .await;
+            if let Err(e) = self.send_replica(endpoint.clone(), req).await {
+                debug!(
+                    "Node {}: Failed to replicate to {} during maintenance: {}",
+                    self.id, replica.id, e
+                );
+                return;
+            }
+        }
+    }
+
+    /// Hash tree of our copies of the keys a primary asked about.
+    pub async fn digest(
+        &self,
+        start_id: u64,
+        end_id: u64,
+        min_replication_factor: usize,
+    ) -> MerkleTree {
+        let mut entries = Vec::new();
+        self.state
+            .read()
+            .await
+            .store
+            .for_each_entry(&mut |key, entry| {
+                let key_id = hash_addr(key);
+                if entry.replication_factor >= min_replication_factor
+                    && is_in_range_inclusive(key_id, start_id, end_id)
+                {
+                    entries.push((key_id, key.to_string(), entry.updated_at));
+                }
+            });
+        MerkleTree::build(
+            start_id,
+            end_id,
+            MERKLE_TREE_DEPTH,
+            entries
+                .iter()
+                .map(|(key_id, key, updated_at)| (*key_id, key.as_str(), *updated_at)),
+        )
+    }
+
+    async fn update_successor_list(&self, successor_addr: String) -> Result<(), Status> {
+        let list = self.get_successor_list_rpc(successor_addr).await?;
+        self.apply_successor_list(list).await;
+        Ok(())
+    }
+
+    /// Rebuilds our successor list from the list reported by our successor.
+    /// Self and duplicate ids are dropped; self is only kept when it is the
+    /// sole entry (i.e. we are alone in the ring).
+    pub async fn apply_successor_list(&self, list: SuccessorList) {
+        let mut state = self.state.write().await;
+        // New successor list = successor + successor.successors (trimmed)
+        let successor = self.successor_or_self(&mut state);
+        let mut new_list: Vec<NodeInfo> = Vec::with_capacity(self.successor_list_len);
+        for succ in std::iter::once(successor).chain(list.successors) {
+            if succ.id == self.id || new_list.iter().any(|s| s.id == succ.id) {
+                continue;
+            }
+            new_list.push(succ);
+            if new_list.len() == self.successor_list_len {
+                // Keep k successors
+                break;
+            }
+        }
+        if new_list.is_empty() {
+            new_list.push(self.self_info());
+        } else {
+            let last_known: Vec<NodeInfo> = new_list
+                .iter()
+                .filter(|s| !state.departed.contains(&s.id))
+                .cloned()
+                .collect();
+            state.last_known_successors = last_known;
+        }
+        state.successor_list = new_list;
+    }
+
+    /// Stores a streamed key transfer as it arrives, a batch of about
+    /// `TRANSFER_BATCH_BYTES` at a time. A value is only buffered up to the
+    /// size limit, so an oversized one can't exhaust memory before it is
+    /// rejected.
+    async fn receive_keys<S>(&self, mut chunks: S) -> Result<TransferKeysResponse, Status>
+    where
+        S: Stream<Item = Result<KeyTransferChunk, Status>> + Unpin,
+    {
+        let mut response = TransferKeysResponse::default();
+        let mut batch = HashMap::new();
+        let mut batch_bytes = 0;
+        let mut current: Option<PutRequest> = None;
+        while let Some(message) = chunks.next().await {
+            let message = message?;
+            if !message.request_ids.is_empty() {
+                let mut state = self.state.write().await;
+                for id in message.request_ids {
+                    state.applied_requests.insert(id);
+                }
+            }
+            let Some(chunk) = message.chunk else {
+                continue;
+            };
+            if !chunk.found {
+                // Past the limit the rest is dropped; the key is rejected anyway
+                if let Some(req) = current.as_mut() {
+                    if req.value.len() <= MAX_VALUE_BYTES {
+                        req.value.extend_from_slice(&chunk.data);
+                    }
+                }
+                continue;
+            }
+            let next = PutRequest {
+                key: chunk.key,
+                value: chunk.data,
+                updated_at: chunk.updated_at,
+                replication_factor: chunk.replication_factor,
+                metadata: chunk.metadata,
+                ..Default::default()
+            };
+            if let Some(req) = current.replace(next) {
+                batch_bytes += req.key.len() + req.value.len();
+                let (key, entry) = self.transferred_entry(req);
+                batch.insert(key, entry);
+                if batch_bytes >= TRANSFER_BATCH_BYTES {
+                    self.store_transferred_keys(std::mem::take(&mut batch), &mut response)
+                        .await;
+                    batch_bytes = 0;
+                }
+            }
+        }
+        if let Some(req) = current {
+            let (key, entry) = self.transferred_entry(req);
+            batch.insert(key, entry);
+        }
+        self.store_transferred_keys(batch, &mut response).await;
+        Ok(response)
+    }
+
+    /// A key received from another node, with its timestamp (now, if the
+    /// sender had none) and replication factor resolved for this node.
+    fn transferred_entry(&self, req: PutRequest) -> (String, StoredValue) {
+        let entry = StoredValue {
+            value: req.value,
+            updated_at: if req.updated_at == 0 {
+                now_millis()
+            } else {
+                req.updated_at
+            },
+            replication_factor: replication_factor(
+                req.replication_factor,
+                self.replication_count,
+                self.successor_list_len,
+            ),
+            metadata: req.metadata,
+        };
+        (req.key, entry)
+    }
+
+    /// The keys of a single-message transfer, remembering the request ids
+    /// it carries.
+    async fn unpack_transfer(&self, req: TransferKeysRequest) -> HashMap<String, StoredValue> {
+        if !req.request_ids.is_empty() {
+            let mut state = self.state.write().await;
+            for id in req.request_ids {
+                state.applied_requests.insert(id);
+            }
+        }
+        let mut metadata = req.metadata;
+        req.keys
+            .into_iter()
+            .map(|(key, value)| {
+                self.transferred_entry(PutRequest {
+                    updated_at: req.updated_at.get(&key).copied().unwrap_or(0),
+                    replication_factor: req.replication_factor.get(&key).copied().unwrap_or(0),
+                    metadata: metadata.remove(&key).map(|m| m.entries).unwrap_or_default(),
+                    key,
+                    value,
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    /// Stores keys handed over by another node, keeping any newer copy we
+    /// already have. Keys over the size limits are rejected rather than
+    /// stored, and added to `response` so the sender keeps its copy.
+    async fn store_transferred_keys(
+        &self,
+        keys: HashMap<String, StoredValue>,
+        response: &mut TransferKeysResponse,
+    ) {
+        if keys.is_empty() {
+            return;
+        }
+        info!("Node {}: Received {} keys", self.id, keys.len());
+        let mut state = self.state.write().await;
+        for (k, entry) in keys {
+            if let Err(e) = validate_put(&entry.to_put_request(k.clone())) {
+                warn!("Node {}: Rejecting a transferred key: {}", self.id, e);
+                response.rejected.push(k);
+                continue;
+            }
+            response.accepted_count += 1;
+            // A sender with an old copy (e.g. a node rejoining with stale data)
+            // must not overwrite a newer write
+            if let Some(existing) = state.store.get(&k) {
+                if existing.updated_at > entry.updated_at {
+                    debug!(
+                        "Node {}: Keeping newer version of transferred key '{}'",
+                        self.id, k
+                    );
+                    continue;
+                }
+            }
+            let _ = self
+                .changes
+                .send(change_event(ChangeOp::Replicate, &k, Some(&entry)));
+            state.store.put(k, entry);
+        }
+        self.evict_over_limit(&mut state);
+    }
+
+    /// Bulk-loads a stream of entries. Each key is routed to its owner and
+    /// the owners get their keys in batches through TransferKeys, so the
+    /// load costs one lookup per key but far fewer writes. Owners copy the
+    /// keys to their replicas on their next anti-entropy round.
+    pub async fn import_entries<S>(&self, mut entries: S) -> Result<ImportResponse, Status>
+    where
+        S: Stream<Item = Result<KeyValue, Status>> + Unpin,
+    {
+        let mut progress = ImportResponse::default();
+        let mut batches: HashMap<u64, ImportBatch> = HashMap::new();
+        while let Some(kv) = entries.next().await {
+            self.import_entry(kv?, &mut batches, &mut progress).await;
+        }
+        Ok(self.finish_import(batches, progress).await)
+    }
+
+    /// Like `import_entries`, for a stream with each value split into
+    /// chunks. Only one value is put back together at a time.
+    pub async fn import_chunks<S>(&self, mut chunks: S) -> Result<ImportResponse, Status>
+    where
+        S: Stream<Item = Result<ValueChunk, Status>> + Unpin,
+    {
+        let mut progress = ImportResponse::default();
+        let mut batches: HashMap<u64, ImportBatch> = HashMap::new();
+        let mut assembler = KeyValueAssembler::new();
+        while let Some(chunk) = chunks.next().await {
+            let entry = assembler.push(chunk?).map_err(Status::invalid_argument)?;
+            if let Some(kv) = entry {
+                self.import_entry(kv, &mut batches, &mut progress).await;
+            }
+        }
+        if let Some(kv) = assembler.finish() {
+            self.import_entry(kv, &mut batches, &mut progress).await;
+        }
+        Ok(self.finish_import(batches, progress).await)
+    }
+
+    /// Adds one imported entry to its owner's batch, sending the batch once
+    /// it is full. An entry that is invalid or has no owner counts as failed.
+    async fn import_entry(
+        &self,
+        kv: KeyValue,
+        batches: &mut HashMap<u64, ImportBatch>,
+        progress: &mut ImportResponse,
+    ) {
+        let req = PutRequest {
+            key: kv.key,
+            value: kv.value,
+            updated_at: kv.updated_at,
+            replication_factor: kv.replication_factor,
+            metadata: kv.metadata,
+            ..Default::default()
+        };
+        if let Err(e) = validate_put(&req) {
+            warn!(
+                "Node {}: Skipping imported key '{}': {}",
+                self.id, req.key, e
+            );
+            progress.failed += 1;
+            return;
+        }
+        let owner = match self.find_owner(hash_addr(&req.key)).await {
+            Ok(owner) => owner,
+            Err(e) => {
+                warn!(
+                    "Node {}: No owner found for imported key '{}': {}",
+                    self.id, req.key, e
+                );
+                progress.failed += 1;
+                return;
+            }
+        };
+
+        let entry = StoredValue {
+            value: req.value,
+            updated_at: if req.updated_at == 0 {
+                now_millis()
+            } else {
+                req.updated_at
+            },
+            replication_factor: replication_factor(
+                req.replication_factor,
+                self.replication_count,
+                self.successor_list_len,
+            ),
+            metadata: req.metadata,
+        };
+        let owner_id = owner.id;
+        let batch = batches
+            .entry(owner_id)
+            .or_insert_with(|| ImportBatch::new(owner));
+        batch.push(req.key, entry);
+        if batch.is_full() {
+            if let Some(batch) = batches.remove(&owner_id) {
+                self.flush_import_batch(batch, progress).await;
+            }
+        }
+    }
+
+    /// Sends the batches that didn't fill up.
+    async fn finish_import(
+        &self,
+        batches: HashMap<u64, ImportBatch>,
+        mut progress: ImportResponse,
+    ) -> ImportResponse {
+        for batch in batches.into_values() {
+            self.flush_import_batch(batch, &mut progress).await;
+        }
+        info!(
+            "Node {}: Import done: {} imported, {} forwarded, {} failed",
+            self.id, progress.imported, progress.forwarded, progress.failed
+        );
+        progress
+    }
+
+    async fn flush_import_batch(&self, batch: ImportBatch, progress: &mut ImportResponse) {
+        let count = batch.keys.len() as u64;
+        if batch.owner.id == self.id {
+            let mut response = TransferKeysResponse::default();
+            self.store_transferred_keys(batch.keys, &mut response).await;
+            progress.imported += response.accepted_count;
+            progress.failed += response.rejected.len() as u64;
+        } else {
+            let sent: Vec<String> = batch.keys.keys().cloned().collect();
+            match self.transfer_keys_rpc(&batch.owner, batch.keys).await {
+                Ok(response) => {
+                    let confirmed = confirmed_keys(sent, &response).len() as u64;
+                    progress.forwarded += confirmed;
+                    progress.failed += count - confirmed;
+                }
+                Err(e) => {
+                    warn!(
+                        "Node {}: Failed to import {} keys into {}: {}",
+                        self.id, count, batch.owner.id, e
+                    );
+                    progress.failed += count;
+                }
+            }
+        }
+        info!(
+            "Node {}: Import progress: {} imported, {} forwarded, {} failed",
+            self.id, progress.imported, progress.forwarded, progress.failed
+        );
+    }
+
+    /// Sends a replica to the first `count` successors that accept it. A
+    /// successor that fails is skipped and the next one in the list takes
+    /// its place, so a dead successor doesn't lower the replication factor.
+    /// Each ack counts towards the replication lag since `accepted_at`.
+    async fn replicate_to_live_successors(
+        &self,
+        candidates: Vec<NodeInfo>,
+        req: PutRequest,
+        count: usize,
+        accepted_at: Instant,
+    ) {
+        let mut replicated = 0;
+        for succ in candidates {
+            if replicated == count {
+                return;
+            }
+            debug!(
+                "Node {}: Replicating key '{}' to {}",
+                self.id, req.key, succ.id
+            );
+            let endpoint = node_url(&succ.address);
+            let req = self.put_for_peer(succ.id, req.clone()).await;
+            match self.send_replica(endpoint, req).await {
+                Ok(()) => {
+                    replicated += 1;
+                    self.replication_lag
+                        .lock()
+                        .unwrap()
+                        .record(accepted_at.elapsed());
+                }
+                Err(e) => warn!(
+                    "Node {}: Failed to replicate to {}, trying the next successor: {}",
+                    self.id, succ.id, e
+                ),
+            }
+        }
+        if replicated < count {
+            warn!(
+                "Node {}: Key '{}' only reached {} of {} replicas",
+                self.id, req.key, replicated, count
+            );
+        }
+    }
+
+    /// The first `count` successors that answer a ping, in ring order.
+    async fn live_successors(&self, successors: Vec<NodeInfo>, count: usize) -> Vec<NodeInfo> {
+        let mut live = Vec::with_capacity(count);
+        for succ in successors {
+            if live.len() == count {
+                break;
+            }
+            match self.ping_cached(node_url(&succ.address)).await {
+                Ok(()) => live.push(succ),
+                Err(e) => debug!(
+                    "Node {}: Skipping unreachable successor {} as a replica: {}",
+                    self.id, succ.id, e
+                ),
+            }
+        }
+        live
+    }
+
+    /// Routes a put to the key's owner, storing and replicating it there.
+    pub async fn put_internal(&self, mut req: PutRequest) -> Result<PutResponse, Status> {
+        // Replicated copies carry the full key
+        let (namespace, client_key) = resolve_namespace(&mut req.namespace, &mut req.key)
+            .map_err(Status::invalid_argument)?;
+        validate_put(&req).map_err(Status::invalid_argument)?;
+        let key_id = hash_addr(&req.key);
+        debug!(
+            "Node {}: Received Put request for key '{}' (ID: {})",
+            self.id, req.key, key_id
+        );
+
+        let successor = self.find_owner(key_id).await?;
+        debug!(
+            "Node {}: Successor for key '{}' is {}",
+            self.id, req.key, successor.id
+        );
+
+        if successor.id == self.id {
+            info!("Node {}: Storing key '{}' locally", self.id, req.key);
+            let entry = StoredValue {
+                replication_factor: replication_factor(
+                    req.replication_factor,
+                    self.replication_count,
+                    self.successor_list_len,
+                ),
+                metadata: req.metadata.clone(),
+                ..StoredValue::new(req.value.clone())
+            };
+            // Replicas store the resolved timestamp and factor
+            req.updated_at = entry.updated_at;
+            req.replication_factor = entry.replication_factor as u32;
+            req.visited.clear();
+            let mut state = self.state.write().await;
+            if state.draining {
+                return Err(self.draining_status(&state));
+            }
+            if let Some(status) = self.partitioned_status(&state) {
+                return Err(status);
+            }
+            if !req.request_id.is_empty() {
+                if state.applied_requests.contains(&req.request_id) {
+                    info!(
+                        "Node {}: Ignoring duplicate put '{}' for key '{}'",
+                        self.id, req.request_id, req.key
+                    );
+                    return Ok(PutResponse {
+                        success: true,
+                        owner_id: self.id,
+                    });
+                }
+                state.applied_requests.insert(req.request_id.clone());
+            }
+            self.commit_owned_write(state, req, entry);
+            Ok(PutResponse {
+                success: true,
+                owner_id: self.id,
+            })
+        } else {
+            if let Some(status) = self.forwarding_loop_status(&req.visited, &req.key) {
+                return Err(status);
+            }
+            req.visited.push(self.id);
+            debug!(
+                "Node {}: Forwarding Put for key '{}' to {}",
+                self.id, req.key, successor.id
+            );
+            req.key = client_key;
+            req.namespace = namespace;
+            let endpoint = node_url(&successor.address);
+            self.timed_rpc("put", &endpoint, async {
+                let _permit = self.forward_permit().await?;
+                let mut client = self.connect_rpc(endpoint.clone()).await?;
+                let response = if req.value.len() > VALUE_CHUNK_SIZE {
+                    client
+                        .put_stream(tokio_stream::iter(value_chunks(req)))
+                        .await?
+                } else {
+                    client.put(Request::new(req)).await?
+                };
+                Ok(response.into_inner())
+            })
+            .await
+        }
+    }
+
+    /// Stores a write we own and replicates it in the background. `req` is
+    /// what the replicas get, so it must carry the entry's resolved
+    /// timestamp and replication factor.
+    fn commit_owned_write(
+        &self,
+        mut state: tokio::sync::RwLockWriteGuard<'_, NodeState>,
+        req: PutRequest,
+        entry: StoredValue,
+    ) {
+        let accepted_at = Instant::now();
+        let replication_count = entry.replication_factor;
+        // Sending only fails when nobody is watching
+        let _ = self
+            .changes
+            .send(change_event(ChangeOp::Put, &req.key, Some(&entry)));
+        state.store.put(req.key.clone(), entry);
+        self.evict_over_limit(&mut state);
+        if replication_count == 0 {
+            return;
+        }
+
+        let candidates: Vec<NodeInfo> = state.storage_successors(self.id);
+        drop(state);
+
+        let node = self.clone();
+        tokio::spawn(async move {
+            node.replicate_to_live_successors(candidates, req, replication_count, accepted_at)
+                .await
+        });
+    }
+
+    /// Routes an append to the key's owner, which adds the item to the
+    /// key's list under the write lock and replicates the whole list.
+    pub async fn append_internal(&self, mut req: AppendRequest) -> Result<PutResponse, Status> {
+        let (namespace, client_key) = resolve_namespace(&mut req.namespace, &mut req.key)
+            .map_err(Status::invalid_argument)?;
+        let successor = self.find_owner(hash_addr(&req.key)).await?;
+        if successor.id != self.id {
+            if let Some(status) = self.forwarding_loop_status(&req.visited, &req.key) {
+                return Err(status);
+            }
+            req.visited.push(self.id);
+            debug!(
+                "Node {}: Forwarding Append for key '{}' to {}",
+                self.id, req.key, successor.id
+            );
+            req.key = client_key;
+            req.namespace = namespace;
+            let endpoint = node_url(&successor.address);
+            return self
+                .timed_rpc("append", &endpoint, async {
+                    let _permit = self.forward_permit().await?;
+                    let mut client = self.connect_rpc(endpoint.clone()).await?;
+                    Ok(client.append(Request::new(req)).await?.into_inner())
+                })
+                .await;
+        }
+
+        let mut state = self.state.write().await;
+        if state.draining {
+            return Err(self.draining_status(&state));
+        }
+        if let Some(status) = self.partitioned_status(&state) {
+            return Err(status);
+        }
+        if !req.request_id.is_empty() && state.applied_requests.contains(&req.request_id) {
+            info!(
+                "Node {}: Ignoring duplicate append '{}' for key '{}'",
+                self.id, req.request_id, req.key
+            );
+            return Ok(PutResponse {
+                success: true,
+                owner_id: self.id,
+            });
+        }
+
+        let existing = state.store.get(&req.key);
+        let mut items = match existing.as_ref().map(StoredValue::list_items) {
+            None => Vec::new(),
+            Some(Some(Ok(items))) => items,
+            Some(Some(Err(e))) => {
+                return Err(Status::data_loss(format!(
+                    "List under '{}' is corrupt: {}",
+                    req.key, e
+                )))
+            }
+            Some(None) => {
+                return Err(Status::failed_precondition(format!(
+                    "Key '{}' holds a plain value, not a list",
+                    req.key
+                )))
+            }
+        };
+        items.push(req.value);
+
+        let (replication_factor, mut metadata) = match existing {
+            Some(entry) => (entry.replication_factor, entry.metadata),
+            None => (
+                replication_factor(0, self.replication_count, self.successor_list_len),
+                HashMap::new(),
+            ),
+        };
+        metadata.insert(LIST_METADATA_KEY.to_string(), String::new());
+        let entry = StoredValue {
+            replication_factor,
+            metadata,
+            ..StoredValue::new(prost::Message::encode_to_vec(&ValueList {
+                items,
+                ..Default::default()
+            }))
+        };
+        let mut put = entry.to_put_request(req.key.clone());
+        validate_put(&put).map_err(Status::invalid_argument)?;
+        if !req.request_id.is_empty() {
+            state.applied_requests.insert(req.request_id.clone());
+        }
+        // Replicas record the id too, so a retry after failover is still a no-op
+        put.request_id = req.request_id;
+
+        info!("Node {}: Appending to list '{}'", self.id, req.key);
+        self.commit_owned_write(state, put, entry);
+        Ok(PutResponse {
+            success: true,
+            owner_id: self.id,
+        })
+    }
+
+    /// Routes a list read to the key's owner.
+    pub async fn get_list_internal(&self, mut req: GetRequest) -> Result<ValueList, Status> {
+        let (namespace, client_key) = resolve_namespace(&mut req.namespace, &mut req.key)
+            .map_err(Status::invalid_argument)?;
+        let successor = self.find_owner(hash_addr(&req.key)).await?;
+        if successor.id != self.id {
+            if let Some(status) = self.forwarding_loop_status(&req.visited, &req.key) {
+                return Err(status);
+            }
+            req.visited.push(self.id);
+            req.key = client_key;
+            req.namespace = namespace;
+            let endpoint = node_url(&successor.address);
+            return self
+                .timed_rpc("get_list", &endpoint, async {
+                    let _permit = self.forward_permit().await?;
+                    let mut client = self.connect_rpc(endpoint.clone()).await?;
+                    Ok(client.get_list(Request::new(req)).await?.into_inner())
+                })
+                .await;
+        }
+
+        let entry = self.state.read().await.store.get(&req.key);
+        let items = match entry.as_ref().map(StoredValue::list_items) {
+            None => {
+                return Ok(ValueList {
+                    owner_id: self.id,
+                    ..Default::default()
+                })
+            }
+            Some(Some(items)) => items.map_err(|e| {
+                Status::data_loss(format!("List under '{}' is corrupt: {}", req.key, e))
+            })?,
+            Some(None) => {
+                return Err(Status::failed_precondition(format!(
+                    "Key '{}' holds a plain value, not a list",
+                    req.key
+                )))
+            }
+        };
+        Ok(ValueList {
+            items,
+            found: true,
+            owner_id: self.id,
+        })
+    }
+
+    /// Refusal to forward a request that has already passed through us. It
+    /// came back around, so routing disagrees between the nodes on its path
+    /// (e.g. mid-stabilization), and forwarding again would bounce it until
+    /// something times out.
+    fn forwarding_loop_status(&self, visited: &[u64], key: &str) -> Option<Status> {
+        if !visited.contains(&self.id) {
+            return None;
+        }
+        warn!(
+            "Node {}: Request for key '{}' came back to us via {:?}",
+            self.id, key, visited
+        );
+        Some(Status::aborted(format!(
+            "Forwarding loop for key '{}' through nodes {:?}",
+            key, visited
+        )))
+    }
+
+    /// Refusal for a write we own while draining, pointing the caller at the
+    /// successor that is taking our keys over.
+    fn draining_status(&self, state: &NodeState) -> Status {
+        let successor = state
+            .successor_list
+            .iter()
+            .find(|s| s.id != self.id && !s.observer);
+        let mut status = Status::unavailable(match successor {
+            Some(s) => format!(
+                "Node {} is draining; send writes to its successor {}",
+                self.id, s.address
+            ),
+            None => format!("Node {} is draining", self.id),
+        });
+        if let Some(addr) = successor.and_then(|s| s.address.parse().ok()) {
+            status.metadata_mut().insert(REDIRECT_METADATA_KEY, addr);
+        }
+        status
+    }
+
+    /// Refusal for a write while we're cut off from every successor we used
+    /// to have. A node that was always alone is a real single-node ring, but
+    /// one that lost its successors is more likely partitioned: a write it
+    /// took couldn't be replicated, and the rest of the ring wouldn't see it.
+    fn partitioned_status(&self, state: &NodeState) -> Option<Status> {
+        let alone = state.successor_list.iter().all(|s| s.id == self.id);
+        if !alone || state.last_known_successors.is_empty() {
+            return None;
+        }
+        let last_known: Vec<String> = state
+            .last_known_successors
+            .iter()
+            .map(|s| s.id.to_string())
+            .collect();
+        warn!(
+            "Node {}: Refusing write, no successor reachable (last known: {})",
+            self.id,
+            last_known.join(", ")
+        );
+        Some(Status::unavailable(format!(
+            "Node {} can't reach any of its successors ({}); refusing the write",
+            self.id,
+            last_known.join(", ")
+        )))
+    }
+
+    /// Why an observer refuses to store replicas or transferred keys: it
+    /// holds no data, so whatever a confused peer sends would be lost.
+    fn observer_status(&self) -> Option<Status> {
+        if !self.observer {
+            return None;
+        }
+        warn!("Node {}: Refusing keys sent to an observer", self.id);
+        Some(Status::failed_precondition(format!(
+            "Node {} is an observer and stores no keys",
+            self.id
+        )))
+    }
+
+    /// The answer to a get for a key we hold, leaving out the value if it
+    /// hasn't changed since the caller's copy.
+    fn found_response(&self, req: &GetRequest, entry: StoredValue) -> GetResponse {
+        if req.since != 0 && entry.updated_at <= req.since {
+            debug!(
+                "Node {}: Key '{}' not modified since {}",
+                self.id, req.key, req.since
+            );
+            return GetResponse {
+                found: true,
+                not_modified: true,
+                updated_at: entry.updated_at,
+                owner_id: self.id,
+                ..Default::default()
+            };
+        }
+        info!("Node {}: Found key '{}'", self.id, req.key);
+        GetResponse {
+            value: entry.value,
+            found: true,
+            updated_at: entry.updated_at,
+            metadata: entry.metadata,
+            owner_id: self.id,
+            ..Default::default()
+        }
+    }
+
+    pub async fn get_internal(&self, mut req: GetRequest) -> Result<GetResponse, Status> {
+        let (namespace, client_key) = resolve_namespace(&mut req.namespace, &mut req.key)
+            .map_err(Status::invalid_argument)?;
+        let key_id = hash_addr(&req.key);
+        debug!(
+            "Node {}: Received Get request for key '{}' (ID: {})",
+            self.id, req.key, key_id
+        );
+
+        if req.allow_stale {
+            let local = {
+                let state = self.state.read().await;
+                let pred_id = state.owned_start(self.id);
+                let is_replica = !is_in_range_inclusive(key_id, pred_id, self.id);
+                state.store.get(&req.key).map(|entry| (entry, is_replica))
+            };
+            if let Some((entry, is_replica)) = local {
+                debug!(
+                    "Node {}: Serving key '{}' from our own copy (replica: {})",
+                    self.id, req.key, is_replica
+                );
+                let mut response = self.found_response(&req, entry);
+                response.is_replica = is_replica;
+                return Ok(response);
+            }
+        }
+
+        let successor = self.find_owner(key_id).await?;
+        debug!(
+            "Node {}: Successor for key '{}' is {}",
+            self.id, req.key, successor.id
+        );
+
+        if successor.id == self.id {
+            debug!("Node {}: Looking up key '{}' locally", self.id, req.key);
+            let entry = self.state.read().await.store.get(&req.key);
+            if let Some(entry) = entry {
+                if READ_REPAIR_ENABLED {
+                    let node = self.clone();
+                    let key = req.key.clone();
+                    let entry = entry.clone();
+                    tokio::spawn(async move { node.read_repair(key, entry).await });
+                }
+                Ok(self.found_response(&req, entry))
+            } else {
+                info!("Node {}: Key '{}' not found", self.id, req.key);
+                Ok(GetResponse {
+                    owner_id: self.id,
+                    ..Default::default()
+                })
+            }
+        } else {
+            if let Some(status) = self.forwarding_loop_status(&req.visited, &req.key) {
+                return Err(status);
+            }
+            req.visited.push(self.id);
+            debug!(
+                "Node {}: Forwarding Get for key '{}' to {}",
+                self.id, req.key, successor.id
+            );
+            req.key = client_key;
+            req.namespace = namespace;
+            let endpoint = node_url(&successor.address);
+            self.timed_rpc("get", &endpoint, async {
+                let _permit = self.forward_permit().await?;
+                let mut client = self.connect_rpc(endpoint.clone()).await?;
+                let response = client.get(Request::new(req)).await?;
+                Ok(response.into_inner())
+            })
+            .await
+        }
+    }
+
+    /// Compares a key's version on each replica and pushes the value to any
+    /// replica that is missing it or holds an older write.
+    pub async fn read_repair(&self, key: String, entry: StoredValue) {
+        let replicas: Vec<NodeInfo> = {
+            let state = self.state.read().await;
+            let mut successors = state.storage_successors(self.id);
+            successors.truncate(entry.replication_factor);
+            successors
+        };
+
+        for replica in replicas {
+            let endpoint = node_url(&replica.address);
+            let version = match self.connect_rpc(endpoint.clone()).await {
+                Ok(mut client) => {
+                    client
+                        .get_replica_version(Request::new(GetRequest {
+                            key: key.clone(),
+                            ..Default::default()
+                        }))
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+
+            match version {
+                Ok(version) => {
+                    let version = version.into_inner();
+                    if version.found && version.updated_at >= entry.updated_at {
+                        continue;
+                    }
+                    info!(
+                        "Node {}: Read repair pushing key '{}' to lagging replica {}",
+                        self.id, key, replica.id
+                    );
+                    let req = self
+                        .put_for_peer(replica.id, entry.to_put_request(key.clone()))
+                        .await;
+                    if let Err(e) = self.send_replica(endpoint, req).await {
+                        warn!(
+                            "Node {}: Read repair to {} failed: {}",
+                            self.id, replica.id, e
+                        );
+                    }
+                }
+                Err(e) => {
+                    debug!(
+                        "Node {}: Read repair could not check replica {}: {}",
+                        self.id, replica.id, e
+                    );
+                }
+            }
+        }
+    }
+
+    /// Locates a key: its primary, plus every node in the primary's successor
+    /// list that currently holds a copy, checked one by one.
+    pub async fn replica_locations(&self, key: &str) -> Result<ReplicaLocations, Status> {
+        let primary = self.find_owner(hash_addr(key)).await?;
+        let primary_addr = node_url(&primary.address);
+        let primary_found = self
+            .replica_version_rpc(primary_addr.clone(), key.to_string())
+            .await?
+            .found;
+        let successors = self.get_successor_list_rpc(primary_addr).await?.successors;
+
+        let mut replicas = Vec::new();
+        for succ in successors {
+            if succ.id == primary.id {
+                continue;
+            }
+            let addr = node_url(&succ.address);
+            match self.replica_version_rpc(addr, key.to_string()).await {
+                Ok(version) if version.found => replicas.push(succ),
+                Ok(_) => {}
+                Err(e) => debug!(
+                    "Node {}: Could not check replica {} for key '{}': {}",
+                    self.id, succ.id, key, e
+                ),
+            }
+        }
+
+        Ok(ReplicaLocations {
+            primary: Some(primary),
+            primary_found,
+            replicas,
+        })
+    }
+
+    async fn store_replica(&self, req: PutRequest) {
+        debug!("Node {}: Replicating key '{}'", self.id, req.key);
+        let entry = StoredValue {
+            value: req.value,
+            updated_at: if req.updated_at == 0 {
+                now_millis()
+            } else {
+                req.updated_at
+            },
+            replication_factor: replication_factor(
+                req.replication_factor,
+                self.replication_count,
+                self.successor_list_len,
+            ),
+            metadata: req.metadata,
+        };
+        let mut state = self.state.write().await;
+        // If the owner fails, a retry of this write lands here
+        if !req.request_id.is_empty() {
+            state.applied_requests.insert(req.request_id);
+        }
+        let _ = self
+            .changes
+            .send(change_event(ChangeOp::Replicate, &req.key, Some(&entry)));
+        state.store.put(req.key, entry);
+        self.evict_over_limit(&mut state);
+    }
+
+    /// Lets a size-bounded store drop keys after a write, telling watchers.
+    fn evict_over_limit(&self, state: &mut NodeState) {
+        let pred_id = state.owned_start(self.id);
+        let is_primary = |key: &str| is_in_range_inclusive(hash_addr(key), pred_id, self.id);
+        for key in state.store.evict(&is_primary) {
+            debug!("Node {}: Evicted key '{}'", self.id, key);
+            let _ = self
+                .changes
+                .send(change_event(ChangeOp::Delete, &key, None));
+        }
+    }
+
+    /// Deletes every key whose id falls in `(start_id, end_id]` by walking the
+    /// interval's owners along the ring. Returns the number of keys removed.
+    pub async fn delete_range_internal(&self, start_id: u64, end_id: u64) -> Result<u64, Status> {
+        self.delete_matching(start_id, end_id, "").await
+    }
+
+    /// Like `delete_range_internal`, but only keys starting with `prefix`
+    /// are removed. An empty prefix matches every key.
+    async fn delete_matching(
+        &self,
+        start_id: u64,
+        end_id: u64,
+        prefix: &str,
+    ) -> Result<u64, Status> {
+        let first = self
+            .find_successor_internal(start_id.wrapping_add(1))
+            .await?;
+        let mut owner = first.clone();
+        let mut deleted = 0;
+
+        loop {
+            let owner_addr = node_url(&owner.address);
+            deleted += self
+                .delete_local_range_rpc(owner_addr.clone(), start_id, end_id, true, prefix)
+                .await?;
+
+            // The first owner at or past end_id holds the last keys of the interval
+            if start_id != end_id && is_in_range_inclusive(end_id, start_id, owner.id) {
+                break;
+            }
+            owner = self.get_successor_rpc(owner_addr).await?;
+            if owner.id == first.id {
+                break;
+            }
+        }
+
+        info!(
+            "Node {}: Deleted {} keys in ({}, {}]",
+            self.id, deleted, start_id, end_id
+        );
+        Ok(deleted)
+    }
+
+    /// Deletes every key in `namespace` from its owners and their replicas,
+    /// asking each owner once for all of its keys. Returns how many keys
+    /// were removed.
+    pub async fn delete_namespace_internal(&self, namespace: &str) -> Result<u64, Status> {
+        if namespace.is_empty() {
+            return Err(Status::invalid_argument(
+                "The default namespace can't be deleted",
+            ));
+        }
+        let prefix = namespaced_key(namespace, "").map_err(Status::invalid_argument)?;
+        // The whole ring
+        let deleted = self.delete_matching(self.id, self.id, &prefix).await?;
+        info!(
+            "Node {}: Deleted {} keys in namespace '{}'",
+            self.id, deleted, namespace
+        );
+        Ok(deleted)
+    }
+
+    /// Lists every key starting with `prefix`. Keys are spread over the ring
+    /// by hash, so this asks each node in turn along the successor chain for
+    /// the matching keys it is primary for.
+    pub async fn scan_prefix(&self, prefix: &str) -> Result<Vec<String>, Status> {
+        // A set, since a node without a predecessor yet also reports its replicas
+        let mut keys: BTreeSet<String> = self.scan_local_prefix(prefix).await.into_iter().collect();
+        let mut visited = HashSet::from([self.id]);
+        let mut owner = self.successor().await;
+
+        while visited.insert(owner.id) {
+            let owner_addr = node_url(&owner.address);
+            keys.extend(
+                self.scan_local_prefix_rpc(owner_addr.clone(), prefix.to_string())
+                    .await?,
+            );
+            owner = self.get_successor_rpc(owner_addr).await?;
+        }
+
+        debug!(
+            "Node {}: Scanned {} nodes, {} keys match '{}'",
+            self.id,
+            visited.len(),
+            keys.len(),
+            prefix
+        );
+        Ok(keys.into_iter().collect())
+    }
+
+    /// Streams every key in the ring into `tx` as value chunks (see
+    /// `chord_proto::chunk`), walking the successor chain from us and asking
+    /// each node for the keys it is primary for. The walk ends once it comes
+    /// back around to a node it has already visited, and stops early if the
+    /// receiver goes away. A failure is sent as the last item.
+    pub async fn export_ring(&self, tx: mpsc::Sender<Result<ValueChunk, Status>>) {
+        match self.walk_export(&tx).await {
+            Ok(exported) => info!("Node {}: Exported {} keys", self.id, exported),
+            Err(e) => {
+                warn!("Node {}: Export failed: {}", self.id, e);
+                let _ = tx.send(Err(e)).await;
+            }
+        }
+    }
+
+    async fn walk_export(
+        &self,
+        tx: &mpsc::Sender<Result<ValueChunk, Status>>,
+    ) -> Result<u64, Status> {
+        // A node without a predecessor yet also reports its replicas, so
+        // the same key can come from two nodes
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut exported = 0;
+        for key in self.owned_keys().await {
+            let Some(entry) = self.state.read().await.store.get(&key) else {
+                continue;
+            };
+            seen.insert(key.clone());
+            for chunk in key_value_chunks(entry.to_key_value(key)) {
+                if tx.send(Ok(chunk)).await.is_err() {
+                    return Ok(exported);
+                }
+            }
+            exported += 1;
+        }
+
+        let mut visited = HashSet::from([self.id]);
+        let mut owner = self.successor().await;
+        while visited.insert(owner.id) {
+            let owner_addr = node_url(&owner.address);
+            let mut client = self.connect_rpc(owner_addr.clone()).await?;
+            if self
+                .peer_speaks(&owner, CHUNKED_EXPORT_PROTOCOL_VERSION)
+                .await
+            {
+                let mut chunks = client
+                    .export_local_chunks(Request::new(Empty {}))
+                    .await?
+                    .into_inner();
+                // Chunks are passed on as they come, skipping every chunk
+                // of a key that was already exported
+                let mut skipping = false;
+                while let Some(chunk) = chunks.message().await? {
+                    if chunk.found {
+                        skipping = !seen.insert(chunk.key.clone());
+                        if !skipping {
+                            exported += 1;
+                        }
+                    }
+                    if !skipping && tx.send(Ok(chunk)).await.is_err() {
+                        return Ok(exported);
+                    }
+                }
+            } else {
+                let mut entries = client
+                    .export_local(Request::new(Empty {}))
+                    .await?
+                    .into_inner();
+                while let Some(kv) = entries.message().await? {
+                    if !seen.insert(kv.key.clone()) {
+                        continue;
+                    }
+                    for chunk in key_value_chunks(kv) {
+                        if tx.send(Ok(chunk)).await.is_err() {
+                            return Ok(exported);
+                        }
+                    }
+                    exported += 1;
+                }
+            }
+            owner = self.get_successor_rpc(owner_addr).await?;
+        }
+        Ok(exported)
+    }
+
+    /// How many keys each node in the ring is primary for, found by walking
+    /// the successor chain from us until it comes back around, plus how
+    /// skewed those counts are. Observers are left out.
+    pub async fn distribution(&self) -> Result<DistributionResponse, Status> {
+        let mut loads = Vec::new();
+        if !self.observer {
+            loads.push(NodeLoad {
+                node: Some(self.self_info()),
+                primary_keys: self.stats().await.primary_keys,
+            });
+        }
+
+        let mut visited = HashSet::from([self.id]);
+        let mut node = self.successor().await;
+        while visited.insert(node.id) {
+            let addr = node_url(&node.address);
+            if !node.observer {
+                let stats = self.get_stats_rpc(addr.clone()).await?;
+                loads.push(NodeLoad {
+                    node: Some(node.clone()),
+                    primary_keys: stats.primary_keys,
+                });
+            }
+            node = self.get_successor_rpc(addr).await?;
+        }
+
+        loads.sort_unstable_by_key(|load| load.node.as_ref().map_or(0, |n| n.id));
+        let counts: Vec<u64> = loads.iter().map(|load| load.primary_keys).collect();
+        Ok(DistributionResponse {
+            gini: distribution::gini(&counts),
+            max_to_mean: distribution::max_to_mean(&counts),
+            nodes: loads,
+        })
+    }
+
+    /// Health and store size of each node, walking the successor chain
+    /// from us until it comes back around. The walk gives up at the first
+    /// node that doesn't answer, since we can't learn its successor, after
+    /// `max_nodes` nodes, and on reaching a node it saw before other than
+    /// us (the chain loops without passing through us); in all of those
+    /// cases the result isn't complete.
+    pub async fn cluster_health(&self, max_nodes: usize) -> ClusterHealthResponse {
+        let mut nodes = vec![NodeHealth {
+            node: Some(self.self_info()),
+            state: self.health().await as i32,
+            store_size: self.state.read().await.store.len() as u64,
+            reachable: true,
+        }];
+        let mut visited = HashSet::from([self.id]);
+        let mut next = self.successor().await;
+        let complete = loop {
+            if next.id == self.id {
+                break true;
+            }
+            if visited.contains(&next.id) {
+                debug!(
+                    "Node {}: Health walk looped back to {} without reaching us",
+                    self.id, next.id
+                );
+                break false;
+            }
+            if nodes.len() >= max_nodes {
+                break false;
+            }
+            visited.insert(next.id);
+            let addr = node_url(&next.address);
+            let health = match self.health_rpc(addr.clone()).await {
+                Ok(health) => health,
+                Err(e) => {
+                    debug!(
+                        "Node {}: Health walk stopped at unreachable node {}: {}",
+                        self.id, next.id, e
+                    );
+                    nodes.push(NodeHealth {
+                        node: Some(next),
+                        reachable: false,
+                        ..Default::default()
+                    });
+                    break false;
+                }
+            };
+            let store_size = self
+                .get_stats_rpc(addr.clone())
+                .await
+                .map_or(0, |stats| stats.store_size);
+            nodes.push(NodeHealth {
+                node: Some(next.clone()),
+                state: health.state,
+                store_size,
+                reachable: true,
+            });
+            next = match self.get_successor_rpc(addr).await {
+                Ok(successor) => successor,
+                Err(_) => break false,
+            };
+        };
+        ClusterHealthResponse { nodes, complete }
+    }
+
+    /// Every key we are primary for, in key order.
+    pub async fn owned_keys(&self) -> Vec<String> {
+        let state = self.state.read().await;
+        let pred_id = state.owned_start(self.id);
+        let mut keys: Vec<String> = state
+            .store
+            .keys()
+            .into_iter()
+            .filter(|key| is_in_range_inclusive(hash_addr(key), pred_id, self.id))
+            .collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    /// Sends every key we are primary for into `tx`, in key order, as the
+    /// items `encode` turns it into. Values are read from the store one key
+    /// at a time, as the receiver keeps up, rather than copied all at once;
+    /// a key deleted in the meantime is left out.
+    async fn export_local_into<T>(
+        &self,
+        tx: mpsc::Sender<Result<T, Status>>,
+        encode: impl Fn(KeyValue) -> Vec<T>,
+    ) {
+        for key in self.owned_keys().await {
+            let Some(entry) = self.state.read().await.store.get(&key) else {
+                continue;
+            };
+            for item in encode(entry.to_key_value(key)) {
+                if tx.send(Ok(item)).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Keys starting with `prefix` that we are primary for.
+    pub async fn scan_local_prefix(&self, prefix: &str) -> Vec<String> {
+        let state = self.state.read().await;
+        let pred_id = state.owned_start(self.id);
+        state
+            .store
+            .keys()
+            .into_iter()
+            .filter(|key| {
+                key.starts_with(prefix) && is_in_range_inclusive(hash_addr(key), pred_id, self.id)
+            })
+            .collect()
+    }
+
+    /// Removes locally stored keys in `(start_id, end_id]` that start with
+    /// `prefix`, forwarding the deletion to our successors when `replicate`
+    /// is set. Only keys we are primary for are counted, so replica copies
+    /// aren't counted twice. A replica-side call leaves our own primary keys
+    /// alone; the walk deletes (and counts) those when it reaches us.
+    pub async fn delete_local_range(
+        &self,
+        start_id: u64,
+        end_id: u64,
+        replicate: bool,
+        prefix: &str,
+    ) -> u64 {
+        let mut state = self.state.write().await;
+        let pred_id = state.owned_start(self.id);
+        let mut owned = 0;
+        for key in state.store.keys() {
+            let key_id = hash_addr(&key);
+            if !key.starts_with(prefix) || !is_in_range_inclusive(key_id, start_id, end_id) {
+                continue;
+            }
+            if is_in_range_inclusive(key_id, pred_id, self.id) {
+                if !replicate {
+                    continue;
+                }
+                owned += 1;
+            }
+            state.store.delete(&key);
+            let _ = self
+                .changes
+                .send(change_event(ChangeOp::Delete, &key, None));
+        }
+        let successors: Vec<_> = state.storage_successors(self.id);
+        drop(state);
+
+        if replicate {
+            for succ in successors {
+                let addr = node_url(&succ.address);
+                if let Err(e) = self
+                    .delete_local_range_rpc(addr, start_id, end_id, false, prefix)
+                    .await
+                {
+                    warn!(
+                        "Node {}: Failed to delete range on replica {}: {}",
+                        self.id, succ.id, e
+                    );
+                }
+            }
+        }
+
+        owned
+    }
+
+    // RPC Helpers
+    /// `max_hops` of 0 leaves the limit to the receiver.
+    async fn find_successor_rpc(
+        &self,
+        addr: String,
+        id: u64,
+        max_hops: u32,
+        dry_run: bool,
+    ) -> Result<NodeInfo, Status> {
+        let call = async {
+            let _permit = self.forward_permit().await?;
+            let mut client = self.connect_rpc(addr.clone()).await?;
+            let request = Request::new(FindSuccessorRequest {
+                id,
+                max_hops,
+                dry_run,
+            });
+            let response = client.find_successor(request).await?;
+            Ok(response.into_inner())
+        };
+        self.observe_rpc("find_successor", &addr, !dry_run, call)
+            .await
+    }
+
+    async fn find_successor_traced_rpc(
+        &self,
+        addr: String,
+        id: u64,
+        path: Vec<NodeInfo>,
+    ) -> Result<TracedLookupResponse, Status> {
+        self.timed_rpc("find_successor_traced", &addr, async {
+            let _permit = self.forward_permit().await?;
+            let mut client = self.connect_rpc(addr.clone()).await?;
+            let request = Request::new(TracedLookupRequest { id, path });
+            let response = client.find_successor_traced(request).await?;
+            Ok(response.into_inner())
+        })
+        .await
+    }
+
+    async fn health_rpc(&self, addr: String) -> Result<HealthResponse, Status> {
+        self.timed_rpc("health", &addr, async {
+            let mut client = self.connect_rpc(addr.clone()).await?;
+            let response = client.health(Request::new(Empty {})).await?;
+            Ok(response.into_inner())
+        })
+        .await
+    }
+
+    async fn get_stats_rpc(&self, addr: String) -> Result<NodeStats, Status> {
+        self.timed_rpc("get_stats", &addr, async {
+            let mut client = self.connect_rpc(addr.clone()).await?;
+            let response = client.get_stats(Request::new(Empty {})).await?;
+            Ok(response.into_inner())
+        })
+        .await
+    }
+
+    async fn get_successor_rpc(&self, addr: String) -> Result<NodeInfo, Status> {
+        self.timed_rpc("get_successor", &addr, async {
+            let mut client = self.connect_rpc(addr.clone()).await?;
+            let response = client.get_successor(Request::new(Empty {})).await?;
+            Ok(response.into_inner())
+        })
+        .await
+    }
+
+    /// A prefix goes in its own RPC, which peers that predate it refuse
+    /// rather than deleting the whole range.
+    async fn delete_local_range_rpc(
+        &self,
+        addr: String,
+        start_id: u64,
+        end_id: u64,
+        replicate: bool,
+        prefix: &str,
+    ) -> Result<u64, Status> {
+        self.timed_rpc("delete_local_range", &addr, async {
+            let mut client = self.connect_rpc(addr.clone()).await?;
+            let range = LocalDeleteRangeRequest {
+                start_id,
+                end_id,
+                replicate,
+            };
+            let response = if prefix.is_empty() {
+                client.delete_local_range(Request::new(range)).await?
+            } else {
+                let request = Request::new(LocalDeletePrefixRequest {
+                    prefix: prefix.to_string(),
+                    range: Some(range),
+                });
+                client.delete_local_prefix(request).await?
+            };
+            Ok(response.into_inner().deleted)
+        })
+        .await
+    }
+
+    async fn scan_local_prefix_rpc(
+        &self,
+        addr: String,
+        prefix: String,
+    ) -> Result<Vec<String>, Status> {
+        self.timed_rpc("scan_local_prefix", &addr, async {
+            let mut client = self.connect_rpc(addr.clone()).await?;
+            let request = Request::new(ScanPrefixRequest { prefix });
+            let response = client.scan_local_prefix(request).await?;
+            Ok(response.into_inner().keys)
+        })
+        .await
+    }
+
+    async fn find_predecessor_rpc(&self, addr: String, id: u64) -> Result<NodeInfo, Status> {
+        self.timed_rpc("find_predecessor", &addr, async {
+            let _permit = self.forward_permit().await?;
+            let mut client = self.connect_rpc(addr.clone()).await?;
+            let request = Request::new(FindPredecessorRequest { id });
+            let response = client.find_predecessor(request).await?;
+            Ok(response.into_inner())
+        })
+        .await
+    }
+
+    async fn get_predecessor_rpc(&self, addr: String) -> Result<NodeInfo, Status> {
+        self.timed_rpc("get_predecessor", &addr, async {
+            let mut client = self.connect_rpc(addr.clone()).await?;
+            let request = Request::new(Empty {});
+            let response = client.get_predecessor(request).await?;
+            Ok(response.into_inner())
+        })
+        .await
+    }
+
+    async fn owned_range_rpc(&self, addr: String) -> Result<IdRange, Status> {
+        self.timed_rpc("owned_range", &addr, async {
+            let mut client = self.connect_rpc(addr.clone()).await?;
+            let response = client.owned_range(Request::new(Empty {})).await?;
+            Ok(response.into_inner())
+        })
+        .await
+    }
+
+    async fn notify_rpc(&self, addr: String, node: NodeInfo) -> Result<(), Status> {
+        self.timed_rpc("notify", &addr, async {
+            let mut client = self.connect_rpc(addr.clone()).await?;
+            let request = Request::new(node);
+            client.notify(request).await?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn replica_version_rpc(
+        &self,
+        addr: String,
+        key: String,
+    ) -> Result<ReplicaVersion, Status> {
+        self.timed_rpc("replica_version", &addr, async {
+            let mut client = self.connect_rpc(addr.clone()).await?;
+            let request = Request::new(GetRequest {
+                key,
+                ..Default::default()
+            });
+            let response = client.get_replica_version(request).await?;
+            Ok(response.into_inner())
+        })
+        .await
+    }
+
+    async fn sync_digest_rpc(
+        &self,
+        addr: String,
+        request: SyncDigestRequest,
+    ) -> Result<SyncDigestResponse, Status> {
+        self.timed_rpc("sync_digest", &addr, async {
+            let mut client = self.connect_rpc(addr.clone()).await?;
+            let response = client.sync_digest(Request::new(request)).await?;
+            Ok(response.into_inner())
+        })
+        .await
+    }
+
+    async fn departing_rpc(&self, addr: String) -> Result<(), Status> {
+        self.timed_rpc("departing", &addr, async {
+            let mut client = self.connect_rpc(addr.clone()).await?;
+            client.departing(Request::new(self.self_info())).await?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn ping_rpc(&self, addr: String) -> Result<(), Status> {
+        let result = self
+            .timed_rpc("ping", &addr, async {
+                let mut client = self.connect_rpc(addr.clone()).await?;
+                client.ping(Request::new(Empty {})).await?;
+                Ok(())
+            })
+            .await;
+        self.liveness.lock().unwrap().record(&addr, result.is_ok());
+        result
     }
 
-    fn is_in_range(id: u64, start: u64, end: u64) -> bool {
-        if start < end {
-            id > start && id < end
-        } else {
-            id > start || id < end
+    /// Like `ping_rpc`, but reuses the result of a recent ping to `addr`.
+    async fn ping_cached(&self, addr: String) -> Result<(), Status> {
+        let cached = self.liveness.lock().unwrap().get(&addr);
+        match cached {
+            Some(true) => Ok(()),
+            Some(false) => Err(Status::unavailable(format!(
+                "{} did not answer a recent ping",
+                addr
+            ))),
+            None => self.ping_rpc(addr).await,
         }
     }
 
-    pub fn is_in_range_inclusive(id: u64, start: u64, end: u64) -> bool {
-        if start < end {
-            id > start && id <= end
-        } else {
-            id > start || id <= end
-        }
+    async fn hello_rpc(&self, addr: String) -> Result<Handshake, Status> {
+        self.timed_rpc("hello", &addr, async {
+            let mut client = self.connect_rpc(addr.clone()).await?;
+            let request = Request::new(Handshake {
+                version: PROTOCOL_VERSION,
+                min_version: MIN_PROTOCOL_VERSION,
+                node: Some(self.self_info()),
+            });
+            let response = client.hello(request).await?;
+            Ok(response.into_inner())
+        })
+        .await
     }
 
-    pub async fn find_successor_internal(&self, id: u64) -> Result<NodeInfo, Status> {
+    async fn get_successor_list_rpc(&self, addr: String) -> Result<SuccessorList, Status> {
+        self.timed_rpc("get_successor_list", &addr, async {
+            let mut client = self.connect_rpc(addr.clone()).await?;
+            let request = Request::new(Empty {});
+            let response = client.get_successor_list(request).await?;
+            Ok(response.into_inner())
+        })
+        .await
+    }
+
+    /// Snapshot of this node's routing state, as reported to the monitor.
+    pub async fn snapshot(&self) -> ProtoNodeState {
+        let stats = self.stats().await;
         let state = self.state.read().await;
-        let successor = state
-            .successor_list
-            .first()
+        let pred_id = state.owned_start(self.id);
+        let stored_keys = state.store.keys();
+        let (primary_keys, replica_keys) = stored_keys
+            .iter()
             .cloned()
-            .expect("Successor list should never be empty");
-
-        if Self::is_in_range_inclusive(id, self.id, successor.id) {
-            return Ok(successor);
-        }
-        drop(state);
-
-        // Get all unique candidates from finger table that are strictly closer to id
-        // We want to try the closest ones first.
-        let candidates = self.get_closest_candidates(id).await;
+            .partition(|key| is_in_range_inclusive(hash_addr(key), pred_id, self.id));
 
-        if candidates.is_empty() {
-            // If no candidates, fall back to successor
-            let state = self.state.read().await;
-            return Ok(state.successor_list[0].clone());
+        ProtoNodeState {
+            id: self.id,
+            address: self.addr.clone(),
+            predecessor: state.predecessor.clone(),
+            successors: state.successor_list.clone(),
+            finger_table: state.finger_table.clone(),
+            stored_keys,
+            primary_keys,
+            replica_keys,
+            stats: Some(stats),
+            uptime_seconds: self.started_at.elapsed().as_secs(),
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            observer: self.observer,
+            overloaded: self
+                .max_keys_soft
+                .is_some_and(|limit| state.store.len() > limit),
         }
+    }
 
-        for candidate in candidates {
-            if candidate.id == self.id {
-                continue;
-            }
+    /// Sends a snapshot to the monitor, but only if it differs from the last
+    /// one sent or `heartbeat` has passed since then, so stable nodes don't
+    /// keep re-sending the same state. While the monitor is unreachable,
+    /// reports are skipped with backoff.
+    pub async fn report_to_monitor(&self, monitor_addr: String, heartbeat: Duration) {
+        let node_state = self.snapshot().await;
+        let fingerprint = Self::report_fingerprint(&node_state);
 
-            let client_addr = format!("http://{}", candidate.address);
-            match self.find_successor_rpc(client_addr, id).await {
-                Ok(info) => return Ok(info),
-                Err(e) => {
-                    warn!(
-                        "Node {}: Failed to contact candidate {} ({}) for id {}: {}",
-                        self.id, candidate.id, candidate.address, id, e
-                    );
-                }
+        if let Some((last, sent_at)) = self.state.read().await.last_report {
+            if last == fingerprint && sent_at.elapsed() < heartbeat {
+                return;
             }
         }
 
-        // If all fingers failed, try successor list as fallback
-        // This helps if the best finger (likely immediate successor) is dead.
-        // We try to find *any* live node in our successor list to forward the query to.
-        // Even if they are not strictly "closest preceding", they are better than failing.
-        // And in a small ring, they are likely the next best hop.
-        let state = self.state.read().await;
-        let successors = state.successor_list.clone();
-        drop(state);
-
-        for succ in successors {
-            // Skip if we already tried it (it was in candidates)
-            if succ.id == self.id {
-                continue;
+        let Some(mut client) = self.monitor.lock().unwrap().client(&monitor_addr) else {
+            return;
+        };
+        // Only a delivered report counts as sent
+        match client.report_state(Request::new(node_state)).await {
+            Ok(_) => {
+                self.monitor.lock().unwrap().succeeded();
+                self.state.write().await.last_report = Some((fingerprint, Instant::now()));
             }
-
-            let client_addr = format!("http://{}", succ.address);
-            debug!(
-                "Node {}: Fallback: trying successor {} for id {}",
-                self.id, succ.id, id
-            );
-            match self.find_successor_rpc(client_addr, id).await {
-                Ok(info) => return Ok(info),
-                Err(e) => {
+            Err(e) => {
+                let mut monitor = self.monitor.lock().unwrap();
+                let backoff = monitor.failed();
+                // Only the first failure of an outage is worth a warning
+                if monitor.failures() == 1 {
                     warn!(
-                        "Node {}: Fallback successor {} failed: {}",
-                        self.id, succ.id, e
+                        "Node {}: Monitor {} unreachable ({}), retrying in {}ms",
+                        self.id,
+                        monitor_addr,
+                        e.message(),
+                        backoff.as_millis()
+                    );
+                } else {
+                    debug!(
+                        "Node {}: Monitor still unreachable, retrying in {}ms",
+                        self.id,
+                        backoff.as_millis()
                     );
                 }
             }
         }
+    }
 
-        Err(Status::unavailable("All candidates and successors failed"))
+    /// Monitor reports that failed in a row; 0 once one gets through.
+    pub fn monitor_failures(&self) -> u32 {
+        self.monitor.lock().unwrap().failures()
     }
 
-    async fn get_closest_candidates(&self, id: u64) -> Vec<NodeInfo> {
-        let state = self.state.read().await;
-        let mut candidates = Vec::new();
+    /// Hash of a snapshot, ignoring the stats that tick on their own (uptime,
+    /// finger age) or with every request (latencies) and the order keys came
+    /// out of the store in.
+    fn report_fingerprint(node_state: &ProtoNodeState) -> u64 {
+        use prost::Message;
+        use std::hash::{DefaultHasher, Hash, Hasher};
 
-        // Collect valid fingers
-        for i in (0..FINGER_TABLE_SIZE).rev() {
-            let finger = &state.finger_table[i];
-            if finger.address.is_empty() {
-                continue;
-            }
-            if Self::is_in_range(finger.id, self.id, id) {
-                candidates.push(finger.clone());
-            }
+        let mut node_state = node_state.clone();
+        node_state.stored_keys.sort_unstable();
+        node_state.primary_keys.sort_unstable();
+        node_state.replica_keys.sort_unstable();
+        node_state.uptime_seconds = 0;
+        if let Some(stats) = node_state.stats.as_mut() {
+            stats.uptime_ms = 0;
+            stats.op_latencies.clear();
+            stats.stalest_finger_age_ms = 0;
         }
-
-        // Sort by ID to approximate closeness
-        candidates.sort_by(|a, b| b.id.cmp(&a.id));
-        candidates.dedup_by(|a, b| a.id == b.id);
-
-        candidates
+        let mut hasher = DefaultHasher::new();
+        node_state.encode_to_vec().hash(&mut hasher);
+        hasher.finish()
     }
 
-    pub async fn join(&self, join_addr: String) -> Result<(), Box<dyn std::error::Error>> {
-        let join_addr = format!("http://{}", join_addr);
-        let info = self.find_successor_rpc(join_addr, self.id).await?;
-
-        let mut state = self.state.write().await;
-        state.successor_list[0] = info;
+    /// Hands our keys to our successor before we go. Our own keys are resent
+    /// until the successor's digest confirms it holds them; the replicas we
+    /// keep for other nodes are sent once, since their owners re-replicate
+    /// them anyway. A successor that can't be reached or doesn't confirm is
+    /// skipped for the next one in the list. If none takes our keys the node
+    /// stays in the ring; otherwise our neighbours are told we departed.
+    pub async fn leave_network(&self) -> Result<(), Status> {
+        self.hand_off_for_leave().await?;
+        self.announce_departure().await;
         Ok(())
     }
 
-    pub async fn stabilize(&self) {
-        let successor = {
+    /// Tells our predecessor and successors that we are going away on
+    /// purpose, so the last of them to lose us doesn't think it is cut off.
+    /// Best effort: a neighbour that misses it only refuses writes until a
+    /// node joins it.
+    async fn announce_departure(&self) {
+        let neighbours: Vec<NodeInfo> = {
             let state = self.state.read().await;
-            state
-                .successor_list
-                .first()
+            let mut neighbours: Vec<NodeInfo> = state
+                .predecessor
+                .iter()
+                .chain(&state.successor_list)
+                .filter(|n| n.id != self.id)
                 .cloned()
-                .expect("Successor list should never be empty")
+                .collect();
+            neighbours.sort_by_key(|n| n.id);
+            neighbours.dedup_by_key(|n| n.id);
+            neighbours
         };
+        for neighbour in neighbours {
+            if let Err(e) = self.departing_rpc(node_url(&neighbour.address)).await {
+                warn!(
+                    "Node {}: Could not tell {} we are departing: {}",
+                    self.id, neighbour.id, e
+                );
+            }
+        }
+    }
 
-        let successor_addr = format!("http://{}", successor.address);
-        let x_result = self.get_predecessor_rpc(successor_addr.clone()).await;
+    async fn hand_off_for_leave(&self) -> Result<(), Status> {
+        let mut state = self.state.write().await;
+        state.leaving = true;
+        let successors: Vec<NodeInfo> = state.storage_successors(self.id);
+        let pred_id = state.owned_start(self.id);
+        let store: HashMap<String, StoredValue> = state.store.entries().into_iter().collect();
+        drop(state);
 
-        match x_result {
-            Ok(x) => {
-                let should_update = if x.id != 0 || !x.address.is_empty() {
-                    Self::is_in_range(x.id, self.id, successor.id)
-                } else {
-                    false
-                };
+        let (owned, replicas): (HashMap<_, _>, HashMap<_, _>) = store
+            .into_iter()
+            .partition(|(key, _)| is_in_range_inclusive(hash_addr(key), pred_id, self.id));
 
-                if should_update {
-                    let mut state = self.state.write().await;
-                    // Ensure successor hasn't changed while we were waiting for RPC
-                    if state.successor_list[0].id == successor.id {
-                        state.successor_list[0] = x;
-                    }
-                }
+        for successor in &successors {
+            if !self.await_reachable(successor).await {
+                warn!(
+                    "Node {}: Successor {} unreachable, trying the next one",
+                    self.id, successor.id
+                );
+                continue;
             }
-            Err(e) => {
-                // NotFound means the successor doesn't have a predecessor yet - this is OK
-                // Only treat Unavailable/transport errors as dead nodes
-                if e.code() == tonic::Code::NotFound {
-                    // Successor is alive but has no predecessor yet, continue normally
-                } else {
-                    warn!("Node {}: Successor {} failed: {}", self.id, successor.id, e);
-                    // Successor failed. If we have more successors in the list, promote the next one.
-                    let mut state = self.state.write().await;
-                    if state.successor_list.len() > 1 {
-                        info!(
-                            "Node {}: Removing dead successor {}, promoting next",
-                            self.id, successor.id
-                        );
-                        state.successor_list.remove(0);
-                        return;
-                    }
+            info!(
+                "Node {}: Transferring {} keys to successor {} before leaving",
+                self.id,
+                owned.len() + replicas.len(),
+                successor.id
+            );
+            if !replicas.is_empty() {
+                if let Err(e) = self.transfer_keys_rpc(successor, replicas.clone()).await {
+                    warn!(
+                        "Node {}: Failed to transfer replicas on leave: {}",
+                        self.id, e
+                    );
                 }
             }
+            if owned.is_empty() {
+                return Ok(());
+            }
+            let deadline = Instant::now() + Duration::from_millis(HANDOFF_TIMEOUT_MS);
+            match self
+                .hand_off(successor, pred_id, 0, owned.clone(), deadline)
+                .await
+            {
+                Ok(()) => return Ok(()),
+                Err(e) => warn!(
+                    "Node {}: Handoff to successor {} failed: {}",
+                    self.id, successor.id, e
+                ),
+            }
         }
 
-        let successor = {
-            let state = self.state.read().await;
-            state
-                .successor_list
-                .first()
-                .cloned()
-                .expect("Successor list should never be empty")
-        };
-
-        let successor_addr = format!("http://{}", successor.address);
-        let me = NodeInfo {
-            id: self.id,
-            address: self.addr.clone(),
-        };
-
-        if let Err(e) = self.notify_rpc(successor_addr.clone(), me).await {
-            warn!(
-                "Node {}: Failed to notify successor {}: {}",
-                self.id, successor.id, e
-            );
+        if owned.is_empty() {
+            return Ok(());
         }
-
-        let _ = self.update_successor_list(successor_addr).await;
+        let mut untransferred: Vec<&String> = owned.keys().collect();
+        untransferred.sort();
+        error!(
+            "Node {}: No successor took our keys, staying in the ring. Not transferred: {:?}",
+            self.id, untransferred
+        );
+        self.state.write().await.leaving = false;
+        Err(Status::unavailable(format!(
+            "no successor confirmed the handoff of {} keys",
+            owned.len()
+        )))
     }
 
-    pub async fn fix_fingers(&self) {
-        let i = {
-            use rand::Rng;
-            let mut rng = rand::thread_rng();
-            rng.gen_range(0..FINGER_TABLE_SIZE)
-        };
-
-        // For u64 space, finger[i] should point to successor of (n + 2^i) mod 2^64
-        // wrapping_add handles the modulo automatically
-        let target = self.id.wrapping_add(1u64 << i);
-
-        if let Ok(successor) = self.find_successor_internal(target).await {
-            let mut state = self.state.write().await;
-            state.finger_table[i] = successor;
+    /// Pings `target` until it answers, at most `leave_attempts` times,
+    /// doubling the wait between tries. Every try is a fresh ping; a cached
+    /// failure would only repeat itself.
+    async fn await_reachable(&self, target: &NodeInfo) -> bool {
+        let mut backoff = self.leave_backoff;
+        for attempt in 1..=self.leave_attempts {
+            match self.ping_rpc(node_url(&target.address)).await {
+                Ok(()) => return true,
+                Err(e) => debug!(
+                    "Node {}: Ping {} of {} to {} failed: {}",
+                    self.id, attempt, self.leave_attempts, target.id, e
+                ),
+            }
+            if attempt < self.leave_attempts {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
         }
+        false
     }
 
-    pub async fn check_predecessor(&self) {
+    /// Takes the node out of service without stopping it. Writes for our
+    /// keys are refused with a redirect, and the keys are handed to the
+    /// successors that take over: the first one as their new primary, the
+    /// rest as its replicas. Once every one of them reports a matching digest
+    /// the node is drained and can be stopped. Returns how many keys it owned.
+    pub async fn drain_network(&self) -> Result<u64, Status> {
         let mut state = self.state.write().await;
-        if let Some(predecessor) = &state.predecessor {
-            let endpoint = format!("http://{}", predecessor.address);
-            let mut client = match self.connect_rpc(endpoint).await {
-                Ok(c) => c,
-                Err(_) => {
-                    state.predecessor = None;
-                    return;
-                }
-            };
-
-            if client.ping(Request::new(Empty {})).await.is_err() {
-                state.predecessor = None;
-            }
+        let successors: Vec<NodeInfo> = state.storage_successors(self.id);
+        if successors.is_empty() {
+            return Err(Status::failed_precondition(
+                "no other node to hand the keys to",
+            ));
         }
-    }
-
-    pub async fn maintain_replication(&self) {
-        let state = self.state.read().await;
-        let store = state.store.clone();
-        let successor_list = state.successor_list.clone();
-        let predecessor = state.predecessor.clone();
+        state.draining = true;
+        let pred_id = state.owned_start(self.id);
+        let owned: HashMap<String, StoredValue> = state
+            .store
+            .entries()
+            .into_iter()
+            .filter(|(key, _)| is_in_range_inclusive(hash_addr(key), pred_id, self.id))
+            .collect();
         drop(state);
 
-        let pred_id = predecessor.map(|p| p.id).unwrap_or(self.id);
-
-        let replication_count = REPLICATION_COUNT;
-        let successors_to_replicate: Vec<_> =
-            successor_list.into_iter().take(replication_count).collect();
-
-        if successors_to_replicate.is_empty() {
-            return;
+        info!(
+            "Node {}: Draining, handing {} keys to successor {}",
+            self.id,
+            owned.len(),
+            successors[0].id
+        );
+        let deadline = Instant::now() + Duration::from_millis(HANDOFF_TIMEOUT_MS);
+        for (i, succ) in successors.iter().enumerate() {
+            // Once we're gone the successor replicates to the nodes after it,
+            // so successor i takes the keys that reach i replicas
+            let keys: HashMap<String, StoredValue> = owned
+                .iter()
+                .filter(|(_, entry)| entry.replication_factor >= i)
+                .map(|(key, entry)| (key.clone(), entry.clone()))
+                .collect();
+            if keys.is_empty() {
+                continue;
+            }
+            if let Err(e) = self.hand_off(succ, pred_id, i, keys, deadline).await {
+                // We still hold every key, so go back to serving writes
+                warn!("Node {}: Drain failed: {}", self.id, e);
+                self.state.write().await.draining = false;
+                return Err(e);
+            }
         }
 
-        for (key, value) in store {
-            let key_id = hash_addr(&key);
-
-            // Check if we are primary
-            let is_primary = Self::is_in_range_inclusive(key_id, pred_id, self.id);
+        self.state.write().await.drained = true;
+        self.announce_departure().await;
+        info!("Node {}: Drained, safe to stop", self.id);
+        Ok(owned.len() as u64)
+    }
 
-            if is_primary {
-                for succ in &successors_to_replicate {
-                    let endpoint = format!("http://{}", succ.address);
-                    let req = PutRequest {
-                        key: key.clone(),
-                        value: value.clone(),
+    /// Transfers `keys` to `target` until its digest of them matches ours,
+    /// resending only the buckets that still differ.
+    async fn hand_off(
+        &self,
+        target: &NodeInfo,
+        pred_id: u64,
+        min_replication_factor: usize,
+        keys: HashMap<String, StoredValue>,
+        deadline: Instant,
+    ) -> Result<(), Status> {
+        let tree = MerkleTree::build(
+            pred_id,
+            self.id,
+            MERKLE_TREE_DEPTH,
+            keys.iter()
+                .map(|(key, entry)| (hash_addr(key), key.as_str(), entry.updated_at)),
+        );
+        let endpoint = node_url(&target.address);
+        let mut to_send = keys.clone();
+        loop {
+            let sent = match self.transfer_keys_rpc(target, to_send).await {
+                Ok(response) if !response.rejected.is_empty() => {
+                    // Resending won't help; the digest can never match
+                    return Err(Status::failed_precondition(format!(
+                        "successor {} rejected {} keys",
+                        target.id,
+                        response.rejected.len()
+                    )));
+                }
+                Ok(_) => {
+                    let request = SyncDigestRequest {
+                        start_id: pred_id,
+                        end_id: self.id,
+                        min_replication_factor: min_replication_factor as u32,
+                        root: tree.root().to_vec(),
                     };
-
-                    tokio::spawn(async move {
-                        use chord_proto::chord::chord_client::ChordClient;
-                        match ChordClient::connect(endpoint).await {
-                            Ok(mut client) => {
-                                if client.replicate(Request::new(req)).await.is_err() {
-                                    debug!("Node: Failed to replicate during maintenance");
-                                }
-                            }
-                            Err(e) => {
-                                debug!(
-                                    "Node: Failed to connect for replication maintenance: {}",
-                                    e
-                                );
-                            }
-                        }
-                    });
+                    self.sync_digest_rpc(endpoint.clone(), request).await
+                }
+                Err(e) => Err(e),
+            };
+            to_send = match sent {
+                Ok(resp) if resp.leaves.is_empty() => return Ok(()),
+                Ok(resp) => {
+                    let differing = tree.diff(&resp.leaves);
+                    keys.iter()
+                        .filter(|(key, _)| differing.contains(&tree.bucket(hash_addr(key))))
+                        .map(|(key, entry)| (key.clone(), entry.clone()))
+                        .collect()
+                }
+                Err(e) => {
+                    warn!(
+                        "Node {}: Handoff to {} failed, retrying: {}",
+                        self.id, target.id, e
+                    );
+                    keys.clone()
                 }
+            };
+            if Instant::now() >= deadline {
+                return Err(Status::deadline_exceeded(format!(
+                    "successor {} did not confirm the handoff",
+                    target.id
+                )));
             }
+            tokio::time::sleep(Duration::from_millis(HANDOFF_RETRY_INTERVAL_MS)).await;
         }
     }
 
-    async fn update_successor_list(&self, successor_addr: String) -> Result<(), Status> {
-        match self.get_successor_list_rpc(successor_addr).await {
-            Ok(list) => {
-                let mut state = self.state.write().await;
-                // New successor list = successor + successor.successors (trimmed)
-                let mut new_list = vec![state.successor_list[0].clone()];
-                new_list.extend(list.successors);
-                if new_list.len() > SUCCESSOR_LIST_LIMIT {
-                    // Keep k successors
-                    new_list.truncate(SUCCESSOR_LIST_LIMIT);
-                }
-                state.successor_list = new_list;
-                Ok(())
-            }
-            Err(e) => Err(e),
-        }
+    /// Copies of the keys we hold in `range`, with the request ids we
+    /// applied recently, for a node pulling them.
+    async fn keys_to_pull(&self, range: IdRange) -> (HashMap<String, StoredValue>, Vec<String>) {
+        let state = self.state.read().await;
+        let keys: HashMap<String, StoredValue> = state
+            .store
+            .entries()
+            .into_iter()
+            .filter(|(k, _)| {
+                range.whole_ring || is_in_range_inclusive(hash_addr(k), range.start, range.end)
+            })
+            .collect();
+        let request_ids = state.applied_requests.ids();
+        drop(state);
+        debug!(
+            "Node {}: Handing out {} keys in ({}, {}]",
+            self.id,
+            keys.len(),
+            range.start,
+            range.end
+        );
+        (keys, request_ids)
     }
 
-    // RPC Helpers
-    async fn find_successor_rpc(&self, addr: String, id: u64) -> Result<NodeInfo, Status> {
-        let mut client = self.connect_rpc(addr).await?;
-        let request = Request::new(FindSuccessorRequest { id });
-        let response = client.find_successor(request).await?;
-        Ok(response.into_inner())
+    /// Whether `peer` speaks protocol `version` or later, e.g. whether it
+    /// takes streamed key transfers. A peer that never said hello is asked
+    /// for its version first; the probe leaves out our own info, so it
+    /// doesn't count as us saying hello to it.
+    async fn peer_speaks(&self, peer: &NodeInfo, version: u32) -> bool {
+        let wanted = version;
+        if let Some(version) = self.peer_version(peer.id).await {
+            return version >= wanted;
+        }
+        let addr = node_url(&peer.address);
+        let version = match self
+            .timed_rpc("hello", &addr, async {
+                let mut client = self.connect_rpc(addr.clone()).await?;
+                let request = Request::new(Handshake {
+                    version: PROTOCOL_VERSION,
+                    min_version: MIN_PROTOCOL_VERSION,
+                    node: None,
+                });
+                Ok(client.hello(request).await?.into_inner().version)
+            })
+            .await
+        {
+            Ok(version) => version,
+            Err(e) if e.code() == tonic::Code::Unimplemented => 1,
+            // The transfer itself will fail and report it
+            Err(_) => return true,
+        };
+        self.state
+            .write()
+            .await
+            .peer_versions
+            .insert(peer.id, version);
+        version >= wanted
     }
 
-    async fn get_predecessor_rpc(&self, addr: String) -> Result<NodeInfo, Status> {
-        let mut client = self.connect_rpc(addr).await?;
-        let request = Request::new(Empty {});
-        let response = client.get_predecessor(request).await?;
-        Ok(response.into_inner())
+    /// Forgets `peer`'s version after a streamed call it doesn't implement,
+    /// e.g. because it was downgraded, so the next transfer asks again.
+    async fn forget_version_if_unimplemented<T>(
+        &self,
+        peer: &NodeInfo,
+        result: &Result<T, Status>,
+    ) {
+        if matches!(result, Err(e) if e.code() == tonic::Code::Unimplemented) {
+            self.state.write().await.peer_versions.remove(&peer.id);
+        }
     }
 
-    async fn notify_rpc(&self, addr: String, node: NodeInfo) -> Result<(), Status> {
-        let mut client = self.connect_rpc(addr).await?;
-        let request = Request::new(node);
-        client.notify(request).await?;
-        Ok(())
+    /// Pulls the keys `peer` holds in (start, end] and stores them.
+    async fn pull_keys_rpc(
+        &self,
+        peer: &NodeInfo,
+        start: u64,
+        end: u64,
+    ) -> Result<TransferKeysResponse, Status> {
+        let addr = node_url(&peer.address);
+        let request = IdRange {
+            start,
+            end,
+            whole_ring: false,
+        };
+        if !self
+            .peer_speaks(peer, STREAMING_TRANSFER_PROTOCOL_VERSION)
+            .await
+        {
+            return self
+                .timed_rpc("pull_keys", &addr, async {
+                    let mut client = self.connect_rpc(addr.clone()).await?;
+                    let keys = client.pull_keys(request).await?.into_inner();
+                    let keys = self.unpack_transfer(keys).await;
+                    let mut response = TransferKeysResponse::default();
+                    self.store_transferred_keys(keys, &mut response).await;
+                    Ok(response)
+                })
+                .await;
+        }
+        let result = self
+            .timed_rpc("pull_key_chunks", &addr, async {
+                let mut client = self.connect_rpc(addr.clone()).await?;
+                let chunks = client.pull_key_chunks(request).await?.into_inner();
+                self.receive_keys(chunks).await
+            })
+            .await;
+        self.forget_version_if_unimplemented(peer, &result).await;
+        result
     }
 
-    async fn get_successor_list_rpc(&self, addr: String) -> Result<SuccessorList, Status> {
-        let mut client = self.connect_rpc(addr).await?;
-        let request = Request::new(Empty {});
-        let response = client.get_successor_list(request).await?;
-        Ok(response.into_inner())
+    async fn transfer_keys_rpc(
+        &self,
+        peer: &NodeInfo,
+        keys: HashMap<String, StoredValue>,
+    ) -> Result<TransferKeysResponse, Status> {
+        let request_ids = self.state.read().await.applied_requests.ids();
+        self.send_keys_rpc(peer, keys, request_ids).await
     }
 
-    pub async fn report_to_monitor(&self, monitor_addr: String) {
-        use chord_proto::chord::chord_monitor_client::ChordMonitorClient;
-        let state = self.state.read().await;
-
-        let node_state = ProtoNodeState {
-            id: self.id,
-            address: self.addr.clone(),
-            predecessor: state.predecessor.clone(),
-            successors: state.successor_list.clone(),
-            finger_table: state.finger_table.clone(),
-            stored_keys: state.store.keys().cloned().collect(),
-        };
-
-        // Fire and forget
-        let monitor_addr = format!("http://{}", monitor_addr);
-        if let Ok(mut client) = ChordMonitorClient::connect(monitor_addr).await {
-            let _ = client.report_state(Request::new(node_state)).await;
+    /// Hands `keys` to `peer`, streamed in chunks unless it predates that.
+    async fn send_keys_rpc(
+        &self,
+        peer: &NodeInfo,
+        keys: HashMap<String, StoredValue>,
+        request_ids: Vec<String>,
+    ) -> Result<TransferKeysResponse, Status> {
+        let addr = node_url(&peer.address);
+        if !self
+            .peer_speaks(peer, STREAMING_TRANSFER_PROTOCOL_VERSION)
+            .await
+        {
+            return self
+                .timed_rpc("transfer_keys", &addr, async {
+                    let mut client = self.connect_rpc(addr.clone()).await?;
+                    let request = Request::new(transfer_keys_request(keys, request_ids));
+                    Ok(client.transfer_keys(request).await?.into_inner())
+                })
+                .await;
         }
+        let result = self
+            .timed_rpc("transfer_key_chunks", &addr, async {
+                let mut client = self.connect_rpc(addr.clone()).await?;
+                let chunks = transfer_stream(keys, request_ids);
+                Ok(client.transfer_key_chunks(chunks).await?.into_inner())
+            })
+            .await;
+        self.forget_version_if_unimplemented(peer, &result).await;
+        result
     }
-    pub async fn leave_network(&self) {
-        let state = self.state.read().await;
-        let successor = state.successor_list.first().cloned();
-        let store = state.store.clone();
-        drop(state);
 
-        if let Some(successor) = successor {
-            if successor.id != self.id {
-                info!(
-                    "Node {}: Transferring {} keys to successor {} before leaving",
-                    self.id,
-                    store.len(),
-                    successor.id
-                );
-                let successor_addr = format!("http://{}", successor.address);
-                if let Err(e) = self.transfer_keys_rpc(successor_addr, store).await {
-                    error!("Node {}: Failed to transfer keys on leave: {}", self.id, e);
-                }
+    /// The keys a new predecessor takes over from us. With a known
+    /// predecessor that is `(old, new]`; a lone node owned the whole ring,
+    /// so it hands over `(self, new]`. Otherwise (a fresh joiner, or our
+    /// predecessor failed) we can't tell which of our keys outside
+    /// `(new, self]` were ours and which are replicas, so the ones a lookup
+    /// places in the new predecessor's range are copied over but kept
+    /// here, and the new predecessor keeps whichever version is newer.
+    fn predecessor_handover(&self, state: &NodeState, new_pred: &NodeInfo) -> Handover {
+        match &state.predecessor {
+            Some(old) if old.id != self.id => Handover::Move {
+                start: state.owned_start(self.id),
+            },
+            _ if state.successor_list.iter().all(|s| s.id == self.id) => {
+                Handover::Move { start: self.id }
             }
+            _ => Handover::CopyOutside { start: new_pred.id },
         }
     }
 
-    async fn transfer_keys_rpc(
-        &self,
-        addr: String,
-        keys: HashMap<String, String>,
-    ) -> Result<(), Status> {
-        use chord_proto::chord::TransferKeysRequest;
-        let mut client = self.connect_rpc(addr).await?;
-        let request = Request::new(TransferKeysRequest { keys });
-        client.transfer_keys(request).await?;
-        Ok(())
-    }
-
     async fn transfer_keys_to_new_predecessor(
         &self,
         state: &mut tokio::sync::RwLockWriteGuard<'_, NodeState>,
         potential_predecessor: &NodeInfo,
+        handover: Handover,
     ) {
         let mut keys_to_transfer = HashMap::new();
         let mut keys_to_remove = Vec::new();
 
-        for (k, v) in &state.store {
-            let key_id = hash_addr(k);
-            // Check if key_id is in (old_pred, new_pred]
-            // If key_id is NOT in (new_pred, self], then it belongs to new_pred (or someone else behind).
-
-            if !Self::is_in_range_inclusive(key_id, potential_predecessor.id, self.id) {
-                keys_to_transfer.insert(k.clone(), v.clone());
-                keys_to_remove.push(k.clone());
+        for (k, v) in state.store.entries() {
+            let key_id = hash_addr(&k);
+            match handover {
+                Handover::Move { start } => {
+                    if is_in_range_inclusive(key_id, start, potential_predecessor.id) {
+                        keys_to_remove.push(k.clone());
+                        keys_to_transfer.insert(k, v);
+                    }
+                }
+                Handover::CopyOutside { start } => {
+                    if !is_in_range_inclusive(key_id, start, self.id) {
+                        keys_to_transfer.insert(k, v);
+                    }
+                }
             }
         }
 
@@ -462,41 +3997,174 @@ impl Node {
                 potential_predecessor.id
             );
 
-            let state_clone = self.state.clone();
-            let target_addr = format!("http://{}", potential_predecessor.address);
-            let keys_to_send = keys_to_transfer;
+            let node = self.clone();
+            let new_pred = potential_predecessor.clone();
             let keys_to_remove_ids = keys_to_remove;
+            let request_ids = state.applied_requests.ids();
 
             tokio::spawn(async move {
-                use chord_proto::chord::chord_client::ChordClient;
-                use chord_proto::chord::TransferKeysRequest;
+                let keys_to_send = match handover {
+                    Handover::Move { .. } => keys_to_transfer,
+                    Handover::CopyOutside { .. } => {
+                        node.keys_in_new_predecessor_range(&new_pred, keys_to_transfer)
+                            .await
+                    }
+                };
+                if keys_to_send.is_empty() {
+                    return;
+                }
 
-                let mut client = match ChordClient::connect(target_addr).await {
-                    Ok(c) => c,
+                let sent: Vec<String> = keys_to_send.keys().cloned().collect();
+                match node
+                    .send_keys_rpc(&new_pred, keys_to_send, request_ids)
+                    .await
+                {
+                    Ok(response) => {
+                        let confirmed: HashSet<String> =
+                            confirmed_keys(sent, &response).into_iter().collect();
+                        let mut state = node.state.write().await;
+                        for k in keys_to_remove_ids {
+                            // Unconfirmed keys stay, so a partial transfer loses nothing
+                            if !confirmed.contains(&k) {
+                                continue;
+                            }
+                            state.store.delete(&k);
+                            let _ = node.changes.send(change_event(ChangeOp::Delete, &k, None));
+                        }
+                    }
                     Err(e) => {
-                        error!(
-                            "Failed to connect to new predecessor for key transfer: {}",
-                            e
-                        );
-                        return;
+                        error!("Failed to transfer keys: {}", e);
                     }
-                };
+                }
+            });
+        }
+    }
+
+    /// Of the keys a `CopyOutside` handover would copy, the ones `new_pred`
+    /// owns. Its range starts at the node a lookup for its id settles on,
+    /// which the ring still knows even when neither of us knows our
+    /// predecessor; the rest are other owners' replicas and stay here only.
+    /// If the lookup fails every key is copied, so the new predecessor
+    /// misses none of its own.
+    async fn keys_in_new_predecessor_range(
+        &self,
+        new_pred: &NodeInfo,
+        keys: HashMap<String, StoredValue>,
+    ) -> HashMap<String, StoredValue> {
+        let start = match self.find_predecessor_internal(new_pred.id).await {
+            Ok(pred) if pred.id != new_pred.id => pred.id,
+            Ok(_) => return keys,
+            Err(e) => {
+                warn!(
+                    "Node {}: Couldn't find the range of new predecessor {}, copying all keys: {}",
+                    self.id, new_pred.id, e
+                );
+                return keys;
+            }
+        };
+        keys.into_iter()
+            .filter(|(key, _)| is_in_range_inclusive(hash_addr(key), start, new_pred.id))
+            .collect()
+    }
+
+    /// The node to route a request with. Requests other nodes forwarded to
+    /// us take their forwarding slots from a separate pool, so a request
+    /// routed back through us never waits on a slot its own earlier hop is
+    /// holding for the rest of the call.
+    fn route_as(&self, forwarded: bool) -> Cow<'_, Node> {
+        if !forwarded {
+            return Cow::Borrowed(self);
+        }
+        Cow::Owned(Node {
+            forward_permits: self.hop_permits.clone(),
+            ..self.clone()
+        })
+    }
 
-                let request = Request::new(TransferKeysRequest { keys: keys_to_send });
+    /// Waits for a free forwarding slot, to be held until the forwarded call
+    /// returns. Fails with `resource_exhausted` if no slot frees up within
+    /// `FORWARD_QUEUE_TIMEOUT_MS`.
+    async fn forward_permit(&self) -> Result<SemaphorePermit<'_>, Status> {
+        let timeout = Duration::from_millis(FORWARD_QUEUE_TIMEOUT_MS);
+        match tokio::time::timeout(timeout, self.forward_permits.acquire()).await {
+            Ok(Ok(permit)) => Ok(permit),
+            Ok(Err(_)) => Err(Status::internal("Forward limiter closed")),
+            Err(_) => {
+                self.forwards_rejected.fetch_add(1, Ordering::Relaxed);
+                warn!(
+                    "Node {}: Too many concurrent forwards, rejecting request",
+                    self.id
+                );
+                Err(Status::resource_exhausted("Too many concurrent forwards"))
+            }
+        }
+    }
 
-                match client.transfer_keys(request).await {
-                    Ok(_) => {
-                        let mut state = state_clone.write().await;
-                        for k in keys_to_remove_ids {
-                            state.store.remove(&k);
-                        }
-                    }
-                    Err(e) => {
-                        error!("Failed to transfer keys: {}", e);
-                    }
+    /// Sends a replica over a pooled connection, streaming it in chunks if
+    /// it is too large for one message. Waits for one of the replication
+    /// slots first, so a burst of writes can't open unbounded connections.
+    async fn send_replica(&self, endpoint: String, req: PutRequest) -> Result<(), Status> {
+        let _permit = self
+            .replication_permits
+            .acquire()
+            .await
+            .map_err(|_| Status::internal("Replication limiter closed"))?;
+        let result = self
+            .timed_rpc("replicate", &endpoint, async {
+                let mut client = self.connections.client(&endpoint).await?;
+                if req.value.len() > VALUE_CHUNK_SIZE {
+                    client
+                        .replicate_stream(tokio_stream::iter(value_chunks(req)))
+                        .await
+                } else {
+                    client.replicate(Request::new(req)).await
                 }
-            });
+            })
+            .await;
+        if let Err(e) = result {
+            self.connections.evict(&endpoint);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Awaits an outbound RPC to `addr`, warning when it takes longer than
+    /// `SLOW_RPC_THRESHOLD_MS` so tail latency shows up in the logs.
+    async fn timed_rpc<T>(
+        &self,
+        op: &str,
+        addr: &str,
+        call: impl Future<Output = Result<T, Status>>,
+    ) -> Result<T, Status> {
+        self.observe_rpc(op, addr, true, call).await
+    }
+
+    /// Like `timed_rpc`, but a failure only drops `addr` from the liveness
+    /// cache when `mark_failures` is set.
+    async fn observe_rpc<T>(
+        &self,
+        op: &str,
+        addr: &str,
+        mark_failures: bool,
+        call: impl Future<Output = Result<T, Status>>,
+    ) -> Result<T, Status> {
+        let started = Instant::now();
+        let result = call.await;
+        if result.is_err() && mark_failures {
+            self.liveness.lock().unwrap().invalidate(addr);
+        }
+        let elapsed = started.elapsed();
+        if elapsed >= Duration::from_millis(SLOW_RPC_THRESHOLD_MS) {
+            warn!(
+                "Node {}: Slow {} RPC to {} took {} ms ({})",
+                self.id,
+                op,
+                addr,
+                elapsed.as_millis(),
+                if result.is_ok() { "ok" } else { "failed" }
+            );
         }
+        result
     }
 
     async fn connect_rpc(
@@ -505,9 +4173,22 @@ impl Node {
     ) -> Result<chord_proto::chord::chord_client::ChordClient<tonic::transport::Channel>, Status>
     {
         use chord_proto::chord::chord_client::ChordClient;
-        ChordClient::connect(addr)
-            .await
-            .map_err(|e| Status::unavailable(e.to_string()))
+        Ok(ChordClient::new(self.transport.channel(&addr).await?))
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl Node {
+    /// Overwrites the predecessor and successor list, and points every finger
+    /// at the new successor, so tests can set up an exact topology without
+    /// racing stabilization. An empty successor list falls back to self.
+    pub async fn set_neighbors(&self, predecessor: Option<NodeInfo>, successors: Vec<NodeInfo>) {
+        let mut state = self.state.write().await;
+        state.predecessor = predecessor;
+        state.successor_list = successors;
+        let successor = self.successor_or_self(&mut state);
+        state.finger_table = vec![successor; FINGER_TABLE_SIZE];
+        state.finger_last_fixed = vec![None; FINGER_TABLE_SIZE];
     }
 }
 
@@ -539,25 +4220,103 @@ impl Chord for Node {
         request: Request<FindSuccessorRequest>,
     ) -> Result<Response<NodeInfo>, Status> {
         let req = request.into_inner();
-        let successor = self.find_successor_internal(req.id).await?;
+        let max_hops = match req.max_hops {
+            0 => MAX_LOOKUP_HOPS,
+            hops => hops,
+        };
+        let node = self.route_as(true);
+        if req.dry_run {
+            let successor = node.find_successor_dry_run(req.id, max_hops).await?;
+            return Ok(Response::new(successor));
+        }
+        let successor = self
+            .timed(
+                Operation::FindSuccessor,
+                node.find_successor_bounded(req.id, max_hops),
+            )
+            .await?;
         Ok(Response::new(successor))
     }
 
+    async fn find_successor_traced(
+        &self,
+        request: Request<TracedLookupRequest>,
+    ) -> Result<Response<TracedLookupResponse>, Status> {
+        let req = request.into_inner();
+        let response = self
+            .route_as(true)
+            .find_successor_traced_internal(req.id, req.path)
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn find_predecessor(
+        &self,
+        request: Request<FindPredecessorRequest>,
+    ) -> Result<Response<NodeInfo>, Status> {
+        let req = request.into_inner();
+        let predecessor = self
+            .route_as(true)
+            .find_predecessor_internal(req.id)
+            .await?;
+        Ok(Response::new(predecessor))
+    }
+
     async fn notify(&self, request: Request<NodeInfo>) -> Result<Response<Empty>, Status> {
         let potential_predecessor = request.into_inner();
+
+        // Another node with our id would be taken for us everywhere, so it
+        // can't be let in
+        if potential_predecessor.id == self.id && potential_predecessor.address != self.addr {
+            warn!(
+                "Node {}: Refusing notify from {}, which has the same id",
+                self.id, potential_predecessor.address
+            );
+            return Err(Status::already_exists(format!(
+                "id {} is already taken by {}",
+                self.id, self.addr
+            )));
+        }
+
         let mut state = self.state.write().await;
 
+        // A lone node's successor is itself, so it notifies itself while
+        // bootstrapping. That only marks it as settled on its own: there is
+        // nothing to transfer, and a real predecessor must never be replaced.
+        if potential_predecessor.id == self.id {
+            let alone = state.successor_list.iter().all(|s| s.id == self.id);
+            if alone && state.predecessor.is_none() {
+                state.predecessor = Some(potential_predecessor);
+            } else {
+                debug!("Node {}: Ignoring notify from self", self.id);
+            }
+            return Ok(Response::new(Empty {}));
+        }
+
         let should_update = if let Some(current_predecessor) = &state.predecessor {
-            Self::is_in_range(potential_predecessor.id, current_predecessor.id, self.id)
+            is_in_range(potential_predecessor.id, current_predecessor.id, self.id)
         } else {
             true
         };
 
-        if should_update {
-            let _old_predecessor = state.predecessor.clone();
+        if should_update && potential_predecessor.observer {
+            // An observer stores nothing, so we keep every key and go on
+            // owning the range it would have taken over from us
+            let range_start = match &state.predecessor {
+                Some(old) if old.id != self.id => Some(state.owned_start(self.id)),
+                _ if state.successor_list.iter().all(|s| s.id == self.id) => Some(self.id),
+                _ => None,
+            };
+            state.predecessor = Some(potential_predecessor);
+            state.observer_range_start = range_start;
+            state.lookup_cache.clear();
+        } else if should_update {
+            let handover = self.predecessor_handover(&state, &potential_predecessor);
             state.predecessor = Some(potential_predecessor.clone());
+            state.observer_range_start = None;
+            state.lookup_cache.clear();
 
-            self.transfer_keys_to_new_predecessor(&mut state, &potential_predecessor)
+            self.transfer_keys_to_new_predecessor(&mut state, &potential_predecessor, handover)
                 .await;
         }
 
@@ -576,144 +4335,502 @@ impl Chord for Node {
 
     async fn put(&self, request: Request<PutRequest>) -> Result<Response<PutResponse>, Status> {
         let req = request.into_inner();
-        let key_id = hash_addr(&req.key);
-        debug!(
-            "Node {}: Received Put request for key '{}' (ID: {})",
-            self.id, req.key, key_id
-        );
+        let node = self.route_as(!req.visited.is_empty());
+        let response = self.timed(Operation::Put, node.put_internal(req)).await?;
+        Ok(Response::new(response))
+    }
 
-        let successor = self.find_successor_internal(key_id).await?;
-        debug!(
-            "Node {}: Successor for key '{}' is {}",
-            self.id, req.key, successor.id
-        );
+    async fn put_stream(
+        &self,
+        request: Request<Streaming<ValueChunk>>,
+    ) -> Result<Response<PutResponse>, Status> {
+        let req = collect_chunks(request.into_inner()).await?;
+        let node = self.route_as(!req.visited.is_empty());
+        Ok(Response::new(node.put_internal(req).await?))
+    }
 
-        if successor.id == self.id {
-            info!("Node {}: Storing key '{}' locally", self.id, req.key);
-            let mut state = self.state.write().await;
-            state.store.insert(req.key.clone(), req.value.clone());
+    async fn replicate(&self, request: Request<PutRequest>) -> Result<Response<Empty>, Status> {
+        if let Some(status) = self.observer_status() {
+            return Err(status);
+        }
+        let req = request.into_inner();
+        validate_put(&req).map_err(Status::invalid_argument)?;
+        self.store_replica(req).await;
+        Ok(Response::new(Empty {}))
+    }
 
-            let successor_list = state.successor_list.clone();
-            drop(state);
+    async fn replicate_stream(
+        &self,
+        request: Request<Streaming<ValueChunk>>,
+    ) -> Result<Response<Empty>, Status> {
+        if let Some(status) = self.observer_status() {
+            return Err(status);
+        }
+        let req = collect_chunks(request.into_inner()).await?;
+        validate_put(&req).map_err(Status::invalid_argument)?;
+        self.store_replica(req).await;
+        Ok(Response::new(Empty {}))
+    }
 
-            let replication_count = REPLICATION_COUNT;
-            let successors_to_replicate: Vec<_> =
-                successor_list.into_iter().take(replication_count).collect();
+    async fn append(
+        &self,
+        request: Request<AppendRequest>,
+    ) -> Result<Response<PutResponse>, Status> {
+        let req = request.into_inner();
+        let node = self.route_as(!req.visited.is_empty());
+        let response = self
+            .timed(Operation::Append, node.append_internal(req))
+            .await?;
+        Ok(Response::new(response))
+    }
 
-            for succ in successors_to_replicate {
-                debug!(
-                    "Node {}: Replicating key '{}' to {}",
-                    self.id, req.key, succ.id
-                );
-                let endpoint = format!("http://{}", succ.address);
-                let req_clone = req.clone();
-                let self_id = self.id;
-
-                tokio::spawn(async move {
-                    use chord_proto::chord::chord_client::ChordClient;
-                    match ChordClient::connect(endpoint).await {
-                        Ok(mut client) => {
-                            if let Err(e) = client.replicate(Request::new(req_clone)).await {
-                                warn!(
-                                    "Node {}: Failed to replicate to {}: {}",
-                                    self_id, succ.id, e
-                                );
-                            }
-                        }
-                        Err(e) => {
-                            warn!(
-                                "Node {}: Failed to connect to replica {}: {}",
-                                self_id, succ.id, e
-                            );
-                        }
-                    }
-                });
-            }
+    async fn get_list(&self, request: Request<GetRequest>) -> Result<Response<ValueList>, Status> {
+        let req = request.into_inner();
+        let node = self.route_as(!req.visited.is_empty());
+        Ok(Response::new(node.get_list_internal(req).await?))
+    }
+
+    async fn get(&self, request: Request<GetRequest>) -> Result<Response<GetResponse>, Status> {
+        let req = request.into_inner();
+        let node = self.route_as(!req.visited.is_empty());
+        let response = self.timed(Operation::Get, node.get_internal(req)).await?;
+        Ok(Response::new(response))
+    }
+
+    async fn get_replica_version(
+        &self,
+        request: Request<GetRequest>,
+    ) -> Result<Response<ReplicaVersion>, Status> {
+        let req = request.into_inner();
+        let state = self.state.read().await;
+        let version = match state.store.get(&req.key) {
+            Some(entry) => ReplicaVersion {
+                found: true,
+                updated_at: entry.updated_at,
+            },
+            None => ReplicaVersion::default(),
+        };
+        Ok(Response::new(version))
+    }
+
+    async fn get_replicas(
+        &self,
+        request: Request<GetRequest>,
+    ) -> Result<Response<ReplicaLocations>, Status> {
+        let req = request.into_inner();
+        Ok(Response::new(self.replica_locations(&req.key).await?))
+    }
+
+    type GetStreamStream = Pin<Box<dyn Stream<Item = Result<ValueChunk, Status>> + Send>>;
+
+    async fn get_stream(
+        &self,
+        request: Request<GetRequest>,
+    ) -> Result<Response<Self::GetStreamStream>, Status> {
+        let mut req = request.into_inner();
+        let forwarded = !req.visited.is_empty();
+        let (namespace, client_key) = resolve_namespace(&mut req.namespace, &mut req.key)
+            .map_err(Status::invalid_argument)?;
+        let key_id = hash_addr(&req.key);
+        let successor = self.find_owner(key_id).await?;
 
-            Ok(Response::new(PutResponse { success: true }))
+        if successor.id == self.id {
+            let chunks = {
+                let state = self.state.read().await;
+                match state.store.get(&req.key) {
+                    Some(entry) => value_chunks(entry.to_put_request(req.key.clone())),
+                    None => vec![ValueChunk {
+                        key: req.key.clone(),
+                        ..Default::default()
+                    }],
+                }
+            };
+            Ok(Response::new(Box::pin(tokio_stream::iter(
+                chunks.into_iter().map(Ok),
+            ))))
         } else {
+            if let Some(status) = self.forwarding_loop_status(&req.visited, &req.key) {
+                return Err(status);
+            }
+            req.visited.push(self.id);
             debug!(
-                "Node {}: Forwarding Put for key '{}' to {}",
+                "Node {}: Forwarding GetStream for key '{}' to {}",
                 self.id, req.key, successor.id
             );
-            let endpoint = format!("http://{}", successor.address);
-            let mut client = self.connect_rpc(endpoint).await?;
-            let response = client.put(Request::new(req)).await?;
-            Ok(Response::new(response.into_inner()))
+            req.key = client_key;
+            req.namespace = namespace;
+            let endpoint = node_url(&successor.address);
+            let node = self.route_as(forwarded);
+            let stream = self
+                .timed_rpc("get_stream", &endpoint, async {
+                    let _permit = node.forward_permit().await?;
+                    let mut client = self.connect_rpc(endpoint.clone()).await?;
+                    Ok(client.get_stream(Request::new(req)).await?.into_inner())
+                })
+                .await?;
+            Ok(Response::new(Box::pin(stream)))
         }
     }
 
-    async fn replicate(&self, request: Request<PutRequest>) -> Result<Response<Empty>, Status> {
+    async fn delete_range(
+        &self,
+        request: Request<DeleteRangeRequest>,
+    ) -> Result<Response<DeleteRangeResponse>, Status> {
         let req = request.into_inner();
-        debug!("Node {}: Replicating key '{}'", self.id, req.key);
-        let mut state = self.state.write().await;
-        state.store.insert(req.key, req.value);
-        Ok(Response::new(Empty {}))
+        let deleted = self.delete_range_internal(req.start_id, req.end_id).await?;
+        Ok(Response::new(DeleteRangeResponse { deleted }))
     }
-    async fn get(&self, request: Request<GetRequest>) -> Result<Response<GetResponse>, Status> {
+
+    async fn delete_local_range(
+        &self,
+        request: Request<LocalDeleteRangeRequest>,
+    ) -> Result<Response<DeleteRangeResponse>, Status> {
         let req = request.into_inner();
-        let key_id = hash_addr(&req.key);
-        debug!(
-            "Node {}: Received Get request for key '{}' (ID: {})",
-            self.id, req.key, key_id
-        );
+        let deleted = self
+            .delete_local_range(req.start_id, req.end_id, req.replicate, "")
+            .await;
+        Ok(Response::new(DeleteRangeResponse { deleted }))
+    }
 
-        let successor = self.find_successor_internal(key_id).await?;
-        debug!(
-            "Node {}: Successor for key '{}' is {}",
-            self.id, req.key, successor.id
-        );
+    async fn delete_local_prefix(
+        &self,
+        request: Request<LocalDeletePrefixRequest>,
+    ) -> Result<Response<DeleteRangeResponse>, Status> {
+        let req = request.into_inner();
+        let range = req
+            .range
+            .ok_or_else(|| Status::invalid_argument("Missing range"))?;
+        let deleted = self
+            .delete_local_range(range.start_id, range.end_id, range.replicate, &req.prefix)
+            .await;
+        Ok(Response::new(DeleteRangeResponse { deleted }))
+    }
 
-        if successor.id == self.id {
-            debug!("Node {}: Looking up key '{}' locally", self.id, req.key);
-            let state = self.state.read().await;
-            if let Some(value) = state.store.get(&req.key) {
-                info!("Node {}: Found key '{}'", self.id, req.key);
-                Ok(Response::new(GetResponse {
-                    value: value.clone(),
-                    found: true,
-                }))
-            } else {
-                info!("Node {}: Key '{}' not found", self.id, req.key);
-                Ok(Response::new(GetResponse {
-                    value: "".to_string(),
-                    found: false,
-                }))
+    async fn delete_namespace(
+        &self,
+        request: Request<DeleteNamespaceRequest>,
+    ) -> Result<Response<DeleteRangeResponse>, Status> {
+        let req = request.into_inner();
+        let deleted = self.delete_namespace_internal(&req.namespace).await?;
+        Ok(Response::new(DeleteRangeResponse { deleted }))
+    }
+
+    async fn scan_prefix(
+        &self,
+        request: Request<ScanPrefixRequest>,
+    ) -> Result<Response<ScanPrefixResponse>, Status> {
+        let req = request.into_inner();
+        let keys = self.scan_prefix(&req.prefix).await?;
+        Ok(Response::new(ScanPrefixResponse { keys }))
+    }
+
+    async fn scan_local_prefix(
+        &self,
+        request: Request<ScanPrefixRequest>,
+    ) -> Result<Response<ScanPrefixResponse>, Status> {
+        let req = request.into_inner();
+        let mut keys = self.scan_local_prefix(&req.prefix).await;
+        keys.sort_unstable();
+        Ok(Response::new(ScanPrefixResponse { keys }))
+    }
+
+    type ExportStream = Pin<Box<dyn Stream<Item = Result<KeyValue, Status>> + Send>>;
+
+    /// For clients from before chunked exports: the chunks are put back
+    /// together, so a value over the message limit fails the export.
+    async fn export(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::ExportStream>, Status> {
+        info!("Node {}: Starting ring export", self.id);
+        let (chunk_tx, mut chunk_rx) = mpsc::channel(EXPORT_BUFFER_LEN);
+        let (tx, rx) = mpsc::channel(EXPORT_BUFFER_LEN);
+        let node = self.clone();
+        tokio::spawn(async move { node.export_ring(chunk_tx).await });
+        tokio::spawn(async move {
+            let mut entries = KeyValueAssembler::new();
+            while let Some(chunk) = chunk_rx.recv().await {
+                let sent = match chunk.map(|chunk| entries.push(chunk)) {
+                    Ok(Ok(None)) => continue,
+                    Ok(Ok(Some(kv))) => tx.send(Ok(kv)).await,
+                    Ok(Err(e)) => tx.send(Err(Status::internal(e))).await,
+                    Err(e) => tx.send(Err(e)).await,
+                };
+                if sent.is_err() {
+                    return;
+                }
             }
-        } else {
+            if let Some(kv) = entries.finish() {
+                let _ = tx.send(Ok(kv)).await;
+            }
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    type ExportChunksStream = Pin<Box<dyn Stream<Item = Result<ValueChunk, Status>> + Send>>;
+
+    async fn export_chunks(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::ExportChunksStream>, Status> {
+        info!("Node {}: Starting ring export", self.id);
+        let (tx, rx) = mpsc::channel(EXPORT_BUFFER_LEN);
+        let node = self.clone();
+        tokio::spawn(async move { node.export_ring(tx).await });
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    type ExportLocalStream = Pin<Box<dyn Stream<Item = Result<KeyValue, Status>> + Send>>;
+
+    async fn export_local(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::ExportLocalStream>, Status> {
+        let (tx, rx) = mpsc::channel(EXPORT_BUFFER_LEN);
+        let node = self.clone();
+        tokio::spawn(async move { node.export_local_into(tx, |kv| vec![kv]).await });
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    type ExportLocalChunksStream = Pin<Box<dyn Stream<Item = Result<ValueChunk, Status>> + Send>>;
+
+    async fn export_local_chunks(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::ExportLocalChunksStream>, Status> {
+        let (tx, rx) = mpsc::channel(EXPORT_BUFFER_LEN);
+        let node = self.clone();
+        tokio::spawn(async move { node.export_local_into(tx, key_value_chunks).await });
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    type WatchChangesStream = Pin<Box<dyn Stream<Item = Result<ChangeEvent, Status>> + Send>>;
+
+    async fn watch_changes(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::WatchChangesStream>, Status> {
+        info!("Node {}: New change watcher", self.id);
+        // A watcher that can't keep up gets an error and has to resync.
+        // The stream item type is fixed by tonic, so Status can't be boxed.
+        #[allow(clippy::result_large_err)]
+        let stream = BroadcastStream::new(self.changes.subscribe()).map(|event| {
+            event.map_err(|BroadcastStreamRecvError::Lagged(missed)| {
+                Status::data_loss(format!("Watcher fell behind by {} events", missed))
+            })
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn ping(&self, _request: Request<Empty>) -> Result<Response<Empty>, Status> {
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn hello(&self, request: Request<Handshake>) -> Result<Response<Handshake>, Status> {
+        let peer = request.into_inner();
+        if peer.version < MIN_PROTOCOL_VERSION || PROTOCOL_VERSION < peer.min_version {
+            return Err(Status::failed_precondition(format!(
+                "Node {} speaks protocol version {} (accepting {} and up), peer speaks {}",
+                self.id, PROTOCOL_VERSION, MIN_PROTOCOL_VERSION, peer.version
+            )));
+        }
+        if let Some(node) = peer.node {
             debug!(
-                "Node {}: Forwarding Get for key '{}' to {}",
-                self.id, req.key, successor.id
+                "Node {}: Node {} speaks protocol version {}",
+                self.id, node.id, peer.version
             );
-            let endpoint = format!("http://{}", successor.address);
-            let mut client = self.connect_rpc(endpoint).await?;
-            let response = client.get(Request::new(req)).await?;
-            Ok(Response::new(response.into_inner()))
+            let mut state = self.state.write().await;
+            state.peer_versions.insert(node.id, peer.version);
+            // Back from a departure
+            state.departed.remove(&node.id);
         }
+        Ok(Response::new(Handshake {
+            version: PROTOCOL_VERSION,
+            min_version: MIN_PROTOCOL_VERSION,
+            node: Some(self.self_info()),
+        }))
     }
 
-    async fn ping(&self, _request: Request<Empty>) -> Result<Response<Empty>, Status> {
-        Ok(Response::new(Empty {}))
+    async fn get_stats(&self, _request: Request<Empty>) -> Result<Response<NodeStats>, Status> {
+        Ok(Response::new(self.stats().await))
+    }
+
+    async fn get_node_info(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<ProtoNodeState>, Status> {
+        Ok(Response::new(self.snapshot().await))
+    }
+
+    async fn distribution(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<DistributionResponse>, Status> {
+        Ok(Response::new(self.distribution().await?))
+    }
+
+    async fn get_cluster_health(
+        &self,
+        request: Request<ClusterHealthRequest>,
+    ) -> Result<Response<ClusterHealthResponse>, Status> {
+        let max_nodes = match request.into_inner().max_nodes {
+            0 => CLUSTER_HEALTH_MAX_NODES,
+            n => (n as usize).min(CLUSTER_HEALTH_MAX_NODES),
+        };
+        Ok(Response::new(self.cluster_health(max_nodes).await))
+    }
+
+    async fn owned_range(&self, _request: Request<Empty>) -> Result<Response<IdRange>, Status> {
+        Ok(Response::new(self.responsible_range().await))
+    }
+
+    async fn self_check(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<SelfCheckResponse>, Status> {
+        let details = self.self_check().await;
+        let violations: Vec<String> = details.iter().map(|v| v.description.clone()).collect();
+        if !violations.is_empty() {
+            warn!(
+                "Node {}: Self check found {} violations: {}",
+                self.id,
+                violations.len(),
+                violations.join("; ")
+            );
+        }
+        Ok(Response::new(SelfCheckResponse {
+            violations,
+            details,
+        }))
+    }
+
+    async fn rebalance(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<RebalanceResponse>, Status> {
+        let keys_moved = self.rebalance_internal().await?;
+        Ok(Response::new(RebalanceResponse { keys_moved }))
+    }
+
+    async fn health(&self, _request: Request<Empty>) -> Result<Response<HealthResponse>, Status> {
+        let state = self.health().await;
+        Ok(Response::new(HealthResponse {
+            state: state as i32,
+        }))
+    }
+
+    async fn sync_digest(
+        &self,
+        request: Request<SyncDigestRequest>,
+    ) -> Result<Response<SyncDigestResponse>, Status> {
+        let req = request.into_inner();
+        let tree = self
+            .digest(
+                req.start_id,
+                req.end_id,
+                req.min_replication_factor as usize,
+            )
+            .await;
+        let root = tree.root().to_vec();
+        let leaves = if root == req.root {
+            Vec::new()
+        } else {
+            tree.leaves().iter().map(|leaf| leaf.to_vec()).collect()
+        };
+        Ok(Response::new(SyncDigestResponse { root, leaves }))
     }
 
     async fn transfer_keys(
         &self,
         request: Request<TransferKeysRequest>,
-    ) -> Result<Response<Empty>, Status> {
-        let req = request.into_inner();
-        info!("Node {}: Received {} keys", self.id, req.keys.len());
-        let mut state = self.state.write().await;
-        for (k, v) in req.keys {
-            state.store.insert(k, v);
+    ) -> Result<Response<TransferKeysResponse>, Status> {
+        if let Some(status) = self.observer_status() {
+            return Err(status);
+        }
+        let keys = self.unpack_transfer(request.into_inner()).await;
+        let mut response = TransferKeysResponse::default();
+        self.store_transferred_keys(keys, &mut response).await;
+        Ok(Response::new(response))
+    }
+
+    async fn pull_keys(
+        &self,
+        request: Request<IdRange>,
+    ) -> Result<Response<TransferKeysRequest>, Status> {
+        let (keys, request_ids) = self.keys_to_pull(request.into_inner()).await;
+        Ok(Response::new(transfer_keys_request(keys, request_ids)))
+    }
+
+    async fn transfer_key_chunks(
+        &self,
+        request: Request<Streaming<KeyTransferChunk>>,
+    ) -> Result<Response<TransferKeysResponse>, Status> {
+        if let Some(status) = self.observer_status() {
+            return Err(status);
         }
+        Ok(Response::new(
+            self.receive_keys(request.into_inner()).await?,
+        ))
+    }
+
+    type PullKeyChunksStream = Pin<Box<dyn Stream<Item = Result<KeyTransferChunk, Status>> + Send>>;
+
+    async fn pull_key_chunks(
+        &self,
+        request: Request<IdRange>,
+    ) -> Result<Response<Self::PullKeyChunksStream>, Status> {
+        let (keys, request_ids) = self.keys_to_pull(request.into_inner()).await;
+        Ok(Response::new(Box::pin(
+            transfer_stream(keys, request_ids).map(Ok),
+        )))
+    }
+
+    async fn import(
+        &self,
+        request: Request<Streaming<KeyValue>>,
+    ) -> Result<Response<ImportResponse>, Status> {
+        Ok(Response::new(
+            self.import_entries(request.into_inner()).await?,
+        ))
+    }
+
+    async fn import_chunks(
+        &self,
+        request: Request<Streaming<ValueChunk>>,
+    ) -> Result<Response<ImportResponse>, Status> {
+        Ok(Response::new(
+            self.import_chunks(request.into_inner()).await?,
+        ))
+    }
+
+    async fn force_stabilize(&self, _request: Request<Empty>) -> Result<Response<Empty>, Status> {
+        debug!("Node {}: Received ForceStabilize request", self.id);
+        self.stabilize().await;
+        self.fix_fingers().await;
+        self.check_predecessor().await;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn drain(&self, _request: Request<Empty>) -> Result<Response<DrainResponse>, Status> {
+        info!("Node {}: Received Drain request", self.id);
+        let keys_transferred = self.drain_network().await?;
+        Ok(Response::new(DrainResponse { keys_transferred }))
+    }
+
+    async fn departing(&self, request: Request<NodeInfo>) -> Result<Response<Empty>, Status> {
+        let node = request.into_inner();
+        info!("Node {}: Node {} is departing", self.id, node.id);
+        let mut state = self.state.write().await;
+        state.departed.insert(node.id);
+        state.last_known_successors.retain(|s| s.id != node.id);
         Ok(Response::new(Empty {}))
     }
 
     async fn leave(&self, _request: Request<Empty>) -> Result<Response<Empty>, Status> {
         info!("Node {}: Received Leave request", self.id);
-        self.leave_network().await;
+        self.leave_network().await?;
 
-        // Spawn a task to exit the process after a short delay to allow the response to be sent
+        // The handoff is confirmed by now; the delay only lets this response go out
         tokio::spawn(async {
             tokio::time::sleep(tokio::time::Duration::from_millis(LEAVE_EXIT_DELAY_MS)).await;
             std::process::exit(0);
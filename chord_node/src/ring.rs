@@ -0,0 +1,73 @@
+//! Interval checks on the identifier circle. Intervals wrap around zero, so
+//! `start > end` describes an interval that crosses the top of the id space.
+//! The size of the circle is defined once, by [`ID_SPACE`]; finger targets
+//! and range checks both derive from it so they agree across the wrap.
+
+/// Bits in a node or key id.
+pub const ID_BITS: u32 = 64;
+
+/// The id space every node uses: the integers modulo 2^[`ID_BITS`].
+pub const ID_SPACE: IdSpace = IdSpace::new(ID_BITS);
+
+/// An identifier circle of 2^`bits` ids. Ids passed in are expected to
+/// already lie in the space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdSpace {
+    bits: u32,
+}
+
+impl IdSpace {
+    /// A circle of 2^`bits` ids, for `bits` from 1 to 64.
+    pub const fn new(bits: u32) -> Self {
+        assert!(bits >= 1 && bits <= 64, "id space must have 1 to 64 bits");
+        Self { bits }
+    }
+
+    pub fn bits(self) -> u32 {
+        self.bits
+    }
+
+    /// Reduces `id` modulo the size of the circle.
+    pub fn wrap(self, id: u64) -> u64 {
+        if self.bits == u64::BITS {
+            id
+        } else {
+            id & ((1u64 << self.bits) - 1)
+        }
+    }
+
+    /// How far clockwise `to` is from `from`.
+    pub fn distance(self, from: u64, to: u64) -> u64 {
+        self.wrap(to.wrapping_sub(from))
+    }
+
+    /// The id `2^i` clockwise from `id`, whose successor is finger `i`.
+    pub fn finger_target(self, id: u64, i: usize) -> u64 {
+        debug_assert!(i < self.bits as usize, "finger {} out of range", i);
+        self.wrap(id.wrapping_add(1u64 << i))
+    }
+
+    /// Whether `id` lies in the open interval `(start, end)`. With
+    /// `start == end` that is every id but `start`.
+    pub fn is_in_range(self, id: u64, start: u64, end: u64) -> bool {
+        let offset = self.distance(start, id);
+        let span = self.distance(start, end);
+        offset > 0 && (span == 0 || offset < span)
+    }
+
+    /// Whether `id` lies in the half-open interval `(start, end]`. With
+    /// `start == end` that is the whole circle.
+    pub fn is_in_range_inclusive(self, id: u64, start: u64, end: u64) -> bool {
+        id == end || self.is_in_range(id, start, end)
+    }
+}
+
+/// Whether `id` lies in the open interval `(start, end)`.
+pub fn is_in_range(id: u64, start: u64, end: u64) -> bool {
+    ID_SPACE.is_in_range(id, start, end)
+}
+
+/// Whether `id` lies in the half-open interval `(start, end]`.
+pub fn is_in_range_inclusive(id: u64, start: u64, end: u64) -> bool {
+    ID_SPACE.is_in_range_inclusive(id, start, end)
+}
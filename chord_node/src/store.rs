@@ -0,0 +1,221 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::sync::Mutex;
+
+use crate::node::StoredValue;
+
+/// Backend for a node's key-value pairs. Calls are made while holding the
+/// node's state lock, so implementations should not block for long; values
+/// are returned by value so a backend isn't tied to keeping them in memory.
+pub trait KvStore: Debug + Send + Sync {
+    fn get(&self, key: &str) -> Option<StoredValue>;
+    /// Stores `value` under `key`, returning the value it replaced.
+    fn put(&mut self, key: String, value: StoredValue) -> Option<StoredValue>;
+    /// Removes `key`, returning its value if it was present.
+    fn delete(&mut self, key: &str) -> Option<StoredValue>;
+    fn keys(&self) -> Vec<String>;
+    /// Every key with its value, for scans over the whole store.
+    fn entries(&self) -> Vec<(String, StoredValue)>;
+    /// Visits every entry in place. Backends that hold their values in
+    /// memory should override this, so a scan that only reads a few fields
+    /// doesn't copy every value.
+    fn for_each_entry(&self, f: &mut dyn FnMut(&str, &StoredValue)) {
+        for (key, value) in self.entries() {
+            f(&key, &value);
+        }
+    }
+    fn len(&self) -> usize;
+
+    fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops entries until the store is back within its limits and returns
+    /// the dropped keys. Keys we are primary for go first, least recently
+    /// used first; replicas only go once no primary key is left, since they
+    /// are what keeps other nodes' keys durable. Unbounded stores never evict.
+    fn evict(&mut self, _is_primary: &dyn Fn(&str) -> bool) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// The default backend: everything in a `HashMap`, lost when the node stops.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    entries: HashMap<String, StoredValue>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl KvStore for MemoryStore {
+    fn get(&self, key: &str) -> Option<StoredValue> {
+        self.entries.get(key).cloned()
+    }
+
+    fn put(&mut self, key: String, value: StoredValue) -> Option<StoredValue> {
+        self.entries.insert(key, value)
+    }
+
+    fn delete(&mut self, key: &str) -> Option<StoredValue> {
+        self.entries.remove(key)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.entries.keys().cloned().collect()
+    }
+
+    fn entries(&self) -> Vec<(String, StoredValue)> {
+        self.entries
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
+    fn for_each_entry(&self, f: &mut dyn FnMut(&str, &StoredValue)) {
+        for (key, value) in &self.entries {
+            f(key, value);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn contains_key(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+}
+
+/// In-memory store capped at a number of keys and/or bytes (key plus value
+/// length). Reads refresh recency, so `evict` drops the coldest keys.
+#[derive(Debug)]
+pub struct LruStore {
+    max_keys: Option<usize>,
+    max_bytes: Option<usize>,
+    // Behind a lock so `get` can refresh recency through `&self`
+    inner: Mutex<LruEntries>,
+}
+
+#[derive(Debug, Default)]
+struct LruEntries {
+    entries: HashMap<String, (StoredValue, u64)>,
+    // Last use tick -> key, oldest first
+    recency: BTreeMap<u64, String>,
+    next_tick: u64,
+    bytes: usize,
+}
+
+impl LruEntries {
+    fn touch(&mut self, key: &str) {
+        let tick = self.next_tick;
+        if let Some((_, last_used)) = self.entries.get_mut(key) {
+            self.recency.remove(last_used);
+            *last_used = tick;
+            self.recency.insert(tick, key.to_string());
+            self.next_tick += 1;
+        }
+    }
+
+    fn remove(&mut self, key: &str) -> Option<StoredValue> {
+        let (value, last_used) = self.entries.remove(key)?;
+        self.recency.remove(&last_used);
+        self.bytes -= entry_bytes(key, &value);
+        Some(value)
+    }
+}
+
+fn entry_bytes(key: &str, value: &StoredValue) -> usize {
+    key.len() + value.value.len()
+}
+
+impl LruStore {
+    pub fn new(max_keys: Option<usize>, max_bytes: Option<usize>) -> Self {
+        Self {
+            max_keys,
+            max_bytes,
+            inner: Mutex::new(LruEntries::default()),
+        }
+    }
+
+    fn over_limit(&self, keys: usize, bytes: usize) -> bool {
+        self.max_keys.is_some_and(|max| keys > max) || self.max_bytes.is_some_and(|max| bytes > max)
+    }
+}
+
+impl KvStore for LruStore {
+    fn get(&self, key: &str) -> Option<StoredValue> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.touch(key);
+        inner.entries.get(key).map(|(value, _)| value.clone())
+    }
+
+    fn put(&mut self, key: String, value: StoredValue) -> Option<StoredValue> {
+        let inner = self.inner.get_mut().unwrap();
+        let replaced = inner.remove(&key);
+        let tick = inner.next_tick;
+        inner.next_tick += 1;
+        inner.bytes += entry_bytes(&key, &value);
+        inner.recency.insert(tick, key.clone());
+        inner.entries.insert(key, (value, tick));
+        replaced
+    }
+
+    fn delete(&mut self, key: &str) -> Option<StoredValue> {
+        self.inner.get_mut().unwrap().remove(key)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.inner.lock().unwrap().entries.keys().cloned().collect()
+    }
+
+    fn entries(&self) -> Vec<(String, StoredValue)> {
+        let inner = self.inner.lock().unwrap();
+        inner
+            .entries
+            .iter()
+            .map(|(key, (value, _))| (key.clone(), value.clone()))
+            .collect()
+    }
+
+    fn for_each_entry(&self, f: &mut dyn FnMut(&str, &StoredValue)) {
+        for (key, (value, _)) in &self.inner.lock().unwrap().entries {
+            f(key, value);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.inner.lock().unwrap().entries.len()
+    }
+
+    fn evict(&mut self, is_primary: &dyn Fn(&str) -> bool) -> Vec<String> {
+        let mut inner = self.inner.lock().unwrap();
+        let (mut keys, mut bytes) = (inner.entries.len(), inner.bytes);
+        // Walk the recency index oldest first, primaries on the first pass
+        // and replicas on the second, until what is left fits
+        let mut evicted = Vec::new();
+        for primaries in [true, false] {
+            for key in inner.recency.values() {
+                if !self.over_limit(keys, bytes) {
+                    break;
+                }
+                if is_primary(key) == primaries {
+                    keys -= 1;
+                    bytes -= entry_bytes(key, &inner.entries[key].0);
+                    evicted.push(key.clone());
+                }
+            }
+        }
+        for key in &evicted {
+            inner.remove(key);
+        }
+        evicted
+    }
+}
@@ -0,0 +1,101 @@
+use crate::node::Node;
+use chord_proto::chord::chord_server::ChordServer;
+use hyper_util::rt::TokioIo;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tonic::transport::{Channel, Endpoint, Server};
+use tonic::Status;
+
+// Buffer size of each direction of an in-memory connection
+const IN_MEMORY_BUFFER_BYTES: usize = 64 * 1024;
+
+/// How a node reaches its peers. `Tcp` (the default) dials their address;
+/// `InMemory` connects to nodes registered in the same process, so tests
+/// can build rings without binding any sockets.
+#[derive(Debug, Clone, Default)]
+pub enum Transport {
+    #[default]
+    Tcp,
+    InMemory(InMemoryNetwork),
+}
+
+impl Transport {
+    /// A channel to the node at `endpoint` (e.g. `http://127.0.0.1:5000`).
+    pub async fn channel(&self, endpoint: &str) -> Result<Channel, Status> {
+        match self {
+            Transport::Tcp => Endpoint::from_shared(endpoint.to_string())
+                .map_err(|e| Status::invalid_argument(e.to_string()))?
+                .connect()
+                .await
+                .map_err(|e| Status::unavailable(e.to_string())),
+            Transport::InMemory(network) => network.channel(endpoint).await,
+        }
+    }
+}
+
+/// Nodes reachable by address within this process. Every connection gets
+/// its own in-memory pipe with the node's gRPC service on the far end.
+/// Removing a node makes new connections to it fail like a dead peer.
+#[derive(Clone, Default)]
+pub struct InMemoryNetwork {
+    nodes: Arc<Mutex<HashMap<String, Arc<Node>>>>,
+}
+
+impl InMemoryNetwork {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes `node` reachable at its address.
+    pub fn register(&self, node: Arc<Node>) {
+        self.nodes.lock().unwrap().insert(node.addr.clone(), node);
+    }
+
+    /// Takes the node at `addr` off the network.
+    pub fn remove(&self, addr: &str) -> Option<Arc<Node>> {
+        self.nodes.lock().unwrap().remove(addr)
+    }
+
+    async fn channel(&self, endpoint: &str) -> Result<Channel, Status> {
+        let addr = endpoint
+            .split_once("://")
+            .map_or(endpoint, |(_, addr)| addr)
+            .to_string();
+        let nodes = self.nodes.clone();
+        Endpoint::from_shared(endpoint.to_string())
+            .map_err(|e| Status::invalid_argument(e.to_string()))?
+            .connect_with_connector(tower::service_fn(move |_| {
+                let node = nodes.lock().unwrap().get(&addr).cloned();
+                let addr = addr.clone();
+                async move {
+                    let node = node.ok_or_else(|| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::ConnectionRefused,
+                            format!("no node at {}", addr),
+                        )
+                    })?;
+                    let (client, server) = tokio::io::duplex(IN_MEMORY_BUFFER_BYTES);
+                    tokio::spawn(async move {
+                        let _ = Server::builder()
+                            .add_service(ChordServer::new((*node).clone()))
+                            .serve_with_incoming(tokio_stream::once(Ok::<_, std::io::Error>(
+                                server,
+                            )))
+                            .await;
+                    });
+                    Ok::<_, std::io::Error>(TokioIo::new(client))
+                }
+            }))
+            .await
+            .map_err(|e| Status::unavailable(e.to_string()))
+    }
+}
+
+// Nodes point back at the network, so only list the addresses
+impl fmt::Debug for InMemoryNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nodes = self.nodes.lock().unwrap();
+        f.debug_set().entries(nodes.keys()).finish()
+    }
+}
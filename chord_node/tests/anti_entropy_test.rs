@@ -0,0 +1,109 @@
+use chord_node::Node;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{ChangeEvent, Empty, PutRequest};
+use chord_proto::hash_addr;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::{Request, Streaming};
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+async fn watch(node: &Arc<Node>) -> Streaming<ChangeEvent> {
+    let mut client = ChordClient::connect(format!("http://{}", node.addr))
+        .await
+        .unwrap();
+    client
+        .watch_changes(Request::new(Empty {}))
+        .await
+        .expect("WatchChanges failed")
+        .into_inner()
+}
+
+/// Everything the stream delivers until it stays quiet for a while.
+async fn drain(stream: &mut Streaming<ChangeEvent>) -> Vec<ChangeEvent> {
+    let mut events = Vec::new();
+    while let Ok(Ok(Some(event))) =
+        tokio::time::timeout(Duration::from_millis(500), stream.message()).await
+    {
+        events.push(event);
+    }
+    events
+}
+
+#[tokio::test]
+async fn test_anti_entropy_only_resends_differing_buckets() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    for i in 0..3 {
+        let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    for i in 0..30 {
+        nodes[0]
+            .put(Request::new(PutRequest {
+                key: format!("ae_key_{}", i),
+                value: "v".into(),
+                ..Default::default()
+            }))
+            .await
+            .expect("Put failed");
+    }
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let key = "ae_key_0";
+    let owner_id = nodes[0]
+        .find_successor_internal(hash_addr(key))
+        .await
+        .unwrap()
+        .id;
+    let primary = nodes.iter().find(|n| n.id == owner_id).unwrap().clone();
+    let (pred_id, replica_id) = {
+        let state = primary.state.read().await;
+        (
+            state.predecessor.as_ref().unwrap().id,
+            state.successor_list[0].id,
+        )
+    };
+    let replica = nodes.iter().find(|n| n.id == replica_id).unwrap().clone();
+    let mut replica_events = watch(&replica).await;
+
+    // In sync: the digests match and nothing is resent
+    primary.maintain_replication().await;
+    let events = drain(&mut replica_events).await;
+    assert!(
+        events.is_empty(),
+        "Resent {} keys to an in-sync replica",
+        events.len()
+    );
+
+    // Lose one key on the replica; only its bucket should be resent
+    replica.state.write().await.store.delete(key);
+    primary.maintain_replication().await;
+    let events = drain(&mut replica_events).await;
+    println!(
+        "Resent after divergence: {:?}",
+        events.iter().map(|e| &e.key).collect::<Vec<_>>()
+    );
+
+    assert!(
+        replica.state.read().await.store.contains_key(key),
+        "Key was not repaired"
+    );
+    let tree = primary.digest(pred_id, primary.id, 1).await;
+    let bucket = tree.bucket(hash_addr(key));
+    assert!(events.iter().any(|e| e.key == key));
+    for event in &events {
+        assert_eq!(
+            tree.bucket(hash_addr(&event.key)),
+            bucket,
+            "Key '{}' from an in-sync bucket was resent",
+            event.key
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use chord_node::Node;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{AppendRequest, GetRequest, PutRequest};
+use chord_proto::hash_addr;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::{Code, Request};
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+fn append(key: &str, value: &str, request_id: &str) -> Request<AppendRequest> {
+    Request::new(AppendRequest {
+        key: key.to_string(),
+        value: value.as_bytes().to_vec(),
+        request_id: request_id.to_string(),
+        ..Default::default()
+    })
+}
+
+fn get(key: &str) -> Request<GetRequest> {
+    Request::new(GetRequest {
+        key: key.to_string(),
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn test_appends_from_any_node_build_one_list() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut _handles = Vec::new();
+    for i in 0..3 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        _handles.push(h);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    let key = "events";
+    for (i, node) in nodes.iter().enumerate() {
+        node.append(append(key, &format!("e{}", i), &format!("req-{}", i)))
+            .await
+            .unwrap();
+    }
+    // A retried append is applied once
+    nodes[0].append(append(key, "e2", "req-2")).await.unwrap();
+
+    let list = nodes[1].get_list(get(key)).await.unwrap().into_inner();
+    assert!(list.found);
+    assert_eq!(
+        list.items,
+        vec![b"e0".to_vec(), b"e1".to_vec(), b"e2".to_vec()]
+    );
+    let owner = nodes[0]
+        .find_successor_internal(hash_addr(key))
+        .await
+        .unwrap();
+    assert_eq!(list.owner_id, owner.id);
+
+    // Replicas hold the whole list
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    for node in nodes.iter().filter(|n| n.id != owner.id) {
+        let replica = node.state.read().await.store.get(key).unwrap();
+        assert_eq!(replica.list_items().unwrap().unwrap().len(), 3);
+    }
+
+    let missing = nodes[2]
+        .get_list(get("nothing"))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!missing.found);
+    assert!(missing.items.is_empty());
+}
+
+#[tokio::test]
+async fn test_append_to_plain_value_is_refused() {
+    let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+    stabilize_ring(std::slice::from_ref(&node), 3).await;
+
+    node.put(Request::new(PutRequest {
+        key: "plain".to_string(),
+        value: b"v".to_vec(),
+        ..Default::default()
+    }))
+    .await
+    .unwrap();
+
+    let err = node
+        .append(append("plain", "x", ""))
+        .await
+        .expect_err("A plain value is not a list");
+    assert_eq!(err.code(), Code::FailedPrecondition);
+    let err = node.get_list(get("plain")).await.unwrap_err();
+    assert_eq!(err.code(), Code::FailedPrecondition);
+    assert_eq!(
+        node.state.read().await.store.get("plain").unwrap().value,
+        b"v"
+    );
+}
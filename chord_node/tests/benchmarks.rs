@@ -1,3 +1,5 @@
+use chord_node::constants::FINGER_TABLE_SIZE;
+use chord_node::ring::{is_in_range, is_in_range_inclusive};
 use chord_proto::chord::chord_server::Chord;
 use chord_proto::chord::{GetRequest, PutRequest};
 use chord_proto::hash_addr;
@@ -10,31 +12,16 @@ use tonic::Request;
 mod common;
 use common::{stabilize_ring, start_node};
 
-// Helper for range checks (local implementation since Node::is_in_range is private)
-fn is_in_range(id: u64, start: u64, end: u64) -> bool {
-    if start < end {
-        id > start && id < end
-    } else {
-        id > start || id < end
-    }
-}
-
-fn is_in_range_inclusive(id: u64, start: u64, end: u64) -> bool {
-    if start < end {
-        id > start && id <= end
-    } else {
-        id > start || id <= end
-    }
-}
-
-// Helper to simulate lookup and count hops locally
+// Helper to simulate lookup and count hops locally, along with the number
+// of finger entries examined on the way
 async fn simulate_lookup_hops(
     start_node_id: u64,
     key_id: u64,
     nodes_map: &HashMap<u64, &Arc<chord_node::Node>>,
-) -> usize {
+) -> (usize, usize) {
     let mut current_node = nodes_map.get(&start_node_id).expect("Start node not found");
     let mut hops = 0;
+    let mut scanned = 0;
     let mut visited = std::collections::HashSet::new();
 
     loop {
@@ -47,15 +34,13 @@ async fn simulate_lookup_hops(
         let successor = state.successor_list[0].clone();
 
         if is_in_range_inclusive(key_id, current_node.id, successor.id) {
-            return hops + 1;
+            return (hops + 1, scanned);
         }
 
         let mut next_node_info = successor.clone();
         // Find closest preceding finger
-        for finger in state.finger_table.iter().rev() {
-            if finger.address.is_empty() {
-                continue;
-            }
+        for finger in state.distinct_fingers() {
+            scanned += 1;
             if is_in_range(finger.id, current_node.id, key_id) {
                 next_node_info = finger.clone();
                 break;
@@ -71,13 +56,15 @@ async fn simulate_lookup_hops(
             break;
         }
     }
-    hops
+    (hops, scanned)
 }
 
 #[tokio::test]
 async fn benchmark_scalability_hops() {
     println!("\n=== Benchmark 1: Scalability (Average Hops vs Network Size) ===");
-    println!("Nodes,Avg_Hops");
+    // Fingers scanned per lookup, compared to walking all FINGER_TABLE_SIZE slots per hop,
+    // and the time to pick a hop with closest_preceding_node vs ranking every finger
+    println!("Nodes,Avg_Hops,Avg_Fingers_Scanned,Full_Table_Scan,Closest_Hop_Ns,Range_Sort_Ns");
 
     let sizes = [10, 20, 30, 40, 50];
 
@@ -106,18 +93,53 @@ async fn benchmark_scalability_hops() {
 
         let num_lookups = 50;
         let mut total_hops = 0;
+        let mut total_scanned = 0;
+        let mut closest_time = Duration::ZERO;
+        let mut range_sort_time = Duration::ZERO;
         use rand::Rng;
         let mut rng = rand::thread_rng();
 
         for _ in 0..num_lookups {
             let start_idx = rng.gen_range(0..num_nodes);
             let key_id: u64 = rng.gen();
-            let hops = simulate_lookup_hops(nodes[start_idx].id, key_id, &nodes_map).await;
+            let (hops, scanned) =
+                simulate_lookup_hops(nodes[start_idx].id, key_id, &nodes_map).await;
             total_hops += hops;
+            total_scanned += scanned;
+
+            let node = &nodes[start_idx];
+            let started = Instant::now();
+            let closest = node.closest_preceding_node(key_id).await;
+            closest_time += started.elapsed();
+            let started = Instant::now();
+            let mut candidates: Vec<_> = node
+                .state
+                .read()
+                .await
+                .distinct_fingers()
+                .into_iter()
+                .filter(|f| is_in_range(f.id, node.id, key_id))
+                .collect();
+            candidates.sort_by_key(|c| std::cmp::Reverse(c.id.wrapping_sub(node.id)));
+            range_sort_time += started.elapsed();
+            assert_eq!(
+                candidates.first().map_or(node.id, |c| c.id),
+                closest.id,
+                "closest_preceding_node disagrees with the ranked candidates"
+            );
         }
 
         let avg_hops = total_hops as f64 / num_lookups as f64;
-        println!("{},{:.2}", num_nodes, avg_hops);
+        let avg_scanned = total_scanned as f64 / num_lookups as f64;
+        println!(
+            "{},{:.2},{:.2},{:.2},{},{}",
+            num_nodes,
+            avg_hops,
+            avg_scanned,
+            avg_hops * FINGER_TABLE_SIZE as f64,
+            closest_time.as_nanos() / num_lookups as u128,
+            range_sort_time.as_nanos() / num_lookups as u128
+        );
     }
 }
 
@@ -148,24 +170,29 @@ async fn benchmark_load_balancing() {
         let key = format!("key-{}", i);
         let req = Request::new(PutRequest {
             key: key.clone(),
-            value: "val".to_string(),
+            value: "val".into(),
+            ..Default::default()
         });
         nodes[i % NUM_NODES].put(req).await.expect("Put failed");
     }
 
     tokio::time::sleep(Duration::from_millis(500)).await;
 
+    let report = nodes[0].distribution().await.expect("Distribution failed");
     println!("Node_ID,Key_Count");
-    for node in &nodes {
-        let state = node.state.read().await;
-        println!("{},{}", node.id, state.store.len());
+    for load in &report.nodes {
+        println!("{},{}", load.node.as_ref().unwrap().id, load.primary_keys);
     }
+    println!(
+        "Gini: {:.3}, max/mean: {:.2}",
+        report.gini, report.max_to_mean
+    );
 }
 
 #[tokio::test]
 async fn benchmark_concurrent_throughput() {
     println!("\n=== Benchmark 3: Concurrent Throughput ===");
-    println!("Clients,Ops_Per_Sec");
+    println!("Clients,Ops_Per_Sec,Replication_Dials,Forward_Rejections");
 
     const NUM_NODES: usize = 10;
     let mut nodes = Vec::new();
@@ -196,10 +223,16 @@ async fn benchmark_concurrent_throughput() {
                     let _ = node
                         .put(Request::new(PutRequest {
                             key: key.clone(),
-                            value: "val".to_string(),
+                            value: "val".into(),
+                            ..Default::default()
+                        }))
+                        .await;
+                    let _ = node
+                        .get(Request::new(GetRequest {
+                            key,
+                            ..Default::default()
                         }))
                         .await;
-                    let _ = node.get(Request::new(GetRequest { key })).await;
                 }
             });
             handles.push(handle);
@@ -212,9 +245,22 @@ async fn benchmark_concurrent_throughput() {
         let duration = start.elapsed();
         let total_ops = num_clients * ops_per_client * 2; // put + get
         let ops_per_sec = total_ops as f64 / duration.as_secs_f64();
+        // Connections replication has opened so far, across the whole ring
+        let dials: u64 = nodes.iter().map(|n| n.replication_dials()).sum();
+        // Requests the forward limiter turned away so far, across the whole ring
+        let rejected: u64 = nodes.iter().map(|n| n.forwards_rejected()).sum();
 
-        println!("{},{:.2}", num_clients, ops_per_sec);
+        println!("{},{:.2},{},{}", num_clients, ops_per_sec, dials, rejected);
     }
+
+    // Pooled replication reuses one connection per peer, so dials stay near
+    // the number of successor links no matter how many writes went out
+    let dials: u64 = nodes.iter().map(|n| n.replication_dials()).sum();
+    assert!(
+        dials <= (NUM_NODES * NUM_NODES) as u64,
+        "Replication dialed {} connections",
+        dials
+    );
 }
 
 #[tokio::test]
@@ -267,7 +313,8 @@ async fn benchmark_replication_delay() {
         let start = Instant::now();
         let req = Request::new(PutRequest {
             key: key.clone(),
-            value: "val".to_string(),
+            value: "val".into(),
+            ..Default::default()
         });
         primary.put(req).await.expect("Put failed");
 
@@ -322,7 +369,8 @@ async fn benchmark_latency_cdf() {
         nodes[0]
             .put(Request::new(PutRequest {
                 key,
-                value: "x".to_string(),
+                value: "x".into(),
+                ..Default::default()
             }))
             .await
             .ok();
@@ -338,7 +386,12 @@ async fn benchmark_latency_cdf() {
         let node_idx = rng.gen_range(0..NUM_NODES);
 
         let start = Instant::now();
-        let _ = nodes[node_idx].get(Request::new(GetRequest { key })).await;
+        let _ = nodes[node_idx]
+            .get(Request::new(GetRequest {
+                key,
+                ..Default::default()
+            }))
+            .await;
         let duration = start.elapsed().as_micros();
         println!("{}", duration);
     }
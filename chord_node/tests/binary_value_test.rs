@@ -0,0 +1,44 @@
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::{GetRequest, PutRequest};
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_binary_value_round_trip() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, _h2) = start_node("127.0.0.1:0".to_string()).await;
+    node2.join(node1.addr.clone()).await.unwrap();
+    stabilize_ring(&[node1.clone(), node2.clone()], 10).await;
+
+    // Not valid UTF-8, and full of null bytes
+    let value: Vec<u8> = vec![0, 159, 146, 150, 0, 0, 255, b'a', 0];
+    let key = "binary_key";
+
+    let mut client1 = ChordClient::connect(format!("http://{}", node1.addr))
+        .await
+        .unwrap();
+    client1
+        .put(Request::new(PutRequest {
+            key: key.to_string(),
+            value: value.clone(),
+            ..Default::default()
+        }))
+        .await
+        .expect("Put failed");
+
+    let mut client2 = ChordClient::connect(format!("http://{}", node2.addr))
+        .await
+        .unwrap();
+    let resp = client2
+        .get(Request::new(GetRequest {
+            key: key.to_string(),
+            ..Default::default()
+        }))
+        .await
+        .expect("Get failed")
+        .into_inner();
+    assert!(resp.found, "Key should be found");
+    assert_eq!(resp.value, value);
+}
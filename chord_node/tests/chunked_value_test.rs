@@ -0,0 +1,104 @@
+use chord_node::node::value_chunks;
+use chord_node::Node;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::{GetRequest, PutRequest};
+use chord_proto::hash_addr;
+use std::time::Duration;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_put_and_get_value_larger_than_grpc_limit() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, _h2) = start_node("127.0.0.1:0".to_string()).await;
+
+    node2.join(node1.addr.clone()).await.unwrap();
+    stabilize_ring(&[node1.clone(), node2.clone()], 10).await;
+
+    // Larger than tonic's default 4MB message limit
+    let key = "large_value_key";
+    let value: Vec<u8> = (0..5 * 1024 * 1024)
+        .map(|i| b'a' + (i % 26) as u8)
+        .collect();
+
+    let mut client1 = ChordClient::connect(format!("http://{}", node1.addr))
+        .await
+        .unwrap();
+    let chunks = value_chunks(PutRequest {
+        key: key.to_string(),
+        value: value.clone(),
+        ..Default::default()
+    });
+    assert!(chunks.len() > 1, "Value should be split into chunks");
+
+    let resp = client1
+        .put_stream(tokio_stream::iter(chunks))
+        .await
+        .expect("PutStream failed")
+        .into_inner();
+    assert!(resp.success);
+
+    let mut client2 = ChordClient::connect(format!("http://{}", node2.addr))
+        .await
+        .unwrap();
+    let mut stream = client2
+        .get_stream(Request::new(GetRequest {
+            key: key.to_string(),
+            ..Default::default()
+        }))
+        .await
+        .expect("GetStream failed")
+        .into_inner();
+
+    let first = stream.message().await.unwrap().expect("No chunks returned");
+    assert!(first.found, "Key should be found");
+    let mut data = first.data;
+    while let Some(chunk) = stream.message().await.unwrap() {
+        data.extend_from_slice(&chunk.data);
+    }
+    assert_eq!(data.len(), value.len());
+    assert!(data == value, "Reassembled value mismatch");
+
+    // The replica should have received the full value through the chunked path too
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    for node in [&node1, &node2] {
+        let state = node.state.read().await;
+        let stored = state.store.get(key).expect("Key missing on a replica");
+        assert_eq!(stored.value.len(), value.len());
+    }
+}
+
+#[tokio::test]
+async fn test_key_transfers_carry_values_larger_than_grpc_limit() {
+    let (node_a, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node_b, _h2) = start_node("127.0.0.1:0".to_string()).await;
+    let key = (0..)
+        .map(|i| format!("large_key_{}", i))
+        .find(|k| Node::is_in_range_inclusive(hash_addr(k), node_a.id, node_b.id))
+        .unwrap();
+    let value: Vec<u8> = (0..5 * 1024 * 1024)
+        .map(|i| b'a' + (i % 26) as u8)
+        .collect();
+    node_a
+        .put_internal(PutRequest {
+            key: key.clone(),
+            value: value.clone(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    // Joining pulls the key from A
+    node_b.join(node_a.addr.clone()).await.unwrap();
+    let pulled = node_b.state.read().await.store.get(&key);
+    assert_eq!(pulled.expect("key not pulled").value, value);
+
+    // Draining hands it back through TransferKeys
+    stabilize_ring(&[node_a.clone(), node_b.clone()], 10).await;
+    node_a.state.write().await.store.delete(&key);
+    node_b.drain_network().await.expect("Drain failed");
+    let handed = node_a.state.read().await.store.get(&key);
+    assert_eq!(handed.expect("key not handed over").value, value);
+}
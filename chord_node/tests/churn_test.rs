@@ -0,0 +1,13 @@
+mod common;
+use common::{run_churn, ChurnConfig};
+
+#[tokio::test]
+async fn test_moderate_churn_success_ratio() {
+    let report = run_churn(ChurnConfig::default()).await;
+
+    assert!(
+        report.success_ratio() >= 0.7,
+        "Success ratio {:.2} below threshold under moderate churn",
+        report.success_ratio()
+    );
+}
@@ -0,0 +1,43 @@
+use chord_proto::chord::NodeInfo;
+
+mod common;
+use common::start_node;
+
+fn info(id: u64) -> NodeInfo {
+    NodeInfo {
+        id,
+        address: format!("127.0.0.1:{}", 10_000 + id % 1000),
+        observer: false,
+    }
+}
+
+#[tokio::test]
+async fn test_closest_preceding_node_wraps_around() {
+    let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+    let me = node.id;
+    {
+        let mut state = node.state.write().await;
+        // Fingers at increasing distances from us, the last two past zero
+        // when we sit high on the ring
+        let distances = [1u64 << 10, 1 << 40, 1 << 62, (1 << 63) + (1 << 62)];
+        for (slot, d) in state.finger_table.iter_mut().zip(distances.iter().cycle()) {
+            *slot = info(me.wrapping_add(*d));
+        }
+    }
+
+    // Every finger precedes the id just behind us; the farthest one wins even
+    // if its raw id is smaller than the others'
+    let target = me.wrapping_sub(1);
+    let closest = node.closest_preceding_node(target).await;
+    assert_eq!(closest.id, me.wrapping_add((1 << 63) + (1 << 62)));
+
+    let target = me.wrapping_add(1 << 41);
+    assert_eq!(
+        node.closest_preceding_node(target).await.id,
+        me.wrapping_add(1 << 40)
+    );
+
+    // Nothing lies between us and our immediate neighbourhood
+    let target = me.wrapping_add(5);
+    assert_eq!(node.closest_preceding_node(target).await.id, me);
+}
@@ -0,0 +1,131 @@
+use chord_node::Node;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::{ClusterHealthRequest, HealthState, NodeInfo, PutRequest};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+fn info(node: &Node) -> NodeInfo {
+    NodeInfo {
+        id: node.id,
+        address: node.addr.clone(),
+        observer: false,
+    }
+}
+
+#[tokio::test]
+async fn test_cluster_health_walks_the_whole_ring() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut handles = Vec::new();
+    for _ in 0..4 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if let Some(first) = nodes.first() {
+            node.join(first.addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        handles.push(h);
+    }
+    stabilize_ring(&nodes, 15).await;
+    nodes[1]
+        .put_internal(PutRequest {
+            key: "health_key".to_string(),
+            value: b"v".to_vec(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    // Replicas are written in the background; wait until the owner and every
+    // replica hold the key, so the sizes below stay put while we compare
+    let copies = 1 + nodes[1].replication_count();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let mut held = 0;
+        for node in &nodes {
+            held += node.state.read().await.store.len();
+        }
+        if held == copies {
+            break;
+        }
+        assert!(
+            Instant::now() < deadline,
+            "Only {} of {} copies were written",
+            held,
+            copies
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    let mut client = ChordClient::connect(format!("http://{}", nodes[2].addr))
+        .await
+        .unwrap();
+    let report = client
+        .get_cluster_health(Request::new(ClusterHealthRequest::default()))
+        .await
+        .expect("GetClusterHealth failed")
+        .into_inner();
+    assert!(report.complete);
+    assert_eq!(report.nodes.len(), nodes.len());
+    // Ring order, starting with the node asked
+    assert_eq!(report.nodes[0].node.as_ref().unwrap().id, nodes[2].id);
+    for (entry, next) in report.nodes.iter().zip(report.nodes.iter().skip(1)) {
+        let node = nodes
+            .iter()
+            .find(|n| n.id == entry.node.as_ref().unwrap().id)
+            .unwrap();
+        assert_eq!(node.successor().await.id, next.node.as_ref().unwrap().id);
+    }
+    for entry in &report.nodes {
+        assert!(entry.reachable);
+        assert_eq!(entry.state(), HealthState::Ready);
+        let node = nodes
+            .iter()
+            .find(|n| n.id == entry.node.as_ref().unwrap().id)
+            .unwrap();
+        assert_eq!(entry.store_size, node.state.read().await.store.len() as u64);
+    }
+    let stored: u64 = report.nodes.iter().map(|entry| entry.store_size).sum();
+    assert!(stored >= 1);
+
+    // The cap stops the walk short
+    let capped = nodes[2].cluster_health(2).await;
+    assert!(!capped.complete);
+    assert_eq!(capped.nodes.len(), 2);
+
+    // A dead successor ends the walk there, marked unreachable
+    let successor = nodes[0].successor().await;
+    let dead = nodes.iter().position(|n| n.id == successor.id).unwrap();
+    handles[dead].abort();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let report = nodes[0].cluster_health(16).await;
+    assert!(!report.complete);
+    assert_eq!(report.nodes.len(), 2);
+    assert!(!report.nodes[1].reachable);
+    assert_eq!(report.nodes[1].node.as_ref().unwrap().id, successor.id);
+}
+
+#[tokio::test]
+async fn test_cluster_health_loop_that_skips_us_is_incomplete() {
+    let mut nodes = Vec::new();
+    for _ in 0..3 {
+        let (node, _handle) = start_node("127.0.0.1:0".to_string()).await;
+        nodes.push(node);
+    }
+    let (a, b, c) = (&nodes[0], &nodes[1], &nodes[2]);
+
+    // B and C point at each other and never lead back to A
+    a.set_neighbors(Some(info(c)), vec![info(b)]).await;
+    b.set_neighbors(Some(info(c)), vec![info(c)]).await;
+    c.set_neighbors(Some(info(b)), vec![info(b)]).await;
+
+    let report = a.cluster_health(16).await;
+    assert!(!report.complete);
+    let ids: Vec<u64> = report
+        .nodes
+        .iter()
+        .map(|entry| entry.node.as_ref().unwrap().id)
+        .collect();
+    assert_eq!(ids, vec![a.id, b.id, c.id]);
+}
@@ -1,4 +1,6 @@
-use chord_node::Node;
+#![allow(dead_code)]
+
+use chord_node::{InMemoryNetwork, Node, Transport};
 use chord_proto::chord::chord_server::ChordServer;
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -9,6 +11,14 @@ use tonic::transport::Server;
 /// Helper to start a node in a background task.
 /// Returns the Node Arc and a JoinHandle to the server task (allowing it to be aborted).
 pub async fn start_node(addr: String) -> (Arc<Node>, tokio::task::JoinHandle<()>) {
+    start_node_with(addr, Node::new).await
+}
+
+/// Like `start_node`, but `build` creates the node from its id and bound address.
+pub async fn start_node_with(
+    addr: String,
+    build: impl FnOnce(u64, String) -> Node,
+) -> (Arc<Node>, tokio::task::JoinHandle<()>) {
     let addr: SocketAddr = addr.parse().unwrap();
     let listener = TcpListener::bind(addr).await.unwrap();
     let local_addr = listener.local_addr().unwrap();
@@ -17,7 +27,7 @@ pub async fn start_node(addr: String) -> (Arc<Node>, tokio::task::JoinHandle<()>
     // Calculate ID based on the actual bound address
     let id = chord_proto::hash_addr(&local_addr_str);
 
-    let node = Node::new(id, local_addr_str.clone());
+    let node = build(id, local_addr_str.clone());
     let node = Arc::new(node);
     let node_clone = node.clone();
 
@@ -34,6 +44,17 @@ pub async fn start_node(addr: String) -> (Arc<Node>, tokio::task::JoinHandle<()>
     (node, handle)
 }
 
+/// A node reachable only through `network`, at a made-up address. No socket
+/// is bound, so these can't collide with anything else on the machine.
+pub fn start_in_memory_node(network: &InMemoryNetwork, addr: &str) -> Arc<Node> {
+    let id = chord_proto::hash_addr(addr);
+    let node = Arc::new(
+        Node::new(id, addr.to_string()).with_transport(Transport::InMemory(network.clone())),
+    );
+    network.register(node.clone());
+    node
+}
+
 pub async fn stabilize_ring(nodes: &[Arc<Node>], rounds: usize) {
     println!("Stabilizing ring for {} rounds...", rounds);
     for _ in 0..rounds {
@@ -45,3 +66,235 @@ pub async fn stabilize_ring(nodes: &[Arc<Node>], rounds: usize) {
         tokio::time::sleep(Duration::from_millis(100)).await;
     }
 }
+
+/// Parameters for a churn run. Join and kill rates are probabilities applied
+/// once per `churn_interval`.
+#[derive(Debug, Clone)]
+pub struct ChurnConfig {
+    pub initial_nodes: usize,
+    pub min_nodes: usize,
+    pub join_rate: f64,
+    pub kill_rate: f64,
+    pub churn_interval: Duration,
+    pub traffic_interval: Duration,
+    pub duration: Duration,
+}
+
+impl Default for ChurnConfig {
+    fn default() -> Self {
+        Self {
+            initial_nodes: 4,
+            min_nodes: 3,
+            join_rate: 0.5,
+            kill_rate: 0.3,
+            churn_interval: Duration::from_millis(1000),
+            traffic_interval: Duration::from_millis(50),
+            duration: Duration::from_secs(5),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ChurnReport {
+    pub successes: usize,
+    pub failures: usize,
+    pub joins: usize,
+    pub kills: usize,
+    /// Acknowledged writes that could not be read back once the ring settled.
+    pub consistency_violations: usize,
+}
+
+impl ChurnReport {
+    pub fn success_ratio(&self) -> f64 {
+        let total = self.successes + self.failures;
+        if total == 0 {
+            return 0.0;
+        }
+        self.successes as f64 / total as f64
+    }
+}
+
+type LiveNodes = Arc<tokio::sync::RwLock<Vec<(Arc<Node>, tokio::task::JoinHandle<()>)>>>;
+
+/// Continuously exercises a ring with put/get traffic while nodes join and
+/// are killed according to `config`, then checks every acknowledged write.
+pub async fn run_churn(config: ChurnConfig) -> ChurnReport {
+    use chord_proto::chord::chord_client::ChordClient;
+    use chord_proto::chord::{GetRequest, PutRequest};
+    use rand::Rng;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tonic::Request;
+
+    println!("Starting churn run: {:?}", config);
+    let live: LiveNodes = Arc::new(tokio::sync::RwLock::new(Vec::new()));
+
+    for i in 0..config.initial_nodes {
+        let (node, handle) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            let entry = live.read().await[0].0.addr.clone();
+            node.join(entry).await.expect("Initial join failed");
+        }
+        live.write().await.push((node, handle));
+    }
+    let initial: Vec<Arc<Node>> = live.read().await.iter().map(|(n, _)| n.clone()).collect();
+    stabilize_ring(&initial, 5).await;
+
+    let running = Arc::new(AtomicBool::new(true));
+
+    // Background maintenance standing in for the node binary's periodic loop
+    let maintenance_handle = {
+        let live = live.clone();
+        let running = running.clone();
+        tokio::spawn(async move {
+            while running.load(Ordering::SeqCst) {
+                let nodes: Vec<Arc<Node>> =
+                    live.read().await.iter().map(|(n, _)| n.clone()).collect();
+                for node in nodes {
+                    node.stabilize().await;
+                    node.fix_fingers().await;
+                    node.check_predecessor().await;
+                    node.maintain_replication().await;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        })
+    };
+
+    let traffic_handle = {
+        let live = live.clone();
+        let running = running.clone();
+        let traffic_interval = config.traffic_interval;
+        tokio::spawn(async move {
+            let mut successes = 0;
+            let mut failures = 0;
+            let mut acknowledged = Vec::new();
+            let mut i = 0usize;
+
+            while running.load(Ordering::SeqCst) {
+                i += 1;
+                let key = format!("churn_key_{}", i);
+                let value = format!("churn_value_{}", i);
+
+                let addr = {
+                    let nodes = live.read().await;
+                    nodes[i % nodes.len()].0.addr.clone()
+                };
+
+                let ok = match ChordClient::connect(format!("http://{}", addr)).await {
+                    Ok(mut client) => {
+                        let put_res = client
+                            .put(Request::new(PutRequest {
+                                key: key.clone(),
+                                value: value.clone().into_bytes(),
+                                ..Default::default()
+                            }))
+                            .await;
+                        if put_res.is_ok() {
+                            acknowledged.push((key.clone(), value.clone()));
+                            match client
+                                .get(Request::new(GetRequest {
+                                    key: key.clone(),
+                                    ..Default::default()
+                                }))
+                                .await
+                            {
+                                Ok(resp) => resp.into_inner().value == value.as_bytes(),
+                                Err(_) => false,
+                            }
+                        } else {
+                            false
+                        }
+                    }
+                    Err(_) => false,
+                };
+
+                if ok {
+                    successes += 1;
+                } else {
+                    failures += 1;
+                }
+                tokio::time::sleep(traffic_interval).await;
+            }
+            (successes, failures, acknowledged)
+        })
+    };
+
+    let mut report = ChurnReport::default();
+    let deadline = tokio::time::Instant::now() + config.duration;
+    while tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(config.churn_interval).await;
+
+        let (join, kill) = {
+            let mut rng = rand::thread_rng();
+            (
+                rng.gen_bool(config.join_rate),
+                rng.gen_bool(config.kill_rate),
+            )
+        };
+
+        if join {
+            let entry = {
+                let nodes = live.read().await;
+                let idx = rand::thread_rng().gen_range(0..nodes.len());
+                nodes[idx].0.addr.clone()
+            };
+            let (node, handle) = start_node("127.0.0.1:0".to_string()).await;
+            match node.join(entry).await {
+                Ok(_) => {
+                    println!("Churn: node {} joined", node.id);
+                    live.write().await.push((node, handle));
+                    report.joins += 1;
+                }
+                Err(e) => {
+                    println!("Churn: node {} failed to join: {}", node.id, e);
+                    handle.abort();
+                }
+            }
+        }
+
+        if kill {
+            let mut nodes = live.write().await;
+            if nodes.len() > config.min_nodes {
+                let idx = rand::thread_rng().gen_range(0..nodes.len());
+                let (node, handle) = nodes.remove(idx);
+                handle.abort();
+                println!("Churn: killed node {}", node.id);
+                report.kills += 1;
+            }
+        }
+    }
+
+    running.store(false, Ordering::SeqCst);
+    let (successes, failures, acknowledged) = traffic_handle.await.unwrap();
+    maintenance_handle.await.unwrap();
+    report.successes = successes;
+    report.failures = failures;
+
+    let survivors: Vec<Arc<Node>> = live.read().await.iter().map(|(n, _)| n.clone()).collect();
+    stabilize_ring(&survivors, 10).await;
+
+    for (key, value) in acknowledged {
+        use chord_proto::chord::chord_server::Chord;
+        let resp = survivors[0]
+            .get(Request::new(GetRequest {
+                key: key.clone(),
+                ..Default::default()
+            }))
+            .await;
+        match resp {
+            Ok(resp) if resp.get_ref().value == value.as_bytes() => {}
+            _ => report.consistency_violations += 1,
+        }
+    }
+
+    println!(
+        "Churn finished: {} successes, {} failures ({:.1}% ok), {} joins, {} kills, {} consistency violations",
+        report.successes,
+        report.failures,
+        report.success_ratio() * 100.0,
+        report.joins,
+        report.kills,
+        report.consistency_violations
+    );
+    report
+}
@@ -0,0 +1,58 @@
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{GetRequest, PutRequest};
+use tonic::Request;
+
+mod common;
+use common::start_node;
+
+#[tokio::test]
+async fn test_conditional_get_if_modified_since() {
+    let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+
+    let key = "conditional_key";
+    let value = "conditional_value";
+
+    node.put(Request::new(PutRequest {
+        key: key.to_string(),
+        value: value.into(),
+        ..Default::default()
+    }))
+    .await
+    .expect("Put failed");
+
+    let updated_at = {
+        let state = node.state.read().await;
+        state.store.get(key).expect("Key not stored").updated_at
+    };
+    println!("Key '{}' updated_at {}", key, updated_at);
+
+    // A `since` newer than the value's timestamp should not return the value
+    let resp = node
+        .get(Request::new(GetRequest {
+            key: key.to_string(),
+            since: updated_at + 1,
+            ..Default::default()
+        }))
+        .await
+        .expect("Get failed")
+        .into_inner();
+    assert!(resp.found, "Key should be found");
+    assert!(resp.not_modified, "Expected not_modified for newer since");
+    assert!(resp.value.is_empty(), "Value should not be sent");
+    assert_eq!(resp.updated_at, updated_at);
+
+    // An older `since` should return the value
+    let resp = node
+        .get(Request::new(GetRequest {
+            key: key.to_string(),
+            since: updated_at - 1,
+            ..Default::default()
+        }))
+        .await
+        .expect("Get failed")
+        .into_inner();
+    assert!(resp.found, "Key should be found");
+    assert!(!resp.not_modified, "Expected value for older since");
+    assert_eq!(resp.value, value.as_bytes());
+    assert_eq!(resp.updated_at, updated_at);
+}
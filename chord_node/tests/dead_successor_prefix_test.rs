@@ -0,0 +1,40 @@
+use chord_node::Node;
+use std::sync::Arc;
+use std::time::Duration;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_stabilize_skips_all_dead_successors_at_once() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut handles = Vec::new();
+    for i in 0..5 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        handles.push(h);
+    }
+    stabilize_ring(&nodes, 15).await;
+
+    let node = nodes[0].clone();
+    let successors = node.state.read().await.successor_list.clone();
+    assert!(successors.len() >= 3);
+
+    // Kill the first two successors without letting anyone else notice
+    for dead in &successors[..2] {
+        let i = nodes.iter().position(|n| n.id == dead.id).unwrap();
+        handles[i].abort();
+    }
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    node.stabilize().await;
+
+    let state = node.state.read().await;
+    assert_eq!(
+        state.successor_list[0].id, successors[2].id,
+        "The first live successor should be promoted in one round"
+    );
+}
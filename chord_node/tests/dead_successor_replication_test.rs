@@ -0,0 +1,72 @@
+use chord_node::constants::REPLICATION_COUNT;
+use chord_node::Node;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::PutRequest;
+use chord_proto::hash_addr;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_replicas_skip_a_dead_successor() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut handles = Vec::new();
+    for i in 0..4 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        handles.push(h);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    // Kill the owner's immediate successor before anyone notices
+    let owner = nodes[0].clone();
+    let successors = owner.state.read().await.successor_list.clone();
+    let dead = successors[0].clone();
+    let dead_idx = nodes.iter().position(|n| n.id == dead.id).unwrap();
+    handles[dead_idx].abort();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(owner.successor().await.id, dead.id);
+
+    let pred = owner.state.read().await.predecessor.clone().unwrap();
+    let key = (0..)
+        .map(|i| format!("key_{}", i))
+        .find(|k| Node::is_in_range_inclusive(hash_addr(k), pred.id, owner.id))
+        .unwrap();
+    owner
+        .put(Request::new(PutRequest {
+            key: key.clone(),
+            value: b"v".to_vec(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let live_replicas: Vec<&Arc<Node>> = successors[1..=REPLICATION_COUNT]
+        .iter()
+        .map(|s| nodes.iter().find(|n| n.id == s.id).unwrap())
+        .collect();
+    for replica in &live_replicas {
+        assert!(
+            replica.state.read().await.store.get(&key).is_some(),
+            "Live successor {} should hold a replica",
+            replica.id
+        );
+    }
+
+    // Anti-entropy also targets the live successors
+    for replica in &live_replicas {
+        replica.state.write().await.store.delete(&key);
+    }
+    owner.maintain_replication().await;
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    for replica in &live_replicas {
+        assert!(replica.state.read().await.store.get(&key).is_some());
+    }
+}
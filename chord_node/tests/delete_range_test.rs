@@ -0,0 +1,71 @@
+use chord_node::Node;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{DeleteRangeRequest, GetRequest, PutRequest};
+use chord_proto::hash_addr;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_delete_range_across_nodes() {
+    let (first, _h) = start_node("127.0.0.1:0".to_string()).await;
+    let mut nodes = vec![first];
+    for _ in 0..2 {
+        let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+        node.join(nodes[0].addr.clone()).await.unwrap();
+        nodes.push(node);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    let keys: Vec<String> = (0..40).map(|i| format!("range_key_{}", i)).collect();
+    for key in &keys {
+        nodes[0]
+            .put(Request::new(PutRequest {
+                key: key.clone(),
+                value: "value".into(),
+                ..Default::default()
+            }))
+            .await
+            .expect("Put failed");
+    }
+
+    // Half of the id space, which spans more than one owner
+    let start_id = u64::MAX / 4;
+    let end_id = u64::MAX / 4 * 3;
+    let in_range = |key: &str| Node::is_in_range_inclusive(hash_addr(key), start_id, end_id);
+    let expected = keys.iter().filter(|k| in_range(k)).count() as u64;
+    println!("Expecting {} of {} keys in range", expected, keys.len());
+
+    let resp = nodes[1]
+        .delete_range(Request::new(DeleteRangeRequest { start_id, end_id }))
+        .await
+        .expect("DeleteRange failed")
+        .into_inner();
+    assert_eq!(resp.deleted, expected);
+
+    // Neither owners nor replicas may keep a deleted key
+    for node in &nodes {
+        let state = node.state.read().await;
+        for key in state.store.keys() {
+            assert!(!in_range(&key), "Node {} still holds '{}'", node.id, key);
+        }
+    }
+
+    for key in &keys {
+        let resp = nodes[2]
+            .get(Request::new(GetRequest {
+                key: key.clone(),
+                ..Default::default()
+            }))
+            .await
+            .expect("Get failed")
+            .into_inner();
+        assert_eq!(
+            resp.found,
+            !in_range(key),
+            "Unexpected result for '{}'",
+            key
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use chord_client::{is_unreachable, retry_delay, DhtClient};
+use chord_node::Node;
+use std::sync::Arc;
+use std::time::Duration;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_dht_client_round_trip() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut _handles = Vec::new();
+    for i in 0..3 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        _handles.push(h);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    let client = DhtClient::new(&nodes[0].addr).unwrap();
+    for i in 0..10 {
+        client
+            .put(&format!("key_{}", i), format!("value_{}", i))
+            .await
+            .unwrap();
+    }
+
+    // Any node serves any key
+    let other = DhtClient::new(&format!("http://{}", nodes[2].addr)).unwrap();
+    for i in 0..10 {
+        let value = other.get(&format!("key_{}", i)).await.unwrap();
+        assert_eq!(value, Some(format!("value_{}", i).into_bytes()));
+    }
+    assert_eq!(other.get("missing").await.unwrap(), None);
+
+    assert!(client.exists("key_3").await.unwrap());
+    assert!(client.delete("key_3").await.unwrap());
+    assert!(!client.exists("key_3").await.unwrap());
+    assert!(!client.delete("key_3").await.unwrap());
+    assert!(other.exists("key_4").await.unwrap());
+
+    client.append("log", "a").await.unwrap();
+    other.append("log", "b").await.unwrap();
+    assert_eq!(
+        client.get_list("log").await.unwrap(),
+        Some(vec![b"a".to_vec(), b"b".to_vec()])
+    );
+}
+
+#[tokio::test]
+async fn test_dht_client_gives_up_after_retries() {
+    // Nothing listens here, so every attempt is unavailable
+    let client = DhtClient::new("127.0.0.1:1").unwrap().with_retries(1);
+    // Not reaching the ring is an error, never a missing key
+    let err = client.get("key").await.unwrap_err();
+    assert_eq!(err.code(), tonic::Code::Unavailable);
+    assert!(is_unreachable(&err));
+}
+
+#[test]
+fn test_retry_delay_doubles_up_to_a_cap() {
+    assert_eq!(retry_delay(0), Duration::from_millis(200));
+    assert_eq!(retry_delay(1), Duration::from_millis(400));
+    assert_eq!(retry_delay(2), Duration::from_millis(800));
+    // Past the cap, including counts whose doubling would overflow
+    let cap = retry_delay(10);
+    assert!(cap < Duration::from_secs(60));
+    for attempt in [20, 32, 64, u32::MAX] {
+        assert_eq!(retry_delay(attempt), cap);
+    }
+}
@@ -0,0 +1,30 @@
+use std::collections::HashSet;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_distinct_fingers_collapse_repeated_entries() {
+    let mut nodes = Vec::new();
+    for _ in 0..3 {
+        let (node, _handle) = start_node("127.0.0.1:0".to_string()).await;
+        nodes.push(node);
+    }
+    for node in nodes.iter().skip(1) {
+        node.join(nodes[0].addr.clone()).await.unwrap();
+    }
+    stabilize_ring(&nodes, 20).await;
+
+    let state = nodes[0].state.read().await;
+    let distinct = state.distinct_fingers();
+    let ids: HashSet<u64> = distinct.iter().map(|f| f.id).collect();
+    println!("Distinct fingers: {:?}", ids);
+
+    assert_eq!(ids.len(), distinct.len(), "Entries should be unique");
+    assert!(
+        distinct.len() <= nodes.len(),
+        "A 3 node ring has at most 3 distinct fingers"
+    );
+    let table_ids: HashSet<u64> = state.finger_table.iter().map(|f| f.id).collect();
+    assert_eq!(ids, table_ids, "Every node in the table should be kept");
+}
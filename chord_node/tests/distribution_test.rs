@@ -0,0 +1,84 @@
+use chord_node::Node;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::{Empty, PutRequest};
+use chord_proto::distribution::{gini, max_to_mean};
+use std::sync::Arc;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[test]
+fn test_skew_measures() {
+    assert_eq!(gini(&[10, 10, 10, 10]), 0.0);
+    assert_eq!(max_to_mean(&[10, 10, 10, 10]), 1.0);
+    // One node holding everything
+    assert_eq!(gini(&[0, 0, 0, 40]), 0.75);
+    assert_eq!(max_to_mean(&[0, 0, 0, 40]), 4.0);
+    // Order doesn't matter
+    assert_eq!(gini(&[30, 10]), gini(&[10, 30]));
+    assert_eq!(gini(&[10, 30]), 0.25);
+    assert_eq!(gini(&[]), 0.0);
+    assert_eq!(max_to_mean(&[0, 0]), 0.0);
+}
+
+#[tokio::test]
+async fn test_distribution_counts_every_primary_once() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut _handles = Vec::new();
+    for _ in 0..4 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if let Some(first) = nodes.first() {
+            node.join(first.addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        _handles.push(h);
+    }
+    stabilize_ring(&nodes, 15).await;
+
+    for i in 0..60 {
+        nodes[i % nodes.len()]
+            .put_internal(PutRequest {
+                key: format!("dist_{}", i),
+                value: b"v".to_vec(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    let mut client = ChordClient::connect(format!("http://{}", nodes[2].addr))
+        .await
+        .unwrap();
+    let report = client
+        .distribution(Request::new(Empty {}))
+        .await
+        .expect("Distribution failed")
+        .into_inner();
+
+    // Every node once, in id order, with the keys it is primary for
+    let ids: Vec<u64> = report
+        .nodes
+        .iter()
+        .map(|load| load.node.as_ref().unwrap().id)
+        .collect();
+    let mut expected: Vec<u64> = nodes.iter().map(|n| n.id).collect();
+    expected.sort_unstable();
+    assert_eq!(ids, expected);
+    for load in &report.nodes {
+        let node = nodes
+            .iter()
+            .find(|n| n.id == load.node.as_ref().unwrap().id)
+            .unwrap();
+        assert_eq!(
+            load.primary_keys,
+            node.scan_local_prefix("dist_").await.len() as u64
+        );
+    }
+    let total: u64 = report.nodes.iter().map(|load| load.primary_keys).sum();
+    assert_eq!(total, 60);
+
+    let counts: Vec<u64> = report.nodes.iter().map(|l| l.primary_keys).collect();
+    assert_eq!(report.gini, gini(&counts));
+    assert_eq!(report.max_to_mean, max_to_mean(&counts));
+}
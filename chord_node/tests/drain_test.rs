@@ -0,0 +1,154 @@
+use chord_node::constants::{MAX_VALUE_BYTES, REDIRECT_METADATA_KEY};
+use chord_node::{Node, StoredValue};
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{Empty, GetRequest, HealthState, PutRequest};
+use chord_proto::hash_addr;
+use std::sync::Arc;
+use tonic::{Code, Request};
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+fn put_request(key: String) -> PutRequest {
+    PutRequest {
+        value: key.clone().into_bytes(),
+        key,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_drain_hands_off_keys_and_refuses_writes() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut handles = Vec::new();
+    for i in 0..4 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        handles.push(h);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    let mut client = ChordClient::connect(format!("http://{}", nodes[0].addr))
+        .await
+        .unwrap();
+    let keys: Vec<String> = (0..40).map(|i| format!("drain_key_{}", i)).collect();
+    for key in &keys {
+        client
+            .put(Request::new(put_request(key.clone())))
+            .await
+            .unwrap();
+    }
+
+    let drained = nodes[1].clone();
+    let (pred_id, successor) = {
+        let state = drained.state.read().await;
+        (
+            state.predecessor.as_ref().unwrap().id,
+            state.successor_list[0].clone(),
+        )
+    };
+    let owns = |key: &str| Node::is_in_range_inclusive(hash_addr(key), pred_id, drained.id);
+    let owned: Vec<&String> = keys.iter().filter(|k| owns(k)).collect();
+
+    let mut drained_client = ChordClient::connect(format!("http://{}", drained.addr))
+        .await
+        .unwrap();
+    let resp = drained_client
+        .drain(Request::new(Empty {}))
+        .await
+        .expect("Drain failed")
+        .into_inner();
+    assert_eq!(resp.keys_transferred, owned.len() as u64);
+    assert_eq!(drained.health().await, HealthState::Drained);
+
+    // The successor now holds every key the drained node owned
+    let successor_node = nodes.iter().find(|n| n.id == successor.id).unwrap();
+    let successor_keys = successor_node.state.read().await.store.keys();
+    for key in &owned {
+        assert!(successor_keys.contains(key), "successor missing {}", key);
+    }
+
+    // New writes for keys the drained node owns are refused with a redirect
+    let probe = (0..)
+        .map(|i| format!("drain_probe_{}", i))
+        .find(|k| owns(k))
+        .unwrap();
+    let err = drained_client
+        .put(Request::new(put_request(probe)))
+        .await
+        .expect_err("draining node accepted a write it owns");
+    assert_eq!(err.code(), Code::Unavailable);
+    assert_eq!(
+        err.metadata()
+            .get(REDIRECT_METADATA_KEY)
+            .and_then(|v| v.to_str().ok()),
+        Some(successor.address.as_str())
+    );
+
+    // Unlike leave, the process keeps serving; stop it now and nothing is lost
+    handles[1].abort();
+    let remaining: Vec<Arc<Node>> = nodes
+        .iter()
+        .filter(|n| n.id != drained.id)
+        .cloned()
+        .collect();
+    stabilize_ring(&remaining, 10).await;
+    for key in &keys {
+        let resp = client
+            .get(Request::new(GetRequest {
+                key: key.clone(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(resp.found, "lost {} after drain", key);
+    }
+}
+
+#[tokio::test]
+async fn test_drain_refuses_lone_node() {
+    let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+    let err = node.drain_network().await.unwrap_err();
+    assert_eq!(err.code(), Code::FailedPrecondition);
+    assert_eq!(node.health().await, HealthState::Starting);
+}
+
+#[tokio::test]
+async fn test_failed_drain_resumes_serving() {
+    let (node_a, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node_b, _h2) = start_node("127.0.0.1:0".to_string()).await;
+    node_b.join(node_a.addr.clone()).await.unwrap();
+    let nodes = vec![node_a.clone(), node_b.clone()];
+    stabilize_ring(&nodes, 10).await;
+
+    // A value over the limit (e.g. stored before the limit was lowered) is
+    // one the successor will always refuse
+    let owns = |key: &str| Node::is_in_range_inclusive(hash_addr(key), node_b.id, node_a.id);
+    let oversized = (0..)
+        .map(|i| format!("oversized_{}", i))
+        .find(|k| owns(k))
+        .unwrap();
+    node_a.state.write().await.store.put(
+        oversized.clone(),
+        StoredValue::new(vec![0; MAX_VALUE_BYTES + 1]),
+    );
+
+    let err = node_a.drain_network().await.unwrap_err();
+    assert_eq!(err.code(), Code::FailedPrecondition);
+    assert_eq!(node_a.health().await, HealthState::Ready);
+    assert!(node_a.state.read().await.store.contains_key(&oversized));
+
+    let probe = (0..)
+        .map(|i| format!("drain_probe_{}", i))
+        .find(|k| owns(k))
+        .unwrap();
+    node_a
+        .put(Request::new(put_request(probe)))
+        .await
+        .expect("node refused writes after a failed drain");
+}
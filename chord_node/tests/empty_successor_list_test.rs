@@ -0,0 +1,36 @@
+use chord_proto::chord::SuccessorList;
+
+mod common;
+use common::start_node;
+
+#[tokio::test]
+async fn test_empty_successor_list_self_heals() {
+    let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+
+    // Simulate the list being emptied, e.g. by a buggy successor response
+    node.state.write().await.successor_list.clear();
+
+    node.apply_successor_list(SuccessorList { successors: vec![] })
+        .await;
+
+    {
+        let state = node.state.read().await;
+        assert_eq!(state.successor_list.len(), 1);
+        assert_eq!(state.successor_list[0].id, node.id);
+    }
+
+    // Maintenance must keep working instead of panicking
+    node.state.write().await.successor_list.clear();
+    node.stabilize().await;
+    node.fix_fingers().await;
+
+    let successor = node
+        .find_successor_internal(node.id.wrapping_add(1))
+        .await
+        .expect("Lookup should succeed on a single healed node");
+    assert_eq!(successor.id, node.id);
+
+    let state = node.state.read().await;
+    assert!(!state.successor_list.is_empty());
+    assert_eq!(state.successor_list[0].id, node.id);
+}
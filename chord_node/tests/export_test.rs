@@ -0,0 +1,177 @@
+use chord_node::Node;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{Empty, Handshake, KeyValue, NodeInfo, PutRequest};
+use chord_proto::chunk::KeyValueAssembler;
+use chord_proto::{CHUNKED_EXPORT_PROTOCOL_VERSION, MIN_PROTOCOL_VERSION};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+async fn start_ring(size: usize) -> (Vec<Arc<Node>>, Vec<tokio::task::JoinHandle<()>>) {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut handles = Vec::new();
+    for i in 0..size {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        handles.push(h);
+    }
+    stabilize_ring(&nodes, 10).await;
+    (nodes, handles)
+}
+
+async fn export(node: &Node) -> Vec<KeyValue> {
+    let mut client = ChordClient::connect(format!("http://{}", node.addr))
+        .await
+        .unwrap();
+    let mut stream = client
+        .export(Request::new(Empty {}))
+        .await
+        .unwrap()
+        .into_inner();
+    let mut entries = Vec::new();
+    while let Some(kv) = stream.message().await.expect("Export failed") {
+        entries.push(kv);
+    }
+    entries
+}
+
+async fn export_chunks(node: &Node) -> Vec<KeyValue> {
+    let mut client = ChordClient::connect(format!("http://{}", node.addr))
+        .await
+        .unwrap();
+    let mut stream = client
+        .export_chunks(Request::new(Empty {}))
+        .await
+        .unwrap()
+        .into_inner();
+    let mut assembler = KeyValueAssembler::new();
+    let mut entries = Vec::new();
+    while let Some(chunk) = stream.message().await.expect("Export failed") {
+        entries.extend(assembler.push(chunk).unwrap());
+    }
+    entries.extend(assembler.finish());
+    entries
+}
+
+#[tokio::test]
+async fn test_export_streams_each_key_once() {
+    let (nodes, _handles) = start_ring(3).await;
+    const NUM_KEYS: usize = 100;
+    for i in 0..NUM_KEYS {
+        nodes[i % nodes.len()]
+            .put(Request::new(PutRequest {
+                key: format!("export_{}", i),
+                value: format!("value_{}", i).into_bytes(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+    }
+    // Let replication land so every node holds copies it must not re-export
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let entries = export(&nodes[1]).await;
+    assert_eq!(entries.len(), NUM_KEYS);
+    let keys: HashSet<&str> = entries.iter().map(|kv| kv.key.as_str()).collect();
+    assert_eq!(keys.len(), NUM_KEYS, "No key should be exported twice");
+
+    // An export loads into a fresh ring unchanged
+    let (fresh, _fresh_handles) = start_ring(2).await;
+    let mut client = ChordClient::connect(format!("http://{}", fresh[0].addr))
+        .await
+        .unwrap();
+    let resp = client
+        .import(Request::new(tokio_stream::iter(entries.clone())))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(resp.failed, 0);
+
+    let mut restored = export(&fresh[0]).await;
+    let mut original = entries;
+    restored.sort_by(|a, b| a.key.cmp(&b.key));
+    original.sort_by(|a, b| a.key.cmp(&b.key));
+    assert_eq!(restored, original);
+}
+
+#[tokio::test]
+async fn test_export_chunks_values_over_the_message_limit() {
+    let (nodes, _handles) = start_ring(3).await;
+    // Larger than one 4MB gRPC message
+    let big: Vec<u8> = (0..6 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+    let owner = nodes[0]
+        .put(Request::new(PutRequest {
+            key: "big".to_string(),
+            value: big.clone(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .owner_id;
+    nodes[0]
+        .put(Request::new(PutRequest {
+            key: "small".to_string(),
+            value: b"v".to_vec(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // Asked through a node that has to fetch the value from its owner
+    let asked = nodes.iter().find(|n| n.id != owner).unwrap();
+    let mut entries = export_chunks(asked).await;
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    let keys: Vec<&str> = entries.iter().map(|kv| kv.key.as_str()).collect();
+    assert_eq!(keys, vec!["big", "small"]);
+    assert_eq!(entries[0].value, big);
+    assert_eq!(entries[1].value, b"v");
+}
+
+#[tokio::test]
+async fn test_export_reads_old_peers_one_message_per_key() {
+    let (nodes, _handles) = start_ring(2).await;
+    for i in 0..20 {
+        nodes[0]
+            .put(Request::new(PutRequest {
+                key: format!("old_{}", i),
+                value: b"v".to_vec(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+    }
+
+    // Pretend nodes[1] predates chunked exports
+    let mut client = ChordClient::connect(format!("http://{}", nodes[0].addr))
+        .await
+        .unwrap();
+    let old_version = CHUNKED_EXPORT_PROTOCOL_VERSION - 1;
+    client
+        .hello(Request::new(Handshake {
+            version: old_version,
+            min_version: MIN_PROTOCOL_VERSION,
+            node: Some(NodeInfo {
+                id: nodes[1].id,
+                address: nodes[1].addr.clone(),
+                observer: false,
+            }),
+        }))
+        .await
+        .unwrap();
+
+    let entries = export_chunks(&nodes[0]).await;
+    assert_eq!(nodes[0].peer_version(nodes[1].id).await, Some(old_version));
+    let keys: HashSet<&str> = entries.iter().map(|kv| kv.key.as_str()).collect();
+    assert_eq!(keys.len(), 20);
+    assert_eq!(entries.len(), 20);
+}
@@ -0,0 +1,40 @@
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_find_predecessor_from_any_node() {
+    let (first, _h) = start_node("127.0.0.1:0".to_string()).await;
+    let mut nodes = vec![first];
+    for _ in 0..2 {
+        let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+        node.join(nodes[0].addr.clone()).await.unwrap();
+        nodes.push(node);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    let mut ids: Vec<u64> = nodes.iter().map(|n| n.id).collect();
+    ids.sort();
+
+    for (i, &id) in ids.iter().enumerate() {
+        let expected_pred = ids[(i + ids.len() - 1) % ids.len()];
+        for node in &nodes {
+            // A node's own id is owned by itself, so its predecessor is the previous node
+            let pred = node
+                .find_predecessor_internal(id)
+                .await
+                .expect("FindPredecessor failed");
+            assert_eq!(
+                pred.id, expected_pred,
+                "Node {} resolved the wrong predecessor for {}",
+                node.id, id
+            );
+
+            // The id right after a node is owned by its successor, so it precedes it
+            let pred = node
+                .find_predecessor_internal(id.wrapping_add(1))
+                .await
+                .expect("FindPredecessor failed");
+            assert_eq!(pred.id, id);
+        }
+    }
+}
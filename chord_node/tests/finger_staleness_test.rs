@@ -0,0 +1,31 @@
+use chord_node::constants::FINGER_TABLE_SIZE;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_fix_fingers_refreshes_stalest_first() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, _h2) = start_node("127.0.0.1:0".to_string()).await;
+
+    node2.join(node1.addr.clone()).await.unwrap();
+    stabilize_ring(&[node1.clone(), node2.clone()], 3).await;
+
+    // Purely random picks would leave ~13% of the table untouched after two
+    // passes; stalest-first covers every finger well within that
+    for _ in 0..2 * FINGER_TABLE_SIZE {
+        node1.fix_fingers().await;
+    }
+
+    let state = node1.state.read().await;
+    let never_fixed = state
+        .finger_last_fixed
+        .iter()
+        .filter(|fixed| fixed.is_none())
+        .count();
+    assert_eq!(never_fixed, 0, "Every finger should have been refreshed");
+    drop(state);
+
+    let stats = node1.stats().await;
+    assert!(stats.stalest_finger_age_ms <= stats.uptime_ms);
+}
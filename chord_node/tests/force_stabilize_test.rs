@@ -0,0 +1,31 @@
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::Empty;
+use tonic::Request;
+
+mod common;
+use common::start_node;
+
+#[tokio::test]
+async fn test_force_stabilize_converges_without_timers() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, _h2) = start_node("127.0.0.1:0".to_string()).await;
+    node2.join(node1.addr.clone()).await.unwrap();
+
+    // Drive the ring purely over the RPC, the way the monitor does
+    for _ in 0..3 {
+        for node in [&node1, &node2] {
+            let mut client = ChordClient::connect(format!("http://{}", node.addr))
+                .await
+                .unwrap();
+            client
+                .force_stabilize(Request::new(Empty {}))
+                .await
+                .expect("ForceStabilize failed");
+        }
+    }
+
+    assert_eq!(node1.successor().await.id, node2.id);
+    assert_eq!(node2.successor().await.id, node1.id);
+    let pred1 = node1.state.read().await.predecessor.clone();
+    assert_eq!(pred1.map(|p| p.id), Some(node2.id));
+}
@@ -0,0 +1,60 @@
+use chord_node::Node;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{NodeInfo, PutRequest};
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tonic::Request;
+
+mod common;
+use common::start_node_with;
+
+#[tokio::test]
+async fn test_forward_waiting_on_a_busy_slot_is_rejected() {
+    let (a, _handle) = start_node_with("127.0.0.1:0".to_string(), |id, addr| {
+        Node::new(id, addr).with_forward_limit(1)
+    })
+    .await;
+
+    // A successor that accepts connections but never answers, so a request
+    // forwarded to it holds A's only forwarding slot
+    let hole = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let key = "forwarded-key".to_string();
+    let hole_info = NodeInfo {
+        id: chord_proto::hash_addr(&key),
+        address: hole.local_addr().unwrap().to_string(),
+        observer: false,
+    };
+    assert_ne!(hole_info.id, a.id);
+    a.set_neighbors(None, vec![hole_info]).await;
+    let _accepted = tokio::spawn(async move {
+        let mut held = Vec::new();
+        while let Ok((socket, _)) = hole.accept().await {
+            held.push(socket);
+        }
+    });
+
+    let put = |node: std::sync::Arc<Node>, key: String| async move {
+        node.put(Request::new(PutRequest {
+            key,
+            value: "value".into(),
+            ..Default::default()
+        }))
+        .await
+    };
+    let stuck = tokio::spawn(put(a.clone(), key.clone()));
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let started = Instant::now();
+    let err = put(a.clone(), key).await.unwrap_err();
+    assert_eq!(err.code(), tonic::Code::ResourceExhausted);
+    assert!(
+        started.elapsed() >= Duration::from_millis(1500),
+        "The second forward should have queued for a slot first"
+    );
+    assert!(
+        !stuck.is_finished(),
+        "The first forward should still hold its slot"
+    );
+    assert_eq!(a.forwards_rejected(), 1);
+    stuck.abort();
+}
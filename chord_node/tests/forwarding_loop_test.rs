@@ -0,0 +1,114 @@
+use chord_node::ring::is_in_range_inclusive;
+use chord_node::Node;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{AppendRequest, GetRequest, NodeInfo, PutRequest};
+use chord_proto::hash_addr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tonic::{Code, Request};
+
+mod common;
+use common::start_node;
+
+fn info(node: &Node) -> NodeInfo {
+    NodeInfo {
+        id: node.id,
+        address: node.addr.clone(),
+        observer: false,
+    }
+}
+
+/// Two nodes that each route `key` to the other: A to its successor B, and
+/// B, one step behind, to A through a stale lookup cache entry.
+async fn bouncing_pair() -> (Arc<Node>, Arc<Node>, JoinHandle<()>, JoinHandle<()>, String) {
+    let (a, ha) = start_node("127.0.0.1:0".to_string()).await;
+    let (b, hb) = start_node("127.0.0.1:0".to_string()).await;
+
+    let key = (0..)
+        .map(|i| format!("bounce_key_{}", i))
+        .find(|k| is_in_range_inclusive(hash_addr(k), a.id, b.id))
+        .unwrap();
+    let key_id = hash_addr(&key);
+
+    // A routes the key to its successor B...
+    let stale_pred = NodeInfo {
+        id: key_id.wrapping_sub(1),
+        address: "127.0.0.1:1".to_string(),
+        observer: false,
+    };
+    a.set_neighbors(Some(stale_pred), vec![info(&b)]).await;
+    // ...while B, one step behind, still has A cached as the owner. A's
+    // predecessor makes that entry look valid, so B sends the key back.
+    b.set_neighbors(Some(info(&a)), vec![info(&a)]).await;
+    b.state.write().await.lookup_cache.insert(key_id, info(&a));
+    (a, b, ha, hb, key)
+}
+
+#[tokio::test]
+async fn test_requests_bouncing_between_two_nodes_fail_fast() {
+    let (a, b, _ha, _hb, key) = bouncing_pair().await;
+    let key_id = hash_addr(&key);
+
+    let put = tokio::time::timeout(
+        Duration::from_secs(5),
+        a.put(Request::new(PutRequest {
+            key: key.clone(),
+            value: b"v".to_vec(),
+            ..Default::default()
+        })),
+    )
+    .await
+    .expect("put bounced until the timeout");
+    let status = put.unwrap_err();
+    assert_eq!(status.code(), Code::Aborted);
+    assert!(status.message().contains("Forwarding loop"), "{}", status);
+
+    b.state.write().await.lookup_cache.insert(key_id, info(&a));
+    let get = tokio::time::timeout(
+        Duration::from_secs(5),
+        a.get(Request::new(GetRequest {
+            key: key.clone(),
+            ..Default::default()
+        })),
+    )
+    .await
+    .expect("get bounced until the timeout");
+    assert_eq!(get.unwrap_err().code(), Code::Aborted);
+}
+
+#[tokio::test]
+async fn test_list_and_stream_requests_bouncing_fail_fast() {
+    let (a, b, _ha, _hb, key) = bouncing_pair().await;
+    let key_id = hash_addr(&key);
+
+    let append = tokio::time::timeout(
+        Duration::from_secs(5),
+        a.append(Request::new(AppendRequest {
+            key: key.clone(),
+            value: b"item".to_vec(),
+            ..Default::default()
+        })),
+    )
+    .await
+    .expect("append bounced until the timeout");
+    assert_eq!(append.unwrap_err().code(), Code::Aborted);
+
+    let get = || {
+        Request::new(GetRequest {
+            key: key.clone(),
+            ..Default::default()
+        })
+    };
+    b.state.write().await.lookup_cache.insert(key_id, info(&a));
+    let list = tokio::time::timeout(Duration::from_secs(5), a.get_list(get()))
+        .await
+        .expect("get_list bounced until the timeout");
+    assert_eq!(list.unwrap_err().code(), Code::Aborted);
+
+    b.state.write().await.lookup_cache.insert(key_id, info(&a));
+    let stream = tokio::time::timeout(Duration::from_secs(5), a.get_stream(get()))
+        .await
+        .expect("get_stream bounced until the timeout");
+    assert_eq!(stream.err().map(|s| s.code()), Some(Code::Aborted));
+}
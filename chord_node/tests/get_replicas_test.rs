@@ -0,0 +1,57 @@
+use chord_node::constants::REPLICATION_COUNT;
+use chord_node::Node;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::{GetRequest, PutRequest};
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_get_replicas_reports_holders() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    for i in 0..4 {
+        let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    let mut client = ChordClient::connect(format!("http://{}", nodes[1].addr))
+        .await
+        .unwrap();
+    client
+        .put(Request::new(PutRequest {
+            key: "located_key".to_string(),
+            value: "v".into(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let locations = client
+        .get_replicas(Request::new(GetRequest {
+            key: "located_key".to_string(),
+            ..Default::default()
+        }))
+        .await
+        .expect("GetReplicas failed")
+        .into_inner();
+    println!("Locations: {:?}", locations);
+
+    let primary = locations.primary.expect("Primary missing");
+    assert!(locations.primary_found);
+    assert_eq!(locations.replicas.len(), REPLICATION_COUNT);
+
+    // The answer matches what the stores actually hold
+    for node in &nodes {
+        let holds = node.state.read().await.store.contains_key("located_key");
+        let listed = node.id == primary.id || locations.replicas.iter().any(|r| r.id == node.id);
+        assert_eq!(holds, listed, "Node {} misreported", node.id);
+    }
+}
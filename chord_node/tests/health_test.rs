@@ -0,0 +1,40 @@
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::{Empty, HealthState};
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_health_follows_join_lifecycle() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, _h2) = start_node("127.0.0.1:0".to_string()).await;
+
+    assert_eq!(node1.health().await, HealthState::Starting);
+
+    // A lone node is ready once it has stabilized onto itself
+    stabilize_ring(std::slice::from_ref(&node1), 2).await;
+    assert_eq!(node1.health().await, HealthState::Ready);
+
+    node2.join(node1.addr.clone()).await.unwrap();
+    assert_eq!(
+        node2.health().await,
+        HealthState::Joining,
+        "Node should not be ready before it has a predecessor"
+    );
+
+    stabilize_ring(&[node1.clone(), node2.clone()], 10).await;
+    let mut client = ChordClient::connect(format!("http://{}", node2.addr))
+        .await
+        .unwrap();
+    let resp = client
+        .health(Request::new(Empty {}))
+        .await
+        .expect("Health failed")
+        .into_inner();
+    assert_eq!(resp.state(), HealthState::Ready);
+    assert_eq!(node1.health().await, HealthState::Ready);
+
+    node2.leave_network().await.unwrap();
+    assert_eq!(node2.health().await, HealthState::Leaving);
+}
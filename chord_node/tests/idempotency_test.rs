@@ -0,0 +1,174 @@
+use chord_node::Node;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{GetRequest, PutRequest};
+use chord_proto::hash_addr;
+use std::time::Duration;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+fn put_request(key: &str, value: &str, request_id: &str) -> PutRequest {
+    PutRequest {
+        key: key.to_string(),
+        value: value.into(),
+        request_id: request_id.to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_retried_put_is_ignored() {
+    let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+
+    let key = "idempotent_key";
+    let put = |value: &str, request_id: &str| PutRequest {
+        key: key.to_string(),
+        value: value.into(),
+        request_id: request_id.to_string(),
+        ..Default::default()
+    };
+
+    node.put(Request::new(put("first", "req-1")))
+        .await
+        .expect("Put failed");
+    node.put(Request::new(put("second", "req-2")))
+        .await
+        .expect("Put failed");
+
+    // A late retry of the first put must not clobber the newer value
+    let resp = node
+        .put(Request::new(put("first", "req-1")))
+        .await
+        .expect("Retried put failed")
+        .into_inner();
+    assert!(resp.success, "Duplicate put should still report success");
+
+    let resp = node
+        .get(Request::new(GetRequest {
+            key: key.to_string(),
+            ..Default::default()
+        }))
+        .await
+        .expect("Get failed")
+        .into_inner();
+    assert_eq!(resp.value, b"second");
+
+    // Puts without a request id are never deduplicated
+    node.put(Request::new(put("third", "")))
+        .await
+        .expect("Put failed");
+    node.put(Request::new(put("fourth", "")))
+        .await
+        .expect("Put failed");
+    let state = node.state.read().await;
+    assert_eq!(state.store.get(key).unwrap().value, b"fourth");
+    assert_eq!(state.applied_requests.len(), 2);
+}
+
+#[tokio::test]
+async fn test_retry_reaching_a_new_owner_is_ignored() {
+    let (node_a, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node_b, _h2) = start_node("127.0.0.1:0".to_string()).await;
+    let key = (0..)
+        .map(|i| format!("key_{}", i))
+        .find(|k| Node::is_in_range_inclusive(hash_addr(k), node_a.id, node_b.id))
+        .unwrap();
+
+    node_a
+        .put(Request::new(put_request(&key, "first", "req-1")))
+        .await
+        .expect("Put failed");
+    node_a
+        .put(Request::new(put_request(&key, "second", "req-2")))
+        .await
+        .expect("Put failed");
+
+    // B takes the key over from A, along with the ids A already applied
+    node_b.join(node_a.addr.clone()).await.unwrap();
+    let nodes = vec![node_a.clone(), node_b.clone()];
+    stabilize_ring(&nodes, 5).await;
+
+    node_b
+        .put(Request::new(put_request(&key, "first", "req-1")))
+        .await
+        .expect("Retried put failed");
+    let state = node_b.state.read().await;
+    assert_eq!(state.store.get(&key).unwrap().value, b"second");
+}
+
+#[tokio::test]
+async fn test_retry_after_owner_failure_is_ignored() {
+    let mut nodes = Vec::new();
+    let mut handles = Vec::new();
+    for _ in 0..3 {
+        let (node, handle) = start_node("127.0.0.1:0".to_string()).await;
+        nodes.push(node);
+        handles.push(handle);
+    }
+    for node in &nodes[1..] {
+        node.join(nodes[0].addr.clone()).await.unwrap();
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    let owner_idx = 0;
+    let pred = nodes[owner_idx]
+        .state
+        .read()
+        .await
+        .predecessor
+        .clone()
+        .unwrap();
+    let key = (0..)
+        .map(|i| format!("key_{}", i))
+        .find(|k| Node::is_in_range_inclusive(hash_addr(k), pred.id, nodes[owner_idx].id))
+        .unwrap();
+    nodes[owner_idx]
+        .put(Request::new(put_request(&key, "first", "req-1")))
+        .await
+        .expect("Put failed");
+    nodes[owner_idx]
+        .put(Request::new(put_request(&key, "second", "req-2")))
+        .await
+        .expect("Put failed");
+
+    let successor = nodes[owner_idx].successor().await;
+    let new_owner = nodes.iter().find(|n| n.id == successor.id).unwrap().clone();
+    for _ in 0..50 {
+        let replicated = new_owner
+            .state
+            .read()
+            .await
+            .store
+            .get(&key)
+            .is_some_and(|e| e.value == b"second");
+        if replicated {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    // The owner dies before the client hears back; its retry lands on the
+    // replica that took over
+    handles[owner_idx].abort();
+    let survivors: Vec<_> = nodes
+        .iter()
+        .filter(|n| n.id != nodes[owner_idx].id)
+        .cloned()
+        .collect();
+    stabilize_ring(&survivors, 10).await;
+
+    new_owner
+        .put(Request::new(put_request(&key, "first", "req-1")))
+        .await
+        .expect("Retried put failed");
+    let resp = new_owner
+        .get(Request::new(GetRequest {
+            key: key.clone(),
+            ..Default::default()
+        }))
+        .await
+        .expect("Get failed")
+        .into_inner();
+    assert_eq!(resp.value, b"second");
+}
@@ -0,0 +1,132 @@
+use chord_node::constants::MAX_KEY_BYTES;
+use chord_node::Node;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{GetRequest, KeyValue};
+use chord_proto::chunk::key_value_chunks;
+use std::sync::Arc;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_import_spreads_keys_to_their_owners() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut _handles = Vec::new();
+    for i in 0..3 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        _handles.push(h);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    const NUM_KEYS: u64 = 1200;
+    let mut entries: Vec<KeyValue> = (0..NUM_KEYS)
+        .map(|i| KeyValue {
+            key: format!("import_{}", i),
+            value: format!("value_{}", i).into_bytes(),
+            updated_at: 1_000 + i,
+            ..Default::default()
+        })
+        .collect();
+    entries.push(KeyValue {
+        key: "k".repeat(MAX_KEY_BYTES + 1),
+        value: b"too long".to_vec(),
+        ..Default::default()
+    });
+
+    let mut client = ChordClient::connect(format!("http://{}", nodes[0].addr))
+        .await
+        .unwrap();
+    let resp = client
+        .import(Request::new(tokio_stream::iter(entries)))
+        .await
+        .expect("Import failed")
+        .into_inner();
+    assert_eq!(resp.imported + resp.forwarded, NUM_KEYS);
+    assert!(resp.imported > 0 && resp.forwarded > 0);
+    assert_eq!(resp.failed, 1);
+
+    // Every key reached its owner with the version it was imported with
+    for i in (0..NUM_KEYS).step_by(37) {
+        let found = nodes[2]
+            .get(Request::new(GetRequest {
+                key: format!("import_{}", i),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(found.found, "import_{} is missing", i);
+        assert_eq!(found.value, format!("value_{}", i).into_bytes());
+        assert_eq!(found.updated_at, 1_000 + i);
+    }
+}
+
+#[tokio::test]
+async fn test_import_chunks_values_over_the_message_limit() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut _handles = Vec::new();
+    for i in 0..2 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        _handles.push(h);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    // Larger than one 4MB gRPC message, between two small values
+    let big: Vec<u8> = (0..6 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+    let entries = vec![
+        KeyValue {
+            key: "before".to_string(),
+            value: b"a".to_vec(),
+            ..Default::default()
+        },
+        KeyValue {
+            key: "big".to_string(),
+            value: big.clone(),
+            ..Default::default()
+        },
+        KeyValue {
+            key: "after".to_string(),
+            value: b"b".to_vec(),
+            ..Default::default()
+        },
+    ];
+    let chunks: Vec<_> = entries.into_iter().flat_map(key_value_chunks).collect();
+    assert!(chunks.len() > 3);
+
+    let mut client = ChordClient::connect(format!("http://{}", nodes[0].addr))
+        .await
+        .unwrap();
+    let resp = client
+        .import_chunks(Request::new(tokio_stream::iter(chunks)))
+        .await
+        .expect("Import failed")
+        .into_inner();
+    assert_eq!(resp.imported + resp.forwarded, 3);
+    assert_eq!(resp.failed, 0);
+
+    // Read from the owners' stores: a get can't return the big value in one message
+    for (key, value) in [
+        ("before", b"a".to_vec()),
+        ("big", big),
+        ("after", b"b".to_vec()),
+    ] {
+        let mut stored = None;
+        for node in &nodes {
+            if node.owned_keys().await.iter().any(|k| k == key) {
+                stored = node.state.read().await.store.get(key);
+            }
+        }
+        let stored = stored.unwrap_or_else(|| panic!("{} is missing on its owner", key));
+        assert!(stored.value == value, "{} has the wrong value", key);
+    }
+}
@@ -0,0 +1,77 @@
+use chord_node::{InMemoryNetwork, Node};
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{GetRequest, PutRequest};
+use chord_proto::hash_addr;
+use std::sync::Arc;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_in_memory_node};
+
+async fn ring(network: &InMemoryNetwork, size: usize) -> Vec<Arc<Node>> {
+    let nodes: Vec<Arc<Node>> = (0..size)
+        .map(|i| start_in_memory_node(network, &format!("mem-{}:1", i)))
+        .collect();
+    for node in &nodes[1..] {
+        node.join(nodes[0].addr.clone()).await.unwrap();
+    }
+    stabilize_ring(&nodes, 10).await;
+    nodes
+}
+
+#[tokio::test]
+async fn test_in_memory_ring_routes_keys() {
+    let network = InMemoryNetwork::new();
+    let nodes = ring(&network, 5).await;
+
+    // Every node ends up with the next node by id as its successor
+    let mut ids: Vec<u64> = nodes.iter().map(|n| n.id).collect();
+    ids.sort_unstable();
+    for node in &nodes {
+        let i = ids.iter().position(|&id| id == node.id).unwrap();
+        assert_eq!(node.successor().await.id, ids[(i + 1) % ids.len()]);
+    }
+
+    for i in 0..20 {
+        nodes[i % nodes.len()]
+            .put(Request::new(PutRequest {
+                key: format!("key_{}", i),
+                value: format!("value_{}", i).into_bytes(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+    }
+    for i in 0..20 {
+        let key = format!("key_{}", i);
+        let resp = nodes[(i + 2) % nodes.len()]
+            .get(Request::new(GetRequest {
+                key: key.clone(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(resp.value, format!("value_{}", i).into_bytes());
+        let owner = nodes[0]
+            .find_successor_internal(hash_addr(&key))
+            .await
+            .unwrap();
+        assert_eq!(resp.owner_id, owner.id);
+    }
+}
+
+#[tokio::test]
+async fn test_removed_node_is_routed_around() {
+    let network = InMemoryNetwork::new();
+    let nodes = ring(&network, 4).await;
+
+    let dead = nodes[0].successor().await;
+    network.remove(&dead.address).unwrap();
+    let live: Vec<Arc<Node>> = nodes.iter().filter(|n| n.id != dead.id).cloned().collect();
+    stabilize_ring(&live, 5).await;
+
+    assert_ne!(nodes[0].successor().await.id, dead.id);
+    let owner = nodes[0].find_successor_internal(dead.id).await.unwrap();
+    assert_ne!(owner.id, dead.id);
+}
@@ -47,7 +47,8 @@ async fn test_chord_ring_formation_and_routing() {
 
     let put_req = Request::new(PutRequest {
         key: key.to_string(),
-        value: value.to_string(),
+        value: value.into(),
+        ..Default::default()
     });
     use chord_proto::chord::chord_server::Chord;
     node1.put(put_req).await.expect("Put failed");
@@ -55,11 +56,12 @@ async fn test_chord_ring_formation_and_routing() {
     println!("Getting key from Node 3...");
     let get_req = Request::new(GetRequest {
         key: key.to_string(),
+        ..Default::default()
     });
     let response = node3.get(get_req).await.expect("Get failed");
     let resp = response.into_inner();
 
     assert!(resp.found, "Key not found");
-    assert_eq!(resp.value, value, "Value mismatch");
+    assert_eq!(resp.value, value.as_bytes(), "Value mismatch");
     println!("Test passed!");
 }
@@ -0,0 +1,75 @@
+use chord_proto::addr::{host_port, node_url, normalize_addr};
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{GetRequest, PutRequest};
+use chord_proto::hash_addr;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[test]
+fn test_addresses_normalize_to_one_spelling() {
+    assert_eq!(normalize_addr("127.0.0.1:5000"), "127.0.0.1:5000");
+    assert_eq!(normalize_addr("[::1]:5000"), "[::1]:5000");
+    assert_eq!(normalize_addr("[0:0:0:0:0:0:0:1]:5000"), "[::1]:5000");
+    assert_eq!(normalize_addr("http://[::1]:5000/"), "[::1]:5000");
+    assert_eq!(
+        normalize_addr(" Node-1.Example.COM.:5000 "),
+        "node-1.example.com:5000"
+    );
+
+    assert_eq!(host_port("::1", 5000), "[::1]:5000");
+    assert_eq!(host_port("[::1]", 5000), "[::1]:5000");
+    assert_eq!(host_port("10.0.0.7", 5000), "10.0.0.7:5000");
+    assert_eq!(host_port("Chord.Local", 5000), "chord.local:5000");
+
+    assert_eq!(node_url("[0::1]:5000"), "http://[::1]:5000");
+    assert_eq!(
+        node_url("https://Chord.Local:5000"),
+        "https://chord.local:5000"
+    );
+
+    // The id no longer depends on how the address was written
+    assert_eq!(
+        hash_addr(&normalize_addr("[0:0::1]:5000")),
+        hash_addr(&host_port("::1", 5000))
+    );
+}
+
+#[tokio::test]
+async fn test_ring_routes_over_ipv6_addresses() {
+    let (a, _h1) = start_node("[::1]:0".to_string()).await;
+    let (b, _h2) = start_node("[::1]:0".to_string()).await;
+    assert!(a.addr.starts_with("[::1]:"), "{}", a.addr);
+    assert_eq!(a.id, hash_addr(&a.addr));
+
+    // Join through a long-form spelling of a's address
+    let port = a.addr.rsplit_once(':').unwrap().1;
+    b.join(format!("[0:0:0:0:0:0:0:1]:{}", port)).await.unwrap();
+    let nodes = vec![a.clone(), b.clone()];
+    stabilize_ring(&nodes, 10).await;
+    assert_eq!(a.successor().await.address, b.addr);
+    assert_eq!(b.successor().await.address, a.addr);
+
+    for i in 0..10 {
+        nodes[i % 2]
+            .put(Request::new(PutRequest {
+                key: format!("key_{}", i),
+                value: format!("value_{}", i).into_bytes(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+    }
+    for i in 0..10 {
+        let resp = nodes[(i + 1) % 2]
+            .get(Request::new(GetRequest {
+                key: format!("key_{}", i),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(resp.value, format!("value_{}", i).into_bytes());
+    }
+}
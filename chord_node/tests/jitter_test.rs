@@ -0,0 +1,31 @@
+use chord_node::jitter::Jitter;
+use std::time::Duration;
+
+#[test]
+fn test_jitter_stays_within_bounds() {
+    let mut jitter = Jitter::new(42, 20);
+    for _ in 0..1000 {
+        let d = jitter.apply(1000);
+        assert!(d >= Duration::from_millis(800) && d <= Duration::from_millis(1200));
+    }
+}
+
+#[test]
+fn test_jitter_is_seeded_per_node() {
+    let sequence = |seed| {
+        let mut jitter = Jitter::new(seed, 20);
+        (0..20).map(|_| jitter.apply(1000)).collect::<Vec<_>>()
+    };
+    assert_eq!(sequence(7), sequence(7));
+    assert_ne!(
+        sequence(7),
+        sequence(8),
+        "nodes should not share a schedule"
+    );
+}
+
+#[test]
+fn test_zero_jitter_keeps_interval() {
+    let mut jitter = Jitter::new(1, 0);
+    assert_eq!(jitter.apply(1000), Duration::from_millis(1000));
+}
@@ -20,7 +20,7 @@ async fn test_join_failure_after_node_departure() {
     stabilize_ring(&[node1.clone(), node2.clone(), node3.clone()], 10).await;
 
     println!("Node 2 leaving...");
-    node2.leave_network().await;
+    node2.leave_network().await.unwrap();
 
     tokio::time::sleep(Duration::from_millis(500)).await;
 
@@ -0,0 +1,146 @@
+use chord_node::{JoinError, Node};
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{HealthState, NodeInfo};
+use chord_proto::{hash_addr, hash_addr_salted};
+use tonic::{Code, Request};
+
+mod common;
+use common::{stabilize_ring, start_node, start_node_with};
+
+#[tokio::test]
+async fn test_join_reports_failure_kind() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, _h2) = start_node("127.0.0.1:0".to_string()).await;
+
+    // Nothing listens on the discard port
+    let err = node2
+        .join("127.0.0.1:9".to_string())
+        .await
+        .expect_err("Joining via a dead seed should fail");
+    println!("Dead seed: {}", err);
+    assert!(matches!(err, JoinError::SeedUnreachable { .. }));
+
+    node2.join(node1.addr.clone()).await.unwrap();
+    stabilize_ring(&[node1.clone(), node2.clone()], 5).await;
+
+    let err = node2
+        .join(node1.addr.clone())
+        .await
+        .expect_err("Joining twice should fail");
+    assert_eq!(err, JoinError::AlreadyJoined);
+}
+
+#[tokio::test]
+async fn test_join_through_own_address_is_refused() {
+    let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+
+    let err = node
+        .join(node.addr.clone())
+        .await
+        .expect_err("Joining ourselves should fail");
+    println!("Self join: {}", err);
+    assert_eq!(
+        err,
+        JoinError::SelfJoin {
+            seed: node.addr.clone()
+        }
+    );
+
+    // Another spelling of the same address is caught once we find ourselves
+    let port = node.addr.rsplit_once(':').unwrap().1;
+    let alias = format!("localhost:{}", port);
+    let err = node
+        .join(alias.clone())
+        .await
+        .expect_err("Joining ourselves by name should fail");
+    assert_eq!(err, JoinError::SelfJoin { seed: alias });
+    assert_eq!(node.successor().await.id, node.id);
+}
+
+#[tokio::test]
+async fn test_failed_join_does_not_start_a_ring() {
+    let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+
+    node.join("127.0.0.1:9".to_string())
+        .await
+        .expect_err("Joining via a dead seed should fail");
+    // Still alone and unsettled, not a ring of one
+    assert_eq!(node.health().await, HealthState::Starting);
+    assert!(node.state.read().await.predecessor.is_none());
+
+    // So a later join still goes through
+    let (seed, _hs) = start_node("127.0.0.1:0".to_string()).await;
+    seed.create().await.unwrap();
+    node.join(seed.addr.clone()).await.unwrap();
+    stabilize_ring(&[seed.clone(), node.clone()], 5).await;
+    assert_eq!(node.successor().await.id, seed.id);
+    assert_eq!(seed.successor().await.id, node.id);
+}
+
+#[tokio::test]
+async fn test_create_settles_a_single_node_ring() {
+    let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+    node.create().await.unwrap();
+    assert_eq!(node.health().await, HealthState::Ready);
+
+    let (other, _ho) = start_node("127.0.0.1:0".to_string()).await;
+    other.join(node.addr.clone()).await.unwrap();
+    assert_eq!(other.create().await, Err(JoinError::AlreadyJoined));
+}
+
+#[tokio::test]
+async fn test_join_with_a_taken_id_is_refused() {
+    let (seed, _hs) = start_node("127.0.0.1:0".to_string()).await;
+    seed.create().await.unwrap();
+    let taken = seed.id;
+    let (twin, _ht) = start_node_with("127.0.0.1:0".to_string(), move |_, addr| {
+        Node::new(taken, addr)
+    })
+    .await;
+
+    let err = twin
+        .join(seed.addr.clone())
+        .await
+        .expect_err("Joining with a taken id should fail");
+    println!("Collision: {}", err);
+    assert_eq!(
+        err,
+        JoinError::IdCollision {
+            id: seed.id,
+            address: seed.addr.clone()
+        }
+    );
+
+    // Nor can the twin sneak in by notifying
+    let err = seed
+        .notify(Request::new(NodeInfo {
+            id: twin.id,
+            address: twin.addr.clone(),
+            observer: false,
+        }))
+        .await
+        .expect_err("A notify from a node with our id should fail");
+    assert_eq!(err.code(), Code::AlreadyExists);
+    assert_eq!(
+        seed.state
+            .read()
+            .await
+            .predecessor
+            .as_ref()
+            .unwrap()
+            .address,
+        seed.addr
+    );
+
+    // A salted id is distinct, and joins
+    let salted = hash_addr_salted(&twin.addr, 1);
+    assert_eq!(hash_addr_salted(&twin.addr, 0), hash_addr(&twin.addr));
+    assert_ne!(salted, seed.id);
+    let (rejoined, _hr) = start_node_with("127.0.0.1:0".to_string(), move |_, addr| {
+        Node::new(salted, addr)
+    })
+    .await;
+    rejoined.join(seed.addr.clone()).await.unwrap();
+    stabilize_ring(&[seed.clone(), rejoined.clone()], 5).await;
+    assert_eq!(seed.successor().await.id, salted);
+}
@@ -25,7 +25,8 @@ async fn test_key_transfer_on_join_and_leave() {
     client_a
         .put(Request::new(PutRequest {
             key: key.to_string(),
-            value: "value1".to_string(),
+            value: "value1".into(),
+            ..Default::default()
         }))
         .await
         .unwrap();
@@ -33,10 +34,11 @@ async fn test_key_transfer_on_join_and_leave() {
     let resp = client_a
         .get(Request::new(GetRequest {
             key: key.to_string(),
+            ..Default::default()
         }))
         .await
         .unwrap();
-    assert_eq!(resp.into_inner().value, "value1");
+    assert_eq!(resp.into_inner().value, b"value1");
 
     {
         let state = node_a.state.read().await;
@@ -95,7 +97,7 @@ async fn test_key_transfer_on_join_and_leave() {
     }
 
     println!("Node B leaving...");
-    node_b.leave_network().await;
+    node_b.leave_network().await.unwrap();
 
     sleep(Duration::from_secs(1)).await;
 
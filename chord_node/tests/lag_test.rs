@@ -0,0 +1,60 @@
+use chord_node::lag::LagWindow;
+use std::time::Duration;
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+#[test]
+fn test_empty_window_reports_zero() {
+    let window = LagWindow::new(8);
+    assert!(window.is_empty());
+    assert_eq!(window.percentile(0.5), Duration::ZERO);
+    assert_eq!(window.percentile(0.99), Duration::ZERO);
+}
+
+#[test]
+fn test_percentile_picks_the_nearest_rank() {
+    let mut window = LagWindow::new(10);
+    // Recorded out of order; the percentile is over the sorted samples
+    for millis in [7, 3, 10, 1, 8, 5, 2, 9, 4, 6] {
+        window.record(ms(millis));
+    }
+    assert_eq!(window.len(), 10);
+    assert_eq!(window.percentile(0.0), ms(1));
+    assert_eq!(window.percentile(0.1), ms(1));
+    assert_eq!(window.percentile(0.15), ms(2));
+    assert_eq!(window.percentile(0.5), ms(5));
+    assert_eq!(window.percentile(0.9), ms(9));
+    assert_eq!(window.percentile(0.95), ms(10));
+    assert_eq!(window.percentile(1.0), ms(10));
+}
+
+#[test]
+fn test_single_sample_is_every_percentile() {
+    let mut window = LagWindow::new(4);
+    window.record(ms(42));
+    for p in [0.0, 0.5, 0.99, 1.0] {
+        assert_eq!(window.percentile(p), ms(42));
+    }
+}
+
+#[test]
+fn test_full_window_drops_the_oldest_sample() {
+    let mut window = LagWindow::new(3);
+    for millis in [100, 1, 2, 3] {
+        window.record(ms(millis));
+    }
+    assert_eq!(window.len(), 3);
+    // The 100ms sample has aged out
+    assert_eq!(window.percentile(1.0), ms(3));
+    assert_eq!(window.percentile(0.5), ms(2));
+}
+
+#[test]
+fn test_zero_capacity_window_stays_empty() {
+    let mut window = LagWindow::new(0);
+    window.record(ms(5));
+    assert!(window.is_empty());
+    assert_eq!(window.percentile(0.5), Duration::ZERO);
+}
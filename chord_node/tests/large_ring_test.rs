@@ -88,6 +88,15 @@ async fn test_large_chord_ring() {
         ("distributed", "hash_table"),
     ];
 
+    // The first node at or after a key's id, wrapping around the ring
+    let expected_owner = |key_id: u64| {
+        node_ids
+            .iter()
+            .copied()
+            .find(|&id| id >= key_id)
+            .unwrap_or(node_ids[0])
+    };
+
     // Put keys from different nodes
     for (i, (key, value)) in test_cases.iter().enumerate() {
         let put_node = &nodes[i % NUM_NODES];
@@ -99,13 +108,21 @@ async fn test_large_chord_ring() {
 
         let put_req = Request::new(PutRequest {
             key: key.to_string(),
-            value: value.to_string(),
+            value: value.to_string().into_bytes(),
+            ..Default::default()
         });
 
-        put_node
+        let resp = put_node
             .put(put_req)
             .await
-            .unwrap_or_else(|_| panic!("Put failed for key '{}'", key));
+            .unwrap_or_else(|_| panic!("Put failed for key '{}'", key))
+            .into_inner();
+        assert_eq!(
+            resp.owner_id,
+            expected_owner(key_id),
+            "Key '{}' stored on the wrong node",
+            key
+        );
     }
 
     tokio::time::sleep(Duration::from_millis(200)).await;
@@ -121,6 +138,7 @@ async fn test_large_chord_ring() {
 
         let get_req = Request::new(GetRequest {
             key: key.to_string(),
+            ..Default::default()
         });
 
         let response = get_node
@@ -131,11 +149,22 @@ async fn test_large_chord_ring() {
 
         assert!(resp.found, "Key '{}' not found", key);
         assert_eq!(
-            resp.value, *expected_value,
+            resp.owner_id,
+            expected_owner(key_id),
+            "Key '{}' served by the wrong node",
+            key
+        );
+        assert_eq!(
+            resp.value,
+            expected_value.as_bytes(),
             "Value mismatch for key '{}'",
             key
         );
-        println!("✓ Got '{}' = '{}'", key, resp.value);
+        println!(
+            "✓ Got '{}' = '{}'",
+            key,
+            String::from_utf8_lossy(&resp.value)
+        );
     }
 
     println!("\n✓ All Put/Get operations successful!");
@@ -154,10 +183,10 @@ async fn test_large_chord_ring() {
                 i,
                 node.id,
                 num_keys,
-                state.store.keys().collect::<Vec<_>>()
+                state.store.keys()
             );
         }
-        stored_keys.extend(state.store.keys().cloned());
+        stored_keys.extend(state.store.keys());
     }
 
     let total_keys = stored_keys.len();
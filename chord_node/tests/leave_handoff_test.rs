@@ -0,0 +1,136 @@
+use chord_node::Node;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{GetRequest, PutRequest};
+use chord_proto::hash_addr;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_leave_loses_no_keys() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut handles = Vec::new();
+    for i in 0..3 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        handles.push(h);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    let mut client = ChordClient::connect(format!("http://{}", nodes[0].addr))
+        .await
+        .unwrap();
+    let keys: Vec<String> = (0..500).map(|i| format!("leave_key_{}", i)).collect();
+    for key in &keys {
+        client
+            .put(Request::new(PutRequest {
+                key: key.clone(),
+                value: key.clone().into_bytes(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+    }
+
+    // Leave returns only once the successor has confirmed the handoff, so
+    // the node can be stopped straight away
+    let leaving = nodes[1].clone();
+    let successor_id = leaving.state.read().await.successor_list[0].id;
+    let left_store = leaving.state.read().await.store.entries();
+    leaving.leave_network().await.expect("Leave failed");
+    handles[1].abort();
+
+    let successor = nodes.iter().find(|n| n.id == successor_id).unwrap();
+    {
+        let state = successor.state.read().await;
+        for (key, entry) in &left_store {
+            let held = state
+                .store
+                .get(key)
+                .unwrap_or_else(|| panic!("successor missing {}", key));
+            assert!(held.updated_at >= entry.updated_at);
+        }
+    }
+
+    let remaining: Vec<Arc<Node>> = nodes
+        .iter()
+        .filter(|n| n.id != leaving.id)
+        .cloned()
+        .collect();
+    stabilize_ring(&remaining, 10).await;
+    for key in &keys {
+        let resp = client
+            .get(Request::new(GetRequest {
+                key: key.clone(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(resp.found, "lost {} after leave", key);
+        assert_eq!(resp.value, key.as_bytes());
+    }
+}
+
+#[tokio::test]
+async fn test_leave_falls_back_to_the_next_successor() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut handles = Vec::new();
+    for i in 0..4 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        handles.push(h);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    let leaving = nodes[0].clone();
+    let pred_id = leaving.state.read().await.predecessor.clone().unwrap().id;
+    let owned: Vec<String> = (0..)
+        .map(|i| format!("fallback_key_{}", i))
+        .filter(|k| Node::is_in_range_inclusive(hash_addr(k), pred_id, leaving.id))
+        .take(20)
+        .collect();
+    for key in &owned {
+        leaving
+            .put(Request::new(PutRequest {
+                key: key.clone(),
+                value: key.clone().into_bytes(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+    }
+
+    // The immediate successor dies before anyone notices
+    let successors = leaving.state.read().await.successor_list.clone();
+    let dead_idx = nodes.iter().position(|n| n.id == successors[0].id).unwrap();
+    handles[dead_idx].abort();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // Drop the replicas it already has so only the handoff can restore them
+    let fallback = nodes.iter().find(|n| n.id == successors[1].id).unwrap();
+    for key in &owned {
+        fallback.state.write().await.store.delete(key);
+    }
+
+    leaving.leave_network().await.expect("Leave failed");
+
+    let state = fallback.state.read().await;
+    for key in &owned {
+        let held = state
+            .store
+            .get(key)
+            .unwrap_or_else(|| panic!("second successor missing {}", key));
+        assert_eq!(held.value, key.as_bytes());
+    }
+}
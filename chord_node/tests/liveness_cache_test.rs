@@ -0,0 +1,125 @@
+use chord_node::liveness::LivenessCache;
+use chord_node::Node;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{NodeInfo, PutRequest};
+use chord_proto::hash_addr;
+use std::time::Duration;
+use tonic::Request;
+
+mod common;
+use common::{start_node, start_node_with};
+
+fn info(node: &Node) -> NodeInfo {
+    NodeInfo {
+        id: node.id,
+        address: node.addr.clone(),
+        observer: false,
+    }
+}
+
+#[test]
+fn test_ping_results_are_reused_until_they_expire() {
+    let mut cache = LivenessCache::new(Duration::from_millis(50));
+    assert_eq!(cache.get("http://127.0.0.1:5000"), None);
+
+    cache.record("http://127.0.0.1:5000", true);
+    cache.record("http://127.0.0.1:5001", false);
+    assert_eq!(cache.get("http://127.0.0.1:5000"), Some(true));
+    assert_eq!(cache.get("http://127.0.0.1:5001"), Some(false));
+
+    std::thread::sleep(Duration::from_millis(80));
+    assert_eq!(
+        cache.get("http://127.0.0.1:5000"),
+        None,
+        "expired result was used"
+    );
+
+    // Recording drops whatever has expired
+    cache.record("http://127.0.0.1:5002", true);
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn test_failed_rpc_forgets_the_address() {
+    let mut cache = LivenessCache::new(Duration::from_secs(60));
+    cache.record("http://127.0.0.1:5000", true);
+    cache.record("http://127.0.0.1:5001", true);
+
+    cache.invalidate("http://127.0.0.1:5000");
+    assert_eq!(cache.get("http://127.0.0.1:5000"), None);
+    assert_eq!(cache.get("http://127.0.0.1:5001"), Some(true));
+}
+
+#[tokio::test]
+async fn test_failed_rpc_to_dead_node_forces_a_fresh_ping() {
+    let (a, _a_handle) = start_node_with("127.0.0.1:0".to_string(), |id, addr| {
+        Node::new(id, addr).with_liveness_ttl(Duration::from_secs(60))
+    })
+    .await;
+    let (b, b_handle) = start_node("127.0.0.1:0".to_string()).await;
+
+    a.set_neighbors(Some(info(&b)), vec![info(&b)]).await;
+    b.set_neighbors(Some(info(&a)), vec![info(&a)]).await;
+
+    // Caches B as alive
+    a.check_predecessor().await;
+    assert_eq!(
+        a.state.read().await.predecessor.as_ref().map(|p| p.id),
+        Some(b.id)
+    );
+
+    b_handle.abort();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Still within the TTL, so the cached answer is reused
+    a.check_predecessor().await;
+    assert_eq!(
+        a.state.read().await.predecessor.as_ref().map(|p| p.id),
+        Some(b.id),
+        "cached ping result was not reused"
+    );
+
+    // Stabilize's RPC to B fails, which should drop B from the cache
+    a.stabilize().await;
+    a.check_predecessor().await;
+    assert!(
+        a.state.read().await.predecessor.is_none(),
+        "dead predecessor kept after a failed RPC to it"
+    );
+}
+
+#[tokio::test]
+async fn test_failed_forward_to_dead_node_forces_a_fresh_ping() {
+    let (a, _a_handle) = start_node_with("127.0.0.1:0".to_string(), |id, addr| {
+        Node::new(id, addr).with_liveness_ttl(Duration::from_secs(60))
+    })
+    .await;
+    let (b, b_handle) = start_node("127.0.0.1:0".to_string()).await;
+
+    a.set_neighbors(Some(info(&b)), vec![info(&b)]).await;
+    b.set_neighbors(Some(info(&a)), vec![info(&a)]).await;
+    a.check_predecessor().await;
+
+    b_handle.abort();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // A key B owns, so A forwards the write to it
+    let key = (0..)
+        .map(|i| format!("forwarded_{}", i))
+        .find(|k| Node::is_in_range_inclusive(hash_addr(k), a.id, b.id))
+        .unwrap();
+    let put = a
+        .put(Request::new(PutRequest {
+            key,
+            value: b"v".to_vec(),
+            ..Default::default()
+        }))
+        .await;
+    assert!(put.is_err());
+
+    a.check_predecessor().await;
+    assert!(
+        a.state.read().await.predecessor.is_none(),
+        "dead predecessor kept after a failed forward to it"
+    );
+}
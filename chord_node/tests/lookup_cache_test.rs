@@ -0,0 +1,197 @@
+use chord_node::lookup_cache::LookupCache;
+use chord_node::Node;
+use chord_proto::chord::NodeInfo;
+use std::sync::Arc;
+use std::time::Duration;
+
+mod common;
+use common::{stabilize_ring, start_node, start_node_with};
+
+fn node_info(id: u64) -> NodeInfo {
+    NodeInfo {
+        id,
+        address: format!("127.0.0.1:{}", id % 65536),
+        observer: false,
+    }
+}
+
+#[test]
+fn test_cache_covers_only_the_resolved_span() {
+    let mut cache = LookupCache::new(16, Duration::from_secs(60));
+    let base = 7u64 << 48;
+    cache.insert(base + 100, node_info(base + 200));
+
+    assert_eq!(cache.get(base + 100).unwrap().id, base + 200);
+    assert_eq!(cache.get(base + 150).unwrap().id, base + 200);
+    assert_eq!(cache.get(base + 200).unwrap().id, base + 200);
+    // Same bucket, but outside what the lookup proved
+    assert!(cache.get(base + 99).is_none());
+    assert!(cache.get(base + 201).is_none());
+}
+
+#[test]
+fn test_cache_expires_and_forgets_nodes() {
+    let mut cache = LookupCache::new(16, Duration::from_millis(50));
+    cache.insert(10, node_info(20));
+    std::thread::sleep(Duration::from_millis(80));
+    assert!(cache.get(15).is_none(), "expired entry was used");
+
+    let mut cache = LookupCache::new(16, Duration::from_secs(60));
+    cache.insert(10, node_info(20));
+    cache.remove_node(20);
+    assert!(cache.get(15).is_none());
+}
+
+#[test]
+fn test_cache_capacity() {
+    let mut cache = LookupCache::new(2, Duration::from_secs(60));
+    for bucket in 0..5u64 {
+        cache.insert(bucket << 48, node_info((bucket << 48) + 1));
+    }
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get(4 << 48).is_some(), "newest entry was evicted");
+
+    let mut disabled = LookupCache::new(0, Duration::from_secs(60));
+    disabled.insert(10, node_info(20));
+    assert!(disabled.is_empty());
+}
+
+#[tokio::test]
+async fn test_dead_cached_owner_is_not_returned() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut handles = Vec::new();
+    for i in 0..5 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        handles.push(h);
+    }
+    stabilize_ring(&nodes, 15).await;
+
+    // Find an id whose owner is neither the asking node nor its successor,
+    // so the lookup has to go remote and gets cached
+    let asker = nodes[0].clone();
+    let successor_id = asker.successor().await.id;
+    let mut target = None;
+    for i in 0..1000u64 {
+        let id = i.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        let owner = asker.find_successor_internal(id).await.unwrap();
+        if owner.id != asker.id && owner.id != successor_id {
+            target = Some((id, owner));
+            break;
+        }
+    }
+    let (id, owner) = target.expect("no remote owner found");
+    assert_eq!(
+        asker.state.read().await.lookup_cache.get(id).map(|n| n.id),
+        Some(owner.id)
+    );
+
+    let dead = nodes.iter().position(|n| n.id == owner.id).unwrap();
+    handles[dead].abort();
+    let live: Vec<Arc<Node>> = nodes.iter().filter(|n| n.id != owner.id).cloned().collect();
+    stabilize_ring(&live, 15).await;
+
+    // The healed ring no longer routes to the dead node, so only a stale
+    // cache entry could still produce it
+    let resolved = asker.find_successor_internal(id).await.unwrap();
+    assert_ne!(resolved.id, owner.id, "a dead cached owner was returned");
+    let expected = live[1].find_successor_internal(id).await.unwrap();
+    assert_eq!(resolved.id, expected.id);
+}
+
+#[tokio::test]
+async fn test_join_inside_cached_span_is_seen() {
+    // Fixed ids, so the asker always has to go remote: a=1, b=5, c=9, d=13
+    // (in units of 2^60), and the newcomer joins at 7, inside the span that
+    // c was resolved for
+    let unit = 1u64 << 60;
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut _handles = Vec::new();
+    for (i, n) in [1u64, 5, 9, 13].into_iter().enumerate() {
+        let (node, h) = start_node_with("127.0.0.1:0".to_string(), |_, addr| {
+            Node::new(n * unit, addr)
+        })
+        .await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        _handles.push(h);
+    }
+    stabilize_ring(&nodes, 15).await;
+
+    let (newcomer, _h) = start_node_with("127.0.0.1:0".to_string(), |_, addr| {
+        Node::new(7 * unit, addr)
+    })
+    .await;
+    let id = newcomer.id;
+    let asker = nodes[0].clone();
+    let old_owner = asker.find_successor_internal(id).await.unwrap();
+    assert_eq!(old_owner.id, 9 * unit);
+    assert_eq!(
+        asker.state.read().await.lookup_cache.get(id).map(|n| n.id),
+        Some(old_owner.id)
+    );
+
+    newcomer.join(nodes[0].addr.clone()).await.unwrap();
+    nodes.push(newcomer.clone());
+    stabilize_ring(&nodes, 5).await;
+
+    let resolved = asker.find_successor_internal(id).await.unwrap();
+    assert_eq!(resolved.id, newcomer.id, "stale cached owner was returned");
+}
+
+#[tokio::test]
+async fn test_dry_run_lookup_leaves_cache_untouched() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut _handles = Vec::new();
+    for i in 0..5 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        _handles.push(h);
+    }
+    stabilize_ring(&nodes, 15).await;
+
+    async fn cache_sizes(nodes: &[Arc<Node>]) -> Vec<usize> {
+        let mut sizes = Vec::new();
+        for node in nodes {
+            sizes.push(node.state.read().await.lookup_cache.len());
+        }
+        sizes
+    }
+
+    let asker = nodes[0].clone();
+    let successor_id = asker.successor().await.id;
+    let ids: Vec<u64> = (0..200u64)
+        .map(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15))
+        .collect();
+
+    let before = cache_sizes(&nodes).await;
+    let mut owners = Vec::new();
+    for &id in &ids {
+        owners.push(asker.find_successor_dry_run(id, 32).await.unwrap());
+    }
+    assert_eq!(
+        cache_sizes(&nodes).await,
+        before,
+        "a dry run touched a lookup cache"
+    );
+    assert!(
+        owners
+            .iter()
+            .any(|o| o.id != asker.id && o.id != successor_id),
+        "every lookup resolved locally"
+    );
+
+    // Same answers as a normal lookup
+    for (&id, owner) in ids.iter().zip(&owners) {
+        let expected = asker.find_successor_internal(id).await.unwrap();
+        assert_eq!(owner.id, expected.id);
+    }
+}
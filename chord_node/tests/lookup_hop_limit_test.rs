@@ -0,0 +1,73 @@
+use chord_node::Node;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::FindSuccessorRequest;
+use std::sync::Arc;
+use tonic::{Code, Request};
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+fn lookup(id: u64, max_hops: u32) -> Request<FindSuccessorRequest> {
+    Request::new(FindSuccessorRequest {
+        id,
+        max_hops,
+        dry_run: false,
+    })
+}
+
+#[tokio::test]
+async fn test_lookup_stops_when_hops_run_out() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut _handles = Vec::new();
+    for i in 0..3 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        _handles.push(h);
+    }
+    stabilize_ring(&nodes, 10).await;
+    for _ in 0..64 {
+        for node in &nodes {
+            node.fix_fingers().await;
+        }
+    }
+
+    // The node two steps ahead of a owns its own id; a has to forward once
+    let a = &nodes[0];
+    let b = a.successor().await;
+    let target = nodes
+        .iter()
+        .find(|n| n.id != a.id && n.id != b.id)
+        .unwrap()
+        .id;
+
+    let err = a
+        .find_successor(lookup(target, 1))
+        .await
+        .expect_err("No hops are left to forward with");
+    assert_eq!(err.code(), Code::Aborted);
+
+    let owner = a
+        .find_successor(lookup(target, 2))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(owner.id, target);
+    // 0 leaves the limit to the receiver
+    let owner = a
+        .find_successor(lookup(target, 0))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(owner.id, target);
+
+    // Ids up to a's successor are answered without forwarding
+    let own = a
+        .find_successor(lookup(b.id, 1))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(own.id, b.id);
+}
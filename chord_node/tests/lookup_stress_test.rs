@@ -0,0 +1,83 @@
+use chord_node::Node;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+/// The node that owns `id`: the first node id at or after it, wrapping around.
+fn owner(sorted_ids: &[u64], id: u64) -> u64 {
+    sorted_ids
+        .iter()
+        .copied()
+        .find(|&node_id| node_id >= id)
+        .unwrap_or(sorted_ids[0])
+}
+
+#[tokio::test]
+async fn test_lookups_stay_correct_during_stabilization() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    for i in 0..8 {
+        let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+    }
+    stabilize_ring(&nodes, 15).await;
+    let mut sorted_ids: Vec<u64> = nodes.iter().map(|n| n.id).collect();
+    sorted_ids.sort();
+
+    // Keep every node stabilizing and fixing fingers as fast as it can
+    let running = Arc::new(AtomicBool::new(true));
+    let mut maintenance = Vec::new();
+    for node in &nodes {
+        let node = node.clone();
+        let running = running.clone();
+        maintenance.push(tokio::spawn(async move {
+            while running.load(Ordering::SeqCst) {
+                node.stabilize().await;
+                node.fix_fingers().await;
+                node.check_predecessor().await;
+            }
+        }));
+    }
+
+    let mut lookups = Vec::new();
+    for (i, node) in nodes.iter().enumerate() {
+        let node = node.clone();
+        let sorted_ids = sorted_ids.clone();
+        lookups.push(tokio::spawn(async move {
+            let mut seed = 0x9e37_79b9_7f4a_7c15u64.wrapping_mul(i as u64 + 1);
+            for _ in 0..100 {
+                // xorshift, just to spread the ids over the ring
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                let found = node
+                    .find_successor_internal(seed)
+                    .await
+                    .expect("lookup failed");
+                assert_eq!(
+                    found.id,
+                    owner(&sorted_ids, seed),
+                    "node {} misrouted id {}",
+                    node.id,
+                    seed
+                );
+            }
+        }));
+    }
+
+    for lookup in lookups {
+        lookup.await.unwrap();
+    }
+    running.store(false, Ordering::SeqCst);
+    for task in maintenance {
+        tokio::time::timeout(Duration::from_secs(10), task)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
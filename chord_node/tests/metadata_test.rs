@@ -0,0 +1,116 @@
+use chord_node::constants::VALUE_CHUNK_SIZE;
+use chord_node::Node;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::{GetRequest, PutRequest};
+use chord_proto::MAX_METADATA_BYTES;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::{Code, Request};
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+fn metadata(owner: &str) -> HashMap<String, String> {
+    HashMap::from([
+        ("content-type".to_string(), "text/plain".to_string()),
+        ("owner".to_string(), owner.to_string()),
+    ])
+}
+
+#[tokio::test]
+async fn test_metadata_follows_the_value() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    for i in 0..3 {
+        let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    let mut client = ChordClient::connect(format!("http://{}", nodes[0].addr))
+        .await
+        .unwrap();
+    let large = vec![b'x'; VALUE_CHUNK_SIZE + 1];
+    for (key, value) in [("small_key", b"v".to_vec()), ("large_key", large)] {
+        client
+            .put(Request::new(PutRequest {
+                key: key.to_string(),
+                value,
+                metadata: metadata(key),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+    }
+    // Overwriting replaces the metadata along with the value
+    client
+        .put(Request::new(PutRequest {
+            key: "small_key".to_string(),
+            value: b"v2".to_vec(),
+            metadata: metadata("second writer"),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    for key in ["small_key", "large_key"] {
+        let resp = client
+            .get(Request::new(GetRequest {
+                key: key.to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let expected = if key == "small_key" {
+            metadata("second writer")
+        } else {
+            metadata(key)
+        };
+        assert_eq!(resp.metadata, expected);
+
+        // Every copy, primary or replica, carries the same metadata
+        let mut copies = 0;
+        for node in &nodes {
+            if let Some(entry) = node.state.read().await.store.get(key) {
+                assert_eq!(entry.metadata, expected, "stale metadata on {}", node.id);
+                copies += 1;
+            }
+        }
+        assert_eq!(copies, nodes.len());
+    }
+
+    // Key transfer on leave keeps it too
+    let leaving = nodes[1].clone();
+    let successor_id = leaving.state.read().await.successor_list[0].id;
+    leaving.leave_network().await.unwrap();
+    let successor = nodes.iter().find(|n| n.id == successor_id).unwrap();
+    let state = successor.state.read().await;
+    for key in ["small_key", "large_key"] {
+        assert!(!state.store.get(key).unwrap().metadata.is_empty());
+    }
+}
+
+#[tokio::test]
+async fn test_oversized_metadata_is_rejected() {
+    let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+    stabilize_ring(std::slice::from_ref(&node), 2).await;
+    let mut client = ChordClient::connect(format!("http://{}", node.addr))
+        .await
+        .unwrap();
+
+    let err = client
+        .put(Request::new(PutRequest {
+            key: "k".to_string(),
+            value: b"v".to_vec(),
+            metadata: HashMap::from([("big".to_string(), "x".repeat(MAX_METADATA_BYTES))]),
+            ..Default::default()
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+}
@@ -0,0 +1,161 @@
+use chord_node::Node;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::chord_monitor_server::{ChordMonitor, ChordMonitorServer};
+use chord_proto::chord::{Empty, NodeState, PutRequest};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+
+mod common;
+use common::{stabilize_ring, start_node, start_node_with};
+
+/// Monitor that only counts the reports it receives and keeps the latest.
+#[derive(Clone, Default)]
+struct CountingMonitor {
+    reports: Arc<AtomicUsize>,
+    last: Arc<Mutex<Option<NodeState>>>,
+}
+
+#[tonic::async_trait]
+impl ChordMonitor for CountingMonitor {
+    async fn report_state(&self, request: Request<NodeState>) -> Result<Response<Empty>, Status> {
+        self.reports.fetch_add(1, Ordering::SeqCst);
+        *self.last.lock().unwrap() = Some(request.into_inner());
+        Ok(Response::new(Empty {}))
+    }
+}
+
+async fn start_monitor() -> (String, Arc<AtomicUsize>) {
+    let monitor = CountingMonitor::default();
+    let reports = monitor.reports.clone();
+    (serve_monitor(monitor).await, reports)
+}
+
+async fn serve_monitor(monitor: CountingMonitor) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        Server::builder()
+            .add_service(ChordMonitorServer::new(monitor))
+            .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener))
+            .await
+            .unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    addr
+}
+
+#[tokio::test]
+async fn test_unchanged_state_is_not_re_reported() {
+    let (monitor_addr, reports) = start_monitor().await;
+    let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+    let heartbeat = Duration::from_secs(60);
+
+    node.report_to_monitor(monitor_addr.clone(), heartbeat)
+        .await;
+    // Uptime moves on between rounds, but nothing else does
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    node.report_to_monitor(monitor_addr.clone(), heartbeat)
+        .await;
+    assert_eq!(reports.load(Ordering::SeqCst), 1);
+
+    let mut client = ChordClient::connect(format!("http://{}", node.addr))
+        .await
+        .unwrap();
+    client
+        .put(Request::new(PutRequest {
+            key: "k".to_string(),
+            value: "v".into(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+    node.report_to_monitor(monitor_addr.clone(), heartbeat)
+        .await;
+    assert_eq!(reports.load(Ordering::SeqCst), 2, "a change is reported");
+}
+
+#[tokio::test]
+async fn test_unchanged_state_is_re_reported_after_heartbeat() {
+    let (monitor_addr, reports) = start_monitor().await;
+    let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+    let heartbeat = Duration::from_millis(200);
+
+    node.report_to_monitor(monitor_addr.clone(), heartbeat)
+        .await;
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    node.report_to_monitor(monitor_addr.clone(), heartbeat)
+        .await;
+    assert_eq!(reports.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_dead_monitor_is_backed_off_while_maintenance_runs() {
+    // Accepts connections but never answers, like a hung monitor
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let monitor_addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        let mut held = Vec::new();
+        while let Ok((socket, _)) = listener.accept().await {
+            held.push(socket);
+        }
+    });
+
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, _h2) = start_node("127.0.0.1:0".to_string()).await;
+    node2.join(node1.addr.clone()).await.unwrap();
+    let heartbeat = Duration::from_millis(100);
+
+    let started = Instant::now();
+    node1
+        .report_to_monitor(monitor_addr.clone(), heartbeat)
+        .await;
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_eq!(node1.monitor_failures(), 1);
+
+    // Backing off: later rounds don't wait on the monitor at all
+    let started = Instant::now();
+    for _ in 0..5 {
+        node1.stabilize().await;
+        node1
+            .report_to_monitor(monitor_addr.clone(), heartbeat)
+            .await;
+    }
+    assert!(started.elapsed() < Duration::from_millis(500));
+    assert_eq!(node1.monitor_failures(), 1);
+
+    stabilize_ring(&[node1.clone(), node2.clone()], 10).await;
+    assert_eq!(node1.successor().await.id, node2.id);
+    assert_eq!(node2.successor().await.id, node1.id);
+}
+
+#[tokio::test]
+async fn test_node_over_soft_key_limit_is_reported_overloaded() {
+    let monitor = CountingMonitor::default();
+    let monitor_addr = serve_monitor(monitor.clone()).await;
+    let (node, _h) = start_node_with("127.0.0.1:0".to_string(), |id, addr| {
+        Node::new(id, addr).with_max_keys_soft(2)
+    })
+    .await;
+    let heartbeat = Duration::from_secs(60);
+    let overloaded = || monitor.last.lock().unwrap().as_ref().unwrap().overloaded;
+
+    for i in 0..3 {
+        node.put_internal(PutRequest {
+            key: format!("soft_{}", i),
+            value: "v".into(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        node.report_to_monitor(monitor_addr.clone(), heartbeat)
+            .await;
+        // At the limit is fine, only going past it raises the flag
+        assert_eq!(overloaded(), i >= 2, "after {} keys", i + 1);
+    }
+    // The soft limit evicts nothing
+    assert_eq!(node.state.read().await.store.len(), 3);
+}
@@ -0,0 +1,160 @@
+use chord_client::DhtClient;
+use chord_node::Node;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{DeleteNamespaceRequest, GetRequest, PutRequest};
+use chord_proto::{namespaced_key, NAMESPACE_MARKER};
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::{Code, Request};
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+async fn put(node: &Node, namespace: &str, key: &str, value: &str) {
+    node.put(Request::new(PutRequest {
+        key: key.to_string(),
+        value: value.into(),
+        namespace: namespace.to_string(),
+        ..Default::default()
+    }))
+    .await
+    .unwrap();
+}
+
+async fn get(node: &Node, namespace: &str, key: &str) -> Option<Vec<u8>> {
+    let resp = node
+        .get(Request::new(GetRequest {
+            key: key.to_string(),
+            namespace: namespace.to_string(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    resp.found.then_some(resp.value)
+}
+
+#[tokio::test]
+async fn test_namespaces_keep_the_same_key_apart() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut _handles = Vec::new();
+    for i in 0..3 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        _handles.push(h);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    put(&nodes[0], "", "user", "shared").await;
+    put(&nodes[1], "alpha", "user", "a").await;
+    put(&nodes[2], "beta", "user", "b").await;
+    for i in 0..5 {
+        put(&nodes[0], "alpha", &format!("extra_{}", i), "x").await;
+    }
+
+    assert_eq!(get(&nodes[2], "", "user").await.unwrap(), b"shared");
+    assert_eq!(get(&nodes[0], "alpha", "user").await.unwrap(), b"a");
+    assert_eq!(get(&nodes[1], "beta", "user").await.unwrap(), b"b");
+    assert!(get(&nodes[0], "gamma", "user").await.is_none());
+    // A default-namespace key that looks namespaced is a key of its own
+    assert!(get(&nodes[0], "", "alpha:user").await.is_none());
+    put(&nodes[1], "", "alpha:user", "bare").await;
+    assert_eq!(get(&nodes[2], "", "alpha:user").await.unwrap(), b"bare");
+    assert_eq!(get(&nodes[2], "alpha", "user").await.unwrap(), b"a");
+
+    // Let replicas land so the deletion has copies to chase
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let deleted = nodes[1]
+        .delete_namespace(Request::new(DeleteNamespaceRequest {
+            namespace: "alpha".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .deleted;
+    assert_eq!(deleted, 6);
+
+    assert!(get(&nodes[0], "alpha", "user").await.is_none());
+    assert_eq!(get(&nodes[0], "beta", "user").await.unwrap(), b"b");
+    assert_eq!(get(&nodes[0], "", "user").await.unwrap(), b"shared");
+    assert_eq!(get(&nodes[0], "", "alpha:user").await.unwrap(), b"bare");
+    let prefix = namespaced_key("alpha", "").unwrap();
+    for node in &nodes {
+        let state = node.state.read().await;
+        assert!(state.store.keys().iter().all(|k| !k.starts_with(&prefix)));
+    }
+}
+
+#[tokio::test]
+async fn test_namespace_with_separator_is_rejected() {
+    let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+    let err = node
+        .put(Request::new(PutRequest {
+            key: "k".to_string(),
+            value: "v".into(),
+            namespace: "a:b".to_string(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn test_default_key_with_namespace_marker_is_rejected() {
+    let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+    let err = node
+        .put(Request::new(PutRequest {
+            key: format!("{}alpha:user", NAMESPACE_MARKER),
+            value: "v".into(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+    assert!(node.state.read().await.store.keys().is_empty());
+}
+
+#[tokio::test]
+async fn test_lists_are_namespaced() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut _handles = Vec::new();
+    for i in 0..3 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        _handles.push(h);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    let alpha = DhtClient::new(&nodes[0].addr)
+        .unwrap()
+        .with_namespace("alpha");
+    let default = DhtClient::new(&nodes[1].addr).unwrap();
+    alpha.append("log", "a1").await.unwrap();
+    alpha.append("log", "a2").await.unwrap();
+    default.append("log", "d1").await.unwrap();
+
+    assert_eq!(
+        alpha.get_list("log").await.unwrap().unwrap(),
+        vec![b"a1".to_vec(), b"a2".to_vec()]
+    );
+    assert_eq!(
+        default.get_list("log").await.unwrap().unwrap(),
+        vec![b"d1".to_vec()]
+    );
+    let prefix = namespaced_key("alpha", "").unwrap();
+    let mut stored = Vec::new();
+    for node in &nodes {
+        stored.extend(node.scan_local_prefix(&prefix).await);
+    }
+    assert!(stored
+        .iter()
+        .all(|k| *k == namespaced_key("alpha", "log").unwrap()));
+    assert!(!stored.is_empty());
+}
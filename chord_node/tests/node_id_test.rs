@@ -0,0 +1,100 @@
+use chord_node::Node;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::FindSuccessorRequest;
+use chord_proto::{hash_addr, hash_addr_salted, NodeIdSource};
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node, start_node_with};
+
+#[test]
+fn test_fixed_id_ignores_address_and_salt() {
+    let fixed = NodeIdSource::Fixed(42);
+    assert!(fixed.is_fixed());
+    assert_eq!(fixed.id(0), 42);
+    assert_eq!(fixed.id(3), 42, "a fixed id should never be rehashed");
+
+    let seeded = NodeIdSource::Hashed("node-a".to_string());
+    assert!(!seeded.is_fixed());
+    assert_eq!(seeded.id(0), hash_addr("node-a"));
+    assert_eq!(seeded.id(2), hash_addr_salted("node-a", 2));
+    assert_ne!(seeded.id(0), seeded.id(1));
+}
+
+#[tokio::test]
+async fn test_fixed_id_keeps_its_place_across_ports() {
+    let (seed, _hs) = start_node("127.0.0.1:0".to_string()).await;
+    seed.create().await.unwrap();
+
+    let fixed = seed.id.wrapping_add(u64::MAX / 2);
+    let (first, h1) =
+        start_node_with("127.0.0.1:0".to_string(), |_, addr| Node::new(fixed, addr)).await;
+    first.join(seed.addr.clone()).await.unwrap();
+    stabilize_ring(&[seed.clone(), first.clone()], 5).await;
+
+    h1.abort();
+    stabilize_ring(std::slice::from_ref(&seed), 5).await;
+
+    // Restarted on another port, it takes back the same range
+    let (second, _h2) =
+        start_node_with("127.0.0.1:0".to_string(), |_, addr| Node::new(fixed, addr)).await;
+    assert_ne!(second.addr, first.addr);
+    second.join(seed.addr.clone()).await.unwrap();
+    stabilize_ring(&[seed.clone(), second.clone()], 5).await;
+
+    let owner = seed
+        .find_successor(Request::new(FindSuccessorRequest {
+            id: fixed,
+            max_hops: 0,
+            dry_run: false,
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(owner.id, fixed);
+    assert_eq!(owner.address, second.addr);
+}
+
+#[test]
+fn test_id_is_hashed_from_the_advertised_address() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_chord_node"))
+        .args([
+            "--create",
+            "--bind",
+            "127.0.0.1",
+            "--advertise",
+            "localhost",
+        ])
+        .args(["--port", &port.to_string()])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let startup = BufReader::new(child.stdout.take().unwrap())
+        .lines()
+        .map_while(Result::ok)
+        .find(|line| line.starts_with("Node starting at"));
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let advertised = format!("localhost:{}", port);
+    let expected = format!(
+        "Node starting at {} with ID {}",
+        advertised,
+        hash_addr(&advertised)
+    );
+    assert_eq!(startup.as_deref(), Some(expected.as_str()));
+    assert_ne!(
+        hash_addr(&advertised),
+        hash_addr(&format!("127.0.0.1:{}", port)),
+        "the bind address would give the same id"
+    );
+}
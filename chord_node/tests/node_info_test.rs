@@ -0,0 +1,82 @@
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::{Empty, PutRequest};
+use std::collections::HashSet;
+use std::time::Duration;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_get_node_info_snapshot() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, _h2) = start_node("127.0.0.1:0".to_string()).await;
+
+    node2.join(node1.addr.clone()).await.unwrap();
+    stabilize_ring(&[node1.clone(), node2.clone()], 10).await;
+
+    let mut client = ChordClient::connect(format!("http://{}", node2.addr))
+        .await
+        .unwrap();
+    let snapshot = client
+        .get_node_info(Request::new(Empty {}))
+        .await
+        .expect("GetNodeInfo failed")
+        .into_inner();
+
+    assert_eq!(snapshot.id, node2.id);
+    assert_eq!(snapshot.address, node2.addr);
+    assert_eq!(snapshot.predecessor.map(|p| p.id), Some(node1.id));
+    assert_eq!(snapshot.successors[0].id, node1.id);
+    assert_eq!(snapshot.crate_version, env!("CARGO_PKG_VERSION"));
+    assert!(snapshot.uptime_seconds <= node2.started_at.elapsed().as_secs());
+
+    let state = node2.state.read().await;
+    assert_eq!(snapshot.finger_table, state.finger_table);
+}
+
+#[tokio::test]
+async fn test_node_info_splits_primary_and_replica_keys() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, _h2) = start_node("127.0.0.1:0".to_string()).await;
+
+    node2.join(node1.addr.clone()).await.unwrap();
+    stabilize_ring(&[node1.clone(), node2.clone()], 10).await;
+
+    let mut client = ChordClient::connect(format!("http://{}", node1.addr))
+        .await
+        .unwrap();
+    let keys: HashSet<String> = (0..10).map(|i| format!("split_key_{}", i)).collect();
+    for key in &keys {
+        client
+            .put(Request::new(PutRequest {
+                key: key.clone(),
+                value: "v".into(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let snapshots = [node1.snapshot().await, node2.snapshot().await];
+    // Each key has one primary, and with two nodes the other holds its replica
+    let mut primaries = HashSet::new();
+    for snapshot in &snapshots {
+        let stored: HashSet<&String> = snapshot.stored_keys.iter().collect();
+        let split: HashSet<&String> = snapshot
+            .primary_keys
+            .iter()
+            .chain(&snapshot.replica_keys)
+            .collect();
+        assert_eq!(stored, split);
+        for key in &snapshot.primary_keys {
+            assert!(primaries.insert(key.clone()), "{} has two primaries", key);
+        }
+    }
+    assert_eq!(primaries, keys);
+    assert_eq!(
+        snapshots[0].replica_keys.iter().collect::<HashSet<_>>(),
+        snapshots[1].primary_keys.iter().collect::<HashSet<_>>()
+    );
+}
@@ -0,0 +1,138 @@
+use chord_node::Node;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::{
+    GetRequest, KeyTransferChunk, NodeInfo, PutRequest, TransferKeysRequest, ValueChunk,
+};
+use chord_proto::hash_addr;
+use chord_proto::ring::owner_of;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+mod common;
+use common::{stabilize_ring, start_node, start_node_with};
+
+#[tokio::test]
+async fn test_observer_routes_but_stores_no_keys() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut _handles = Vec::new();
+    for _ in 0..3 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if let Some(first) = nodes.first() {
+            node.join(first.addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        _handles.push(h);
+    }
+    let (observer, h) = start_node_with("127.0.0.1:0".to_string(), |id, addr| {
+        Node::new(id, addr).as_observer()
+    })
+    .await;
+    _handles.push(h);
+    assert!(observer.is_observer());
+    observer.join(nodes[0].addr.clone()).await.unwrap();
+    nodes.push(observer.clone());
+    stabilize_ring(&nodes, 15).await;
+
+    for i in 0..30 {
+        observer
+            .put_internal(PutRequest {
+                key: format!("obs_{}", i),
+                value: format!("v{}", i).into_bytes(),
+                ..Default::default()
+            })
+            .await
+            .expect("Put through the observer failed");
+    }
+    for node in &nodes {
+        node.maintain_replication().await;
+    }
+
+    // Neither primaries nor replicas land on the observer
+    assert!(observer.scan_local_prefix("obs_").await.is_empty());
+    assert!(observer.snapshot().await.observer);
+
+    let mut members = Vec::new();
+    for node in &nodes {
+        members.push(NodeInfo {
+            id: node.id,
+            address: node.addr.clone(),
+            observer: node.is_observer(),
+        });
+    }
+    for i in 0..30 {
+        let key = format!("obs_{}", i);
+        let owner = owner_of(hash_addr(&key), &members).unwrap();
+        assert!(!owner.observer);
+        let holder = nodes.iter().find(|n| n.id == owner.id).unwrap();
+        assert!(
+            holder.scan_local_prefix(&key).await.contains(&key),
+            "{} missing from its owner {}",
+            key,
+            owner.id
+        );
+
+        let got = observer
+            .get_internal(GetRequest {
+                key: key.clone(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(got.found);
+        assert_eq!(got.value, format!("v{}", i).into_bytes());
+        assert_eq!(got.owner_id, owner.id);
+    }
+}
+
+#[tokio::test]
+async fn test_observer_refuses_replicas_and_transfers() {
+    let (observer, _handle) = start_node_with("127.0.0.1:0".to_string(), |id, addr| {
+        Node::new(id, addr).as_observer()
+    })
+    .await;
+    let mut client = ChordClient::connect(format!("http://{}", observer.addr))
+        .await
+        .unwrap();
+    let put = PutRequest {
+        key: "pushed".to_string(),
+        value: b"v".to_vec(),
+        updated_at: 1,
+        replication_factor: 1,
+        ..Default::default()
+    };
+    let chunk = ValueChunk {
+        key: put.key.clone(),
+        data: put.value.clone(),
+        updated_at: 1,
+        found: true,
+        replication_factor: 1,
+        ..Default::default()
+    };
+
+    let err = client.replicate(put.clone()).await.unwrap_err();
+    assert_eq!(err.code(), tonic::Code::FailedPrecondition);
+    let err = client
+        .replicate_stream(tokio_stream::iter(vec![chunk.clone()]))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::FailedPrecondition);
+    let err = client
+        .transfer_keys(TransferKeysRequest {
+            keys: HashMap::from([(put.key.clone(), put.value.clone())]),
+            updated_at: HashMap::from([(put.key.clone(), 1)]),
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::FailedPrecondition);
+    let err = client
+        .transfer_key_chunks(tokio_stream::iter(vec![KeyTransferChunk {
+            chunk: Some(chunk),
+            ..Default::default()
+        }]))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::FailedPrecondition);
+
+    assert!(observer.scan_local_prefix("pushed").await.is_empty());
+}
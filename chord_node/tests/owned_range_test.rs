@@ -0,0 +1,48 @@
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::Empty;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_owned_ranges_partition_id_space() {
+    let (single, _h) = start_node("127.0.0.1:0".to_string()).await;
+    let range = single.responsible_range().await;
+    assert!(
+        range.whole_ring,
+        "A lone node without predecessor owns everything"
+    );
+
+    let mut nodes = vec![single];
+    for _ in 0..3 {
+        let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+        node.join(nodes[0].addr.clone()).await.unwrap();
+        nodes.push(node);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    let mut ranges = Vec::new();
+    for node in &nodes {
+        let mut client = ChordClient::connect(format!("http://{}", node.addr))
+            .await
+            .unwrap();
+        let range = client
+            .owned_range(Request::new(Empty {}))
+            .await
+            .expect("OwnedRange failed")
+            .into_inner();
+        println!("Node {} owns ({}, {}]", node.id, range.start, range.end);
+        assert!(!range.whole_ring);
+        assert_eq!(range.end, node.id);
+        ranges.push(range);
+    }
+
+    // Sorted by end, each range must start where the previous one ended,
+    // wrapping around so the ranges cover the id space exactly once.
+    ranges.sort_by_key(|r| r.end);
+    for i in 0..ranges.len() {
+        let prev = &ranges[(i + ranges.len() - 1) % ranges.len()];
+        assert_eq!(ranges[i].start, prev.end, "Gap or overlap in ownership");
+    }
+}
@@ -0,0 +1,78 @@
+use chord_node::Node;
+use chord_proto::chord::NodeInfo;
+use chord_proto::hash_addr;
+use chord_proto::ring::owner_of;
+use std::sync::Arc;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+fn info(id: u64) -> NodeInfo {
+    NodeInfo {
+        id,
+        address: format!("node-{}", id),
+        observer: false,
+    }
+}
+
+#[test]
+fn test_owner_of_wraps_and_ignores_order() {
+    let members = [info(300), info(100), info(200)];
+    assert_eq!(owner_of(50, &members).unwrap().id, 100);
+    assert_eq!(owner_of(100, &members).unwrap().id, 100);
+    assert_eq!(owner_of(101, &members).unwrap().id, 200);
+    assert_eq!(owner_of(301, &members).unwrap().id, 100);
+    assert_eq!(owner_of(u64::MAX, &[info(u64::MAX)]).unwrap().id, u64::MAX);
+    assert!(owner_of(1, &[]).is_none());
+}
+
+#[test]
+fn test_owner_of_skips_observers() {
+    let observer = |id| NodeInfo {
+        observer: true,
+        ..info(id)
+    };
+    let members = [info(100), observer(200), info(300)];
+    assert_eq!(owner_of(150, &members).unwrap().id, 300);
+    assert_eq!(owner_of(200, &members).unwrap().id, 300);
+    assert_eq!(owner_of(250, &members).unwrap().id, 300);
+    // Wrapping past the top skips an observer at the bottom too
+    let members = [observer(100), info(300)];
+    assert_eq!(owner_of(301, &members).unwrap().id, 300);
+    assert!(owner_of(1, &[observer(100)]).is_none());
+}
+
+#[tokio::test]
+async fn test_owner_of_agrees_with_live_lookups() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut _handles = Vec::new();
+    for i in 0..5 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        _handles.push(h);
+    }
+    stabilize_ring(&nodes, 15).await;
+
+    let members: Vec<NodeInfo> = nodes
+        .iter()
+        .map(|n| NodeInfo {
+            id: n.id,
+            address: n.addr.clone(),
+            observer: false,
+        })
+        .collect();
+    for i in 0..50 {
+        let key_id = hash_addr(&format!("owner_key_{}", i));
+        let expected = owner_of(key_id, &members).unwrap();
+        let entry = &nodes[i % nodes.len()];
+        let found = entry.find_successor_internal(key_id).await.unwrap();
+        assert_eq!(found.id, expected.id, "key id {}", key_id);
+    }
+    // A node's own id belongs to it
+    for node in &nodes {
+        assert_eq!(owner_of(node.id, &members).unwrap().id, node.id);
+    }
+}
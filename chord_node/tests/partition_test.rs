@@ -0,0 +1,76 @@
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::PutRequest;
+use tonic::{Code, Request};
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+fn put(key: &str) -> Request<PutRequest> {
+    Request::new(PutRequest {
+        key: key.to_string(),
+        value: b"v".to_vec(),
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn test_single_node_ring_accepts_writes() {
+    let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+    stabilize_ring(std::slice::from_ref(&node), 3).await;
+    node.put(put("alone"))
+        .await
+        .expect("A lone node owns every key");
+}
+
+#[tokio::test]
+async fn test_isolated_node_refuses_writes() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, h2) = start_node("127.0.0.1:0".to_string()).await;
+    node2.join(node1.addr.clone()).await.unwrap();
+    stabilize_ring(&[node1.clone(), node2.clone()], 5).await;
+    node1.put(put("before")).await.unwrap();
+
+    // Cut node1 off from its only successor
+    h2.abort();
+    stabilize_ring(std::slice::from_ref(&node1), 3).await;
+    assert_eq!(node1.successor().await.id, node1.id);
+
+    let err = node1
+        .put(put("during"))
+        .await
+        .expect_err("A partitioned node should not take writes");
+    assert_eq!(err.code(), Code::Unavailable);
+    assert!(node1.state.read().await.store.get("during").is_none());
+
+    // Writes resume once another node links up with it
+    let (node3, _h3) = start_node("127.0.0.1:0".to_string()).await;
+    node3.join(node1.addr.clone()).await.unwrap();
+    stabilize_ring(&[node1.clone(), node3.clone()], 5).await;
+    node1.put(put("after")).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_last_node_after_graceful_leaves_accepts_writes() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, h2) = start_node("127.0.0.1:0".to_string()).await;
+    let (node3, h3) = start_node("127.0.0.1:0".to_string()).await;
+    node2.join(node1.addr.clone()).await.unwrap();
+    node3.join(node1.addr.clone()).await.unwrap();
+    stabilize_ring(&[node1.clone(), node2.clone(), node3.clone()], 8).await;
+    node1.put(put("before")).await.unwrap();
+
+    // Both others leave on purpose, one after the other
+    node2.leave_network().await.unwrap();
+    h2.abort();
+    stabilize_ring(&[node1.clone(), node3.clone()], 5).await;
+    node3.leave_network().await.unwrap();
+    h3.abort();
+    stabilize_ring(std::slice::from_ref(&node1), 5).await;
+    assert_eq!(node1.successor().await.id, node1.id);
+
+    node1
+        .put(put("after"))
+        .await
+        .expect("The last node standing should keep taking writes");
+    assert!(node1.state.read().await.store.get("after").is_some());
+}
@@ -0,0 +1,103 @@
+use chord_node::{Node, StoredValue};
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::NodeInfo;
+use chord_proto::hash_addr;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::Request;
+
+mod common;
+use common::start_node;
+
+fn info(node: &Node) -> NodeInfo {
+    NodeInfo {
+        id: node.id,
+        address: node.addr.clone(),
+        observer: false,
+    }
+}
+
+const KEYS: usize = 200;
+
+async fn fill(node: &Node) {
+    let mut state = node.state.write().await;
+    for i in 0..KEYS {
+        state
+            .store
+            .put(format!("key_{}", i), StoredValue::new(b"v".to_vec()));
+    }
+}
+
+async fn stored(node: &Node) -> BTreeSet<String> {
+    node.state.read().await.store.keys().into_iter().collect()
+}
+
+/// The generated keys whose id falls in (start, end].
+fn keys_in(start: u64, end: u64) -> BTreeSet<String> {
+    (0..KEYS)
+        .map(|i| format!("key_{}", i))
+        .filter(|k| Node::is_in_range_inclusive(hash_addr(k), start, end))
+        .collect()
+}
+
+async fn three_nodes() -> (Vec<Arc<Node>>, Vec<tokio::task::JoinHandle<()>>) {
+    let mut nodes = Vec::new();
+    let mut handles = Vec::new();
+    for _ in 0..3 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        nodes.push(node);
+        handles.push(h);
+    }
+    nodes.sort_by_key(|n| n.id);
+    (nodes, handles)
+}
+
+#[tokio::test]
+async fn test_lone_node_hands_over_only_the_new_predecessors_keys() {
+    let (node, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (pred, _h2) = start_node("127.0.0.1:0".to_string()).await;
+    fill(&node).await;
+    assert!(node.state.read().await.predecessor.is_none());
+
+    node.notify(Request::new(info(&pred))).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    // Alone, the node owned the whole ring; the wrapping (node, pred] moves
+    assert_eq!(stored(&pred).await, keys_in(node.id, pred.id));
+    assert_eq!(stored(&node).await, keys_in(pred.id, node.id));
+}
+
+#[tokio::test]
+async fn test_replicas_stay_when_a_new_predecessor_arrives() {
+    let (nodes, _handles) = three_nodes().await;
+    let (a, b, c) = (&nodes[0], &nodes[1], &nodes[2]);
+    c.set_neighbors(Some(info(a)), vec![info(a)]).await;
+    // c is primary for (a, c] and holds the rest as replicas
+    fill(c).await;
+
+    c.notify(Request::new(info(b))).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    assert_eq!(stored(b).await, keys_in(a.id, b.id));
+    let mut kept = keys_in(b.id, c.id);
+    kept.extend(keys_in(c.id, a.id));
+    assert_eq!(stored(c).await, kept);
+}
+
+#[tokio::test]
+async fn test_unknown_range_is_copied_not_moved() {
+    let (nodes, _handles) = three_nodes().await;
+    let (a, b, c) = (&nodes[0], &nodes[1], &nodes[2]);
+    // c lost its predecessor but is part of a ring
+    c.set_neighbors(None, vec![info(a)]).await;
+    fill(c).await;
+
+    c.notify(Request::new(info(b))).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    // The ring still routes b's id past a, so b gets (a, b]; keys in
+    // (c, a] are a's, which c only holds as replicas
+    assert_eq!(stored(b).await, keys_in(a.id, b.id));
+    assert_eq!(stored(c).await.len(), KEYS);
+}
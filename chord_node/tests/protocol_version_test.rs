@@ -0,0 +1,156 @@
+use chord_node::Node;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::{Handshake, KeyValue, NodeInfo, PutRequest};
+use chord_proto::{
+    hash_addr, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION, STREAMING_TRANSFER_PROTOCOL_VERSION,
+};
+use std::collections::HashMap;
+use std::time::Duration;
+use tonic::{Code, Request};
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_join_exchanges_versions() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, _h2) = start_node("127.0.0.1:0".to_string()).await;
+    node2.join(node1.addr.clone()).await.unwrap();
+
+    assert_eq!(node2.peer_version(node1.id).await, Some(PROTOCOL_VERSION));
+    assert_eq!(node1.peer_version(node2.id).await, Some(PROTOCOL_VERSION));
+}
+
+#[tokio::test]
+async fn test_incompatible_hello_is_rejected() {
+    let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+    let mut client = ChordClient::connect(format!("http://{}", node.addr))
+        .await
+        .unwrap();
+
+    let err = client
+        .hello(Request::new(Handshake {
+            version: MIN_PROTOCOL_VERSION - 1,
+            min_version: MIN_PROTOCOL_VERSION - 1,
+            node: None,
+        }))
+        .await
+        .expect_err("A version below the minimum should be refused");
+    assert_eq!(err.code(), Code::FailedPrecondition);
+
+    let err = client
+        .hello(Request::new(Handshake {
+            version: PROTOCOL_VERSION + 1,
+            min_version: PROTOCOL_VERSION + 1,
+            node: None,
+        }))
+        .await
+        .expect_err("A peer that requires a newer version should be refused");
+    assert_eq!(err.code(), Code::FailedPrecondition);
+}
+
+#[tokio::test]
+async fn test_old_peer_gets_no_metadata() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, _h2) = start_node("127.0.0.1:0".to_string()).await;
+    node2.join(node1.addr.clone()).await.unwrap();
+    stabilize_ring(&[node1.clone(), node2.clone()], 5).await;
+
+    // Pretend node2 was downgraded to a version without metadata
+    let mut client = ChordClient::connect(format!("http://{}", node1.addr))
+        .await
+        .unwrap();
+    client
+        .hello(Request::new(Handshake {
+            version: 1,
+            min_version: 1,
+            node: Some(NodeInfo {
+                id: node2.id,
+                address: node2.addr.clone(),
+                observer: false,
+            }),
+        }))
+        .await
+        .unwrap();
+    assert_eq!(node1.peer_version(node2.id).await, Some(1));
+
+    // A key node1 owns, so node2 only gets the replica
+    let key = (0..)
+        .map(|i| format!("key_{}", i))
+        .find(|k| Node::is_in_range_inclusive(hash_addr(k), node2.id, node1.id))
+        .unwrap();
+    client
+        .put(Request::new(PutRequest {
+            key: key.clone(),
+            value: b"v".to_vec(),
+            metadata: HashMap::from([("owner".to_string(), "test".to_string())]),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    let primary = node1.state.read().await.store.get(&key).unwrap();
+    assert_eq!(primary.metadata.len(), 1);
+    let replica = node2.state.read().await.store.get(&key).unwrap();
+    assert_eq!(replica.value, b"v");
+    assert!(replica.metadata.is_empty());
+}
+
+#[tokio::test]
+async fn test_old_peer_gets_keys_in_one_message() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, _h2) = start_node("127.0.0.1:0".to_string()).await;
+    node2.join(node1.addr.clone()).await.unwrap();
+    stabilize_ring(&[node1.clone(), node2.clone()], 5).await;
+
+    // Pretend node2 predates streamed transfers
+    let mut client = ChordClient::connect(format!("http://{}", node1.addr))
+        .await
+        .unwrap();
+    let old_version = STREAMING_TRANSFER_PROTOCOL_VERSION - 1;
+    client
+        .hello(Request::new(Handshake {
+            version: old_version,
+            min_version: MIN_PROTOCOL_VERSION,
+            node: Some(NodeInfo {
+                id: node2.id,
+                address: node2.addr.clone(),
+                observer: false,
+            }),
+        }))
+        .await
+        .unwrap();
+
+    // Keys node2 owns, imported through node1, which hands them over
+    let keys: Vec<String> = (0..)
+        .map(|i| format!("key_{}", i))
+        .filter(|k| Node::is_in_range_inclusive(hash_addr(k), node1.id, node2.id))
+        .take(5)
+        .collect();
+    let entries: Vec<KeyValue> = keys
+        .iter()
+        .map(|key| KeyValue {
+            key: key.clone(),
+            value: b"v".to_vec(),
+            updated_at: 1_000,
+            metadata: HashMap::from([("owner".to_string(), "test".to_string())]),
+            ..Default::default()
+        })
+        .collect();
+    let resp = client
+        .import(Request::new(tokio_stream::iter(entries)))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(resp.forwarded, keys.len() as u64);
+    assert_eq!(node1.peer_version(node2.id).await, Some(old_version));
+
+    let state = node2.state.read().await;
+    for key in &keys {
+        let entry = state.store.get(key).expect("key missing on its owner");
+        assert_eq!(entry.value, b"v");
+        assert_eq!(entry.updated_at, 1_000);
+        assert_eq!(entry.metadata.len(), 1);
+    }
+}
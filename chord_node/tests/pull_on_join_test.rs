@@ -0,0 +1,42 @@
+use chord_node::Node;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::PutRequest;
+use chord_proto::hash_addr;
+use std::collections::BTreeSet;
+use tonic::Request;
+
+mod common;
+use common::start_node;
+
+#[tokio::test]
+async fn test_joining_node_pulls_its_keys_immediately() {
+    let (node_a, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let keys: Vec<String> = (0..100).map(|i| format!("key_{}", i)).collect();
+    for key in &keys {
+        node_a
+            .put(Request::new(PutRequest {
+                key: key.clone(),
+                value: b"v".to_vec(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+    }
+
+    let (node_b, _h2) = start_node("127.0.0.1:0".to_string()).await;
+    node_b.join(node_a.addr.clone()).await.unwrap();
+
+    // No stabilization yet: B holds its range straight after joining
+    let expected: BTreeSet<String> = keys
+        .iter()
+        .filter(|k| Node::is_in_range_inclusive(hash_addr(k), node_a.id, node_b.id))
+        .cloned()
+        .collect();
+    assert!(!expected.is_empty());
+    let pulled: BTreeSet<String> = node_b.state.read().await.store.keys().into_iter().collect();
+    assert_eq!(pulled, expected);
+
+    // A keeps serving them until it learns about B
+    let state_a = node_a.state.read().await;
+    assert!(keys.iter().all(|k| state_a.store.contains_key(k)));
+}
@@ -0,0 +1,62 @@
+use chord_node::Node;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{GetRequest, PutRequest};
+use chord_proto::hash_addr;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_get_repairs_lagging_replica() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    for i in 0..3 {
+        let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    let key = "repair_key";
+    nodes[0]
+        .put(Request::new(PutRequest {
+            key: key.to_string(),
+            value: "repair_value".into(),
+            ..Default::default()
+        }))
+        .await
+        .expect("Put failed");
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let owner = nodes[0]
+        .find_successor_internal(hash_addr(key))
+        .await
+        .unwrap();
+    let replica = nodes
+        .iter()
+        .find(|n| n.id != owner.id)
+        .expect("Need a replica");
+
+    println!("Dropping key from replica {}", replica.id);
+    assert!(replica.state.write().await.store.delete(key).is_some());
+
+    let resp = nodes[0]
+        .get(Request::new(GetRequest {
+            key: key.to_string(),
+            ..Default::default()
+        }))
+        .await
+        .expect("Get failed")
+        .into_inner();
+    assert!(resp.found);
+
+    // Repair happens asynchronously after the read returns
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let state = replica.state.read().await;
+    let entry = state.store.get(key).expect("Replica was not repaired");
+    assert_eq!(entry.value, b"repair_value");
+}
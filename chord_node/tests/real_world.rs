@@ -69,17 +69,21 @@ async fn test_churn_and_concurrency() {
                 let put_res = client
                     .put(Request::new(PutRequest {
                         key: key.clone(),
-                        value: value.clone(),
+                        value: value.clone().into_bytes(),
+                        ..Default::default()
                     }))
                     .await;
 
                 if put_res.is_ok() {
                     // Try to Get it back immediately (might fail if not propagated or during churn)
                     let get_res = client
-                        .get(Request::new(GetRequest { key: key.clone() }))
+                        .get(Request::new(GetRequest {
+                            key: key.clone(),
+                            ..Default::default()
+                        }))
                         .await;
                     if let Ok(resp) = get_res {
-                        if resp.into_inner().value == value {
+                        if resp.into_inner().value == value.as_bytes() {
                             success_count += 1;
                         } else {
                             failure_count += 1;
@@ -156,7 +160,8 @@ async fn test_churn_and_concurrency() {
     node0
         .put(Request::new(PutRequest {
             key: key.to_string(),
-            value: value.to_string(),
+            value: value.into(),
+            ..Default::default()
         }))
         .await
         .expect("Final put failed");
@@ -167,10 +172,15 @@ async fn test_churn_and_concurrency() {
     let resp = node4
         .get(Request::new(GetRequest {
             key: key.to_string(),
+            ..Default::default()
         }))
         .await
         .expect("Final get failed");
 
-    assert_eq!(resp.into_inner().value, value, "Value mismatch after churn");
+    assert_eq!(
+        resp.into_inner().value,
+        value.as_bytes(),
+        "Value mismatch after churn"
+    );
     println!("Test passed!");
 }
@@ -0,0 +1,100 @@
+use chord_node::Node;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{Empty, NodeInfo, PutRequest};
+use chord_proto::hash_addr;
+use std::sync::Arc;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+fn info(node: &Node) -> NodeInfo {
+    NodeInfo {
+        id: node.id,
+        address: node.addr.clone(),
+        observer: false,
+    }
+}
+
+#[tokio::test]
+async fn test_rebalance_moves_misplaced_primaries_to_owner() {
+    let (a, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (b, _h2) = start_node("127.0.0.1:0".to_string()).await;
+    b.join(a.addr.clone()).await.unwrap();
+    stabilize_ring(&[a.clone(), b.clone()], 10).await;
+
+    // A key b owns, planted on a as a plain replica
+    let key = (0..)
+        .map(|i| format!("stray_{}", i))
+        .find(|k| Node::is_in_range_inclusive(hash_addr(k), a.id, b.id))
+        .unwrap();
+    a.replicate(Request::new(PutRequest {
+        key: key.clone(),
+        value: b"v".to_vec(),
+        ..Default::default()
+    }))
+    .await
+    .unwrap();
+
+    // Nothing is misplaced while a's predecessor is right
+    assert_eq!(a.rebalance_internal().await.unwrap(), 0);
+
+    // An out-of-date predecessor makes a treat the key as its own primary
+    a.set_neighbors(Some(info(&a)), vec![info(&b)]).await;
+    let mut client = ChordClient::connect(format!("http://{}", a.addr))
+        .await
+        .unwrap();
+    let moved = client
+        .rebalance(Request::new(Empty {}))
+        .await
+        .expect("Rebalance failed")
+        .into_inner()
+        .keys_moved;
+    assert_eq!(moved, 1);
+    assert_eq!(b.scan_local_prefix("stray_").await, vec![key.clone()]);
+    // a is b's successor, so its copy is still a valid replica
+    assert_eq!(a.scan_local_prefix("stray_").await, vec![key]);
+}
+
+#[tokio::test]
+async fn test_rebalance_drops_copies_outside_the_replica_set() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut _handles = Vec::new();
+    for i in 0..3 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        _handles.push(h);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    // Ring order b -> c -> a -> b, so a is b's second successor
+    let by_id = |id: u64| nodes.iter().find(|n| n.id == id).unwrap().clone();
+    let b = nodes[0].clone();
+    let c = by_id(b.successor().await.id);
+    let a = by_id(c.successor().await.id);
+    assert_eq!(a.successor().await.id, b.id);
+
+    // A key b owns with a single replica, which lives on c
+    let key = (0..)
+        .map(|i| format!("stray_{}", i))
+        .find(|k| Node::is_in_range_inclusive(hash_addr(k), a.id, b.id))
+        .unwrap();
+    a.replicate(Request::new(PutRequest {
+        key: key.clone(),
+        value: b"v".to_vec(),
+        replication_factor: 1,
+        ..Default::default()
+    }))
+    .await
+    .unwrap();
+
+    a.set_neighbors(Some(info(&a)), vec![info(&b), info(&c)])
+        .await;
+    assert_eq!(a.rebalance_internal().await.unwrap(), 1);
+    assert_eq!(b.scan_local_prefix("stray_").await, vec![key]);
+    assert!(a.scan_local_prefix("stray_").await.is_empty());
+}
@@ -0,0 +1,140 @@
+use chord_node::Node;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{GetRequest, PutRequest};
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node, start_node_with};
+
+async fn count_holders(nodes: &[Arc<Node>], key: &str) -> usize {
+    let mut holders = 0;
+    for node in nodes {
+        if node.state.read().await.store.contains_key(key) {
+            holders += 1;
+        }
+    }
+    holders
+}
+
+#[tokio::test]
+async fn test_per_key_replication_factor() {
+    const NUM_NODES: usize = 5;
+
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    for i in 0..NUM_NODES {
+        let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    nodes[0]
+        .put(Request::new(PutRequest {
+            key: "durable_key".to_string(),
+            value: "v".into(),
+            replication_factor: 3,
+            ..Default::default()
+        }))
+        .await
+        .expect("Put failed");
+    nodes[0]
+        .put(Request::new(PutRequest {
+            key: "default_key".to_string(),
+            value: "v".into(),
+            ..Default::default()
+        }))
+        .await
+        .expect("Put failed");
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // Primary plus its replicas
+    assert_eq!(count_holders(&nodes, "durable_key").await, 1 + 3);
+    assert_eq!(
+        count_holders(&nodes, "default_key").await,
+        1 + chord_node::constants::REPLICATION_COUNT
+    );
+
+    // Replicas remember the factor, so maintenance keeps targeting three successors
+    for node in &nodes {
+        if let Some(entry) = node.state.read().await.store.get("durable_key") {
+            assert_eq!(entry.replication_factor, 3);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_replication_count_zero_keeps_only_the_owner_copy() {
+    const NUM_NODES: usize = 4;
+
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut handles = Vec::new();
+    for i in 0..NUM_NODES {
+        let (node, h) = start_node_with("127.0.0.1:0".to_string(), |id, addr| {
+            Node::new(id, addr).with_replication_count(0).unwrap()
+        })
+        .await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        handles.push(h);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    let keys: Vec<String> = (0..20).map(|i| format!("cache_{}", i)).collect();
+    for key in &keys {
+        nodes[0]
+            .put(Request::new(PutRequest {
+                key: key.clone(),
+                value: "v".into(),
+                ..Default::default()
+            }))
+            .await
+            .expect("Put failed");
+    }
+    for node in &nodes {
+        node.maintain_replication().await;
+    }
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // No replica copies, even after anti-entropy
+    for key in &keys {
+        assert_eq!(count_holders(&nodes, key).await, 1, "{}", key);
+    }
+
+    // Once its owner is gone, a key is simply not found
+    let key = &keys[0];
+    let owner = nodes[0]
+        .find_successor_internal(chord_proto::hash_addr(key))
+        .await
+        .unwrap();
+    let dead = nodes.iter().position(|n| n.id == owner.id).unwrap();
+    handles[dead].abort();
+    let survivors: Vec<Arc<Node>> = nodes
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != dead)
+        .map(|(_, n)| n.clone())
+        .collect();
+    stabilize_ring(&survivors, 10).await;
+
+    let response = survivors[0]
+        .get_internal(GetRequest {
+            key: key.clone(),
+            ..Default::default()
+        })
+        .await
+        .expect("Get after the owner failed should not error");
+    assert!(!response.found);
+}
+
+#[test]
+fn test_replication_count_must_fit_the_successor_list() {
+    let node = Node::with_successor_list_len(1, "127.0.0.1:0".to_string(), 3).unwrap();
+    assert!(node.with_replication_count(4).is_err());
+}
@@ -0,0 +1,54 @@
+use chord_node::Node;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::PutRequest;
+use chord_proto::hash_addr;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node_with};
+
+#[tokio::test]
+async fn test_replication_reuses_pooled_connections() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut _handles = Vec::new();
+    for i in 0..3 {
+        let (node, h) = start_node_with("127.0.0.1:0".to_string(), |id, addr| {
+            Node::new(id, addr).with_replication_concurrency(1)
+        })
+        .await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        _handles.push(h);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    let owner = nodes[0].clone();
+    let pred = owner.state.read().await.predecessor.clone().unwrap();
+    let keys: Vec<String> = (0..)
+        .map(|i| format!("pooled_{}", i))
+        .filter(|k| Node::is_in_range_inclusive(hash_addr(k), pred.id, owner.id))
+        .take(50)
+        .collect();
+    for key in &keys {
+        owner
+            .put(Request::new(PutRequest {
+                key: key.clone(),
+                value: b"v".to_vec(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(1000)).await;
+
+    // One connection per replica, however many writes went out
+    assert_eq!(owner.replication_dials(), 2);
+    for replica in nodes.iter().filter(|n| n.id != owner.id) {
+        let state = replica.state.read().await;
+        assert!(keys.iter().all(|k| state.store.get(k).is_some()));
+    }
+}
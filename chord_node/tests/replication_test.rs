@@ -55,7 +55,8 @@ async fn test_replication() {
     client
         .put(Request::new(PutRequest {
             key: key.to_string(),
-            value: value.to_string(),
+            value: value.into(),
+            ..Default::default()
         }))
         .await
         .expect("Put failed");
@@ -66,9 +67,14 @@ async fn test_replication() {
     println!("\nVerifying data on all nodes...");
     for (i, node) in nodes.iter().enumerate() {
         let state = node.state.read().await;
-        if let Some(val) = state.store.get(key) {
-            println!("Node {} (ID: {}) HAS key. Value: {}", i, node.id, val);
-            assert_eq!(val, value, "Value mismatch on Node {}", i);
+        if let Some(val) = state.store.get(key).map(|e| e.value) {
+            println!(
+                "Node {} (ID: {}) HAS key. Value: {}",
+                i,
+                node.id,
+                String::from_utf8_lossy(&val)
+            );
+            assert_eq!(val, value.as_bytes(), "Value mismatch on Node {}", i);
         } else {
             panic!("Node {} (ID: {}) MISSING key '{}'", i, node.id, key);
         }
@@ -90,13 +96,14 @@ async fn test_replication() {
     let response = client_1
         .get(Request::new(GetRequest {
             key: key.to_string(),
+            ..Default::default()
         }))
         .await
         .expect("Get failed from Node 1");
 
     assert_eq!(
         response.into_inner().value,
-        value,
+        value.as_bytes(),
         "Value mismatch from Node 1 after Node 0 failure"
     );
     println!("✓ Data retrieved successfully from surviving node.");
@@ -0,0 +1,99 @@
+use chord_node::constants::FINGER_TABLE_SIZE;
+use chord_node::ring::{is_in_range, is_in_range_inclusive, IdSpace, ID_BITS};
+use rand::Rng;
+
+/// Every id met walking clockwise from `start` (exclusive) to `end`
+/// (inclusive), one step at a time; the whole circle when they are equal.
+fn walk(space: IdSpace, start: u64, end: u64) -> Vec<u64> {
+    let mut ids = Vec::new();
+    let mut id = start;
+    loop {
+        id = space.wrap(id + 1);
+        ids.push(id);
+        if id == end {
+            return ids;
+        }
+    }
+}
+
+#[test]
+fn test_range_checks_match_a_walk_of_the_circle() {
+    for bits in 1..=5 {
+        let space = IdSpace::new(bits);
+        let size = 1u64 << bits;
+        for start in 0..size {
+            for end in 0..size {
+                let inclusive = walk(space, start, end);
+                for id in 0..size {
+                    let expected = inclusive.contains(&id);
+                    assert_eq!(
+                        space.is_in_range_inclusive(id, start, end),
+                        expected,
+                        "{} in ({}, {}] mod {}",
+                        id,
+                        start,
+                        end,
+                        size
+                    );
+                    assert_eq!(
+                        space.is_in_range(id, start, end),
+                        expected && id != end,
+                        "{} in ({}, {}) mod {}",
+                        id,
+                        start,
+                        end,
+                        size
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_finger_targets_agree_with_range_checks() {
+    for bits in 1..=6 {
+        let space = IdSpace::new(bits);
+        let size = 1u64 << bits;
+        for id in 0..size {
+            for i in 0..bits as usize {
+                let target = space.finger_target(id, i);
+                assert!(target < size);
+                assert_eq!(space.distance(id, target), 1 << i);
+                // The target is the last id of the walk 2^i steps from id,
+                // however often that crosses zero
+                assert_eq!(*walk(space, id, target).last().unwrap(), target);
+                assert_eq!(walk(space, id, target).len() as u64, 1 << i);
+                assert!(space.is_in_range_inclusive(target, id, target));
+                assert!(!space.is_in_range(id, id, target));
+                if i + 1 < bits as usize {
+                    // Each finger covers the ids up to the next one
+                    let next = space.finger_target(id, i + 1);
+                    assert!(space.is_in_range(target, id, next));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_full_space_keeps_the_plain_u64_checks() {
+    assert_eq!(FINGER_TABLE_SIZE, ID_BITS as usize);
+    let space = IdSpace::new(64);
+    assert_eq!(space.finger_target(u64::MAX, 0), 0);
+    assert_eq!(space.finger_target(1 << 63, 63), 0);
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..10_000 {
+        let (id, start, end): (u64, u64, u64) = (rng.gen(), rng.gen(), rng.gen());
+        let plain = if start < end {
+            id > start && id < end
+        } else {
+            id > start || id < end
+        };
+        assert_eq!(is_in_range(id, start, end), plain);
+        assert_eq!(is_in_range_inclusive(id, start, end), plain || id == end);
+        // Ids right at the wrap boundary
+        assert_eq!(is_in_range_inclusive(0, start, end), start >= end);
+    }
+}
@@ -0,0 +1,69 @@
+use chord_node::Node;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::{PutRequest, ScanPrefixRequest};
+use std::sync::Arc;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_scan_prefix_lists_each_key_once() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    for i in 0..4 {
+        let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    let mut client = ChordClient::connect(format!("http://{}", nodes[0].addr))
+        .await
+        .unwrap();
+    let mut users: Vec<String> = (0..20).map(|i| format!("user:{}", i)).collect();
+    let orders: Vec<String> = (0..10).map(|i| format!("order:{}", i)).collect();
+    for key in users.iter().chain(&orders) {
+        client
+            .put(Request::new(PutRequest {
+                key: key.clone(),
+                value: "v".into(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+    }
+
+    // Replicas are in place, so every key sits on several nodes
+    let mut client = ChordClient::connect(format!("http://{}", nodes[2].addr))
+        .await
+        .unwrap();
+    let scan = |prefix: &str| ScanPrefixRequest {
+        prefix: prefix.to_string(),
+    };
+    let keys = client
+        .scan_prefix(Request::new(scan("user:")))
+        .await
+        .expect("ScanPrefix failed")
+        .into_inner()
+        .keys;
+    users.sort();
+    assert_eq!(keys, users);
+
+    let all = client
+        .scan_prefix(Request::new(scan("")))
+        .await
+        .unwrap()
+        .into_inner()
+        .keys;
+    assert_eq!(all.len(), users.len() + orders.len());
+
+    let none = client
+        .scan_prefix(Request::new(scan("missing:")))
+        .await
+        .unwrap()
+        .into_inner()
+        .keys;
+    assert!(none.is_empty());
+}
@@ -0,0 +1,160 @@
+use chord_node::Node;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{Empty, NodeInfo, PutRequest, ViolationKind};
+use chord_proto::hash_addr;
+use std::sync::Arc;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+fn info(node: &Node) -> NodeInfo {
+    NodeInfo {
+        id: node.id,
+        address: node.addr.clone(),
+        observer: false,
+    }
+}
+
+/// The descriptions of `node`'s violations of the given kind.
+async fn violations_of(node: &Node, kind: ViolationKind) -> Vec<String> {
+    node.self_check()
+        .await
+        .into_iter()
+        .filter(|v| v.kind == kind as i32)
+        .map(|v| v.description)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_stabilized_ring_has_no_violations() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut _handles = Vec::new();
+    for i in 0..4 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        _handles.push(h);
+    }
+    stabilize_ring(&nodes, 10).await;
+    for i in 0..40 {
+        nodes[i % 4]
+            .put(Request::new(PutRequest {
+                key: format!("key_{}", i),
+                value: b"v".to_vec(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+    }
+
+    for node in &nodes {
+        let mut client = ChordClient::connect(format!("http://{}", node.addr))
+            .await
+            .unwrap();
+        let resp = client
+            .self_check(Request::new(Empty {}))
+            .await
+            .expect("SelfCheck failed")
+            .into_inner();
+        assert!(
+            resp.violations.is_empty(),
+            "Node {} reported {:?}",
+            node.id,
+            resp.violations
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_broken_routing_state_is_reported() {
+    let (a, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (b, _h2) = start_node("127.0.0.1:0".to_string()).await;
+
+    // Lone and settled on itself: nothing to report
+    stabilize_ring(std::slice::from_ref(&a), 2).await;
+    assert!(a.self_check().await.is_empty());
+
+    a.set_neighbors(Some(info(&a)), vec![info(&b), info(&a), info(&b)])
+        .await;
+    assert_eq!(a.self_check().await.len(), 3);
+    let violations = violations_of(&a, ViolationKind::Routing).await;
+    assert_eq!(violations.len(), 3, "{:?}", violations);
+    assert!(violations.iter().any(|v| v.contains("alongside")));
+    assert!(violations.iter().any(|v| v.contains("listed twice")));
+    assert!(violations.iter().any(|v| v.contains("predecessor is us")));
+}
+
+#[tokio::test]
+async fn test_misplaced_primary_key_is_reported() {
+    let (a, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (b, _h2) = start_node("127.0.0.1:0".to_string()).await;
+    b.join(a.addr.clone()).await.unwrap();
+    stabilize_ring(&[a.clone(), b.clone()], 10).await;
+
+    // A key b owns, planted on a
+    let key = (0..)
+        .map(|i| format!("stray_{}", i))
+        .find(|k| Node::is_in_range_inclusive(hash_addr(k), a.id, b.id))
+        .unwrap();
+    a.replicate(Request::new(PutRequest {
+        key: key.clone(),
+        value: b"v".to_vec(),
+        ..Default::default()
+    }))
+    .await
+    .unwrap();
+
+    // Held as a replica it is where it should be
+    assert!(a.self_check().await.is_empty());
+
+    // Once a's predecessor is wrong, a takes it for its own primary
+    a.set_neighbors(Some(info(&a)), vec![info(&b)]).await;
+    let violations = violations_of(&a, ViolationKind::MisplacedKey).await;
+    let expected = format!(
+        "primary key '{}' (id {}) is owned by {}",
+        key,
+        hash_addr(&key),
+        b.id
+    );
+    assert!(violations.contains(&expected), "{:?}", violations);
+}
+
+#[tokio::test]
+async fn test_every_misplaced_primary_key_is_reported() {
+    let (a, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (b, _h2) = start_node("127.0.0.1:0".to_string()).await;
+    b.join(a.addr.clone()).await.unwrap();
+    stabilize_ring(&[a.clone(), b.clone()], 10).await;
+
+    // Keys b owns and keys a owns, all held by a
+    let (strays, own): (Vec<String>, Vec<String>) = (0..40)
+        .map(|i| format!("mixed_{}", i))
+        .partition(|k| Node::is_in_range_inclusive(hash_addr(k), a.id, b.id));
+    assert!(!strays.is_empty() && !own.is_empty());
+    for key in strays.iter().chain(&own) {
+        a.replicate(Request::new(PutRequest {
+            key: key.clone(),
+            value: b"v".to_vec(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+    }
+
+    a.set_neighbors(Some(info(&a)), vec![info(&b)]).await;
+    let violations = violations_of(&a, ViolationKind::MisplacedKey).await;
+    assert_eq!(violations.len(), strays.len(), "{:?}", violations);
+    for key in &strays {
+        let expected = format!(
+            "primary key '{}' (id {}) is owned by {}",
+            key,
+            hash_addr(key),
+            b.id
+        );
+        assert!(violations.contains(&expected), "{:?}", violations);
+    }
+}
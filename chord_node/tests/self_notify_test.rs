@@ -0,0 +1,119 @@
+use chord_node::Node;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::{NodeInfo, PutRequest};
+use chord_proto::hash_addr;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+fn info(node: &Node) -> NodeInfo {
+    NodeInfo {
+        id: node.id,
+        address: node.addr.clone(),
+        observer: false,
+    }
+}
+
+async fn predecessor_id(node: &Node) -> Option<u64> {
+    node.state.read().await.predecessor.as_ref().map(|p| p.id)
+}
+
+#[tokio::test]
+async fn test_lone_node_settles_on_self_notify() {
+    let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+
+    let mut client_a = ChordClient::connect(format!("http://{}", node.addr))
+        .await
+        .unwrap();
+    client_a
+        .put(Request::new(PutRequest {
+            key: "solo".to_string(),
+            value: b"value".to_vec(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+
+    // A lone node's successor is itself, so stabilize notifies itself. That
+    // settles it on its own without moving any keys.
+    for _ in 0..3 {
+        node.stabilize().await;
+    }
+    assert_eq!(predecessor_id(&node).await, Some(node.id));
+    assert!(node.state.read().await.store.contains_key("solo"));
+}
+
+#[tokio::test]
+async fn test_joined_node_ignores_self_notify() {
+    let (a, _ha) = start_node("127.0.0.1:0".to_string()).await;
+    let (b, _hb) = start_node("127.0.0.1:0".to_string()).await;
+    b.join(a.addr.clone()).await.unwrap();
+
+    // B has a successor but no predecessor yet; adopting itself would make it
+    // look settled and claim the whole ring
+    let mut client = ChordClient::connect(format!("http://{}", b.addr))
+        .await
+        .unwrap();
+    client.notify(Request::new(info(&b))).await.unwrap();
+    assert_eq!(predecessor_id(&b).await, None);
+
+    stabilize_ring(&[a.clone(), b.clone()], 5).await;
+    assert_eq!(predecessor_id(&b).await, Some(a.id));
+}
+
+#[tokio::test]
+async fn test_two_node_ring_settles_without_self_transfer() {
+    let (a, _ha) = start_node("127.0.0.1:0".to_string()).await;
+    let mut client_a = ChordClient::connect(format!("http://{}", a.addr))
+        .await
+        .unwrap();
+    let keys: Vec<String> = (0..50).map(|i| format!("key-{}", i)).collect();
+    for key in &keys {
+        client_a
+            .put(Request::new(PutRequest {
+                key: key.clone(),
+                value: b"value".to_vec(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+    }
+
+    let (b, _hb) = start_node("127.0.0.1:0".to_string()).await;
+    b.join(a.addr.clone()).await.unwrap();
+    stabilize_ring(&[a.clone(), b.clone()], 10).await;
+
+    // Each node is the other's predecessor and successor
+    assert_eq!(predecessor_id(&a).await, Some(b.id));
+    assert_eq!(predecessor_id(&b).await, Some(a.id));
+    assert_eq!(a.successor().await.id, b.id);
+    assert_eq!(b.successor().await.id, a.id);
+
+    // Self-notifies on a settled ring change nothing
+    for node in [&a, &b] {
+        let before = node.state.read().await.store.len();
+        let mut client = ChordClient::connect(format!("http://{}", node.addr))
+            .await
+            .unwrap();
+        client.notify(Request::new(info(node))).await.unwrap();
+        let other = if node.id == a.id { b.id } else { a.id };
+        assert_eq!(predecessor_id(node).await, Some(other));
+        assert_eq!(node.state.read().await.store.len(), before);
+    }
+
+    // Every key is held by its owner
+    for key in &keys {
+        let owner = if Node::is_in_range_inclusive(hash_addr(key), a.id, b.id) {
+            &b
+        } else {
+            &a
+        };
+        assert!(
+            owner.state.read().await.store.contains_key(key),
+            "owner {} is missing {}",
+            owner.id,
+            key
+        );
+    }
+}
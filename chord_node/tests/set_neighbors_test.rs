@@ -0,0 +1,40 @@
+use chord_proto::chord::NodeInfo;
+
+mod common;
+use common::start_node;
+
+fn info(node: &chord_node::Node) -> NodeInfo {
+    NodeInfo {
+        id: node.id,
+        address: node.addr.clone(),
+        observer: false,
+    }
+}
+
+#[tokio::test]
+async fn test_stabilize_repairs_skipped_successor() {
+    let mut nodes = Vec::new();
+    for _ in 0..3 {
+        let (node, _handle) = start_node("127.0.0.1:0".to_string()).await;
+        nodes.push(node);
+    }
+    nodes.sort_by_key(|n| n.id);
+    let (a, b, c) = (&nodes[0], &nodes[1], &nodes[2]);
+
+    // A skips over B, which C already knows as its predecessor
+    a.set_neighbors(Some(info(c)), vec![info(c)]).await;
+    b.set_neighbors(Some(info(a)), vec![info(c)]).await;
+    c.set_neighbors(Some(info(b)), vec![info(a)]).await;
+
+    a.stabilize().await;
+
+    let state = a.state.read().await;
+    assert_eq!(
+        state.successor_list[0].id, b.id,
+        "A should adopt B from its successor's predecessor"
+    );
+    drop(state);
+
+    let state = b.state.read().await;
+    assert_eq!(state.predecessor.as_ref().map(|p| p.id), Some(a.id));
+}
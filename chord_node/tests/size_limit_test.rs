@@ -0,0 +1,61 @@
+use chord_node::constants::{MAX_KEY_BYTES, MAX_VALUE_BYTES};
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::PutRequest;
+use tonic::{Code, Request};
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_oversized_writes_are_rejected() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, _h2) = start_node("127.0.0.1:0".to_string()).await;
+    node2.join(node1.addr.clone()).await.unwrap();
+    stabilize_ring(&[node1.clone(), node2.clone()], 10).await;
+    let nodes = [node1.clone(), node2.clone()];
+
+    let big_value = PutRequest {
+        key: "big_value".to_string(),
+        value: vec![0; MAX_VALUE_BYTES + 1],
+        ..Default::default()
+    };
+    let big_key = PutRequest {
+        key: "k".repeat(MAX_KEY_BYTES + 1),
+        value: b"v".to_vec(),
+        ..Default::default()
+    };
+
+    for req in [big_value, big_key] {
+        let err = node1
+            .put(Request::new(req.clone()))
+            .await
+            .expect_err("Oversized put should be rejected");
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        let err = node2
+            .replicate(Request::new(req.clone()))
+            .await
+            .expect_err("Oversized replica should be rejected");
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        // Nothing may have been stored or replicated anywhere
+        for node in &nodes {
+            let state = node.state.read().await;
+            assert!(
+                !state.store.contains_key(&req.key),
+                "Node {} stored a rejected key",
+                node.id
+            );
+        }
+    }
+
+    // A value right at the limit is still accepted
+    node1
+        .put(Request::new(PutRequest {
+            key: "limit_value".to_string(),
+            value: vec![0; MAX_VALUE_BYTES],
+            ..Default::default()
+        }))
+        .await
+        .expect("Value at the limit should be accepted");
+}
@@ -0,0 +1,75 @@
+use chord_node::{Node, StoredValue};
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{GetRequest, PutRequest};
+use chord_proto::hash_addr;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+fn get(key: &str, allow_stale: bool) -> Request<GetRequest> {
+    Request::new(GetRequest {
+        key: key.to_string(),
+        allow_stale,
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn test_allow_stale_reads_the_local_replica() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut _handles = Vec::new();
+    for i in 0..3 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        _handles.push(h);
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    let key = "hot_key";
+    nodes[0]
+        .put(Request::new(PutRequest {
+            key: key.to_string(),
+            value: b"v1".to_vec(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let primary = nodes[0]
+        .find_successor_internal(hash_addr(key))
+        .await
+        .unwrap();
+    let replica = nodes.iter().find(|n| n.id != primary.id).unwrap();
+    assert!(replica.state.read().await.store.get(key).is_some());
+
+    let resp = replica.get(get(key, true)).await.unwrap().into_inner();
+    assert!(resp.found);
+    assert!(resp.is_replica);
+    assert_eq!(resp.value, b"v1");
+
+    // Let the replica fall behind: only an opted-in read sees its old copy
+    replica
+        .state
+        .write()
+        .await
+        .store
+        .put(key.to_string(), StoredValue::new(b"old".to_vec()));
+    let stale = replica.get(get(key, true)).await.unwrap().into_inner();
+    assert_eq!(stale.value, b"old");
+    let fresh = replica.get(get(key, false)).await.unwrap().into_inner();
+    assert_eq!(fresh.value, b"v1");
+    assert!(!fresh.is_replica);
+
+    // The primary's own copy isn't flagged as a replica
+    let owner = nodes.iter().find(|n| n.id == primary.id).unwrap();
+    let resp = owner.get(get(key, true)).await.unwrap().into_inner();
+    assert!(!resp.is_replica);
+    assert_eq!(resp.value, b"v1");
+}
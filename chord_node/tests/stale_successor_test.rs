@@ -0,0 +1,37 @@
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_find_successor_recovers_from_dead_successor() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, h2) = start_node("127.0.0.1:0".to_string()).await;
+
+    println!("Node 1: {} ({})", node1.id, node1.addr);
+    println!("Node 2: {} ({})", node2.id, node2.addr);
+
+    node2.join(node1.addr.clone()).await.unwrap();
+    stabilize_ring(&[node1.clone(), node2.clone()], 10).await;
+
+    {
+        let state = node1.state.read().await;
+        assert_eq!(state.successor_list[0].id, node2.id);
+    }
+
+    println!("Killing Node 2 without stabilizing Node 1...");
+    h2.abort();
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+    // An id owned by Node 1 forces a remote hop through the stale successor.
+    let target = node2.id.wrapping_add(1);
+    let successor = node1
+        .find_successor_internal(target)
+        .await
+        .expect("Lookup should recover by refreshing the stale successor list");
+    assert_eq!(successor.id, node1.id);
+
+    let state = node1.state.read().await;
+    assert_eq!(
+        state.successor_list[0].id, node1.id,
+        "Dead successor should have been dropped"
+    );
+}
@@ -0,0 +1,149 @@
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::{Empty, GetRequest, PutRequest};
+use chord_proto::latency::{bucket_for, bucket_upper_bound, percentile, LATENCY_BUCKETS};
+use std::time::Duration;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_get_stats() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, _h2) = start_node("127.0.0.1:0".to_string()).await;
+
+    node2.join(node1.addr.clone()).await.unwrap();
+    stabilize_ring(&[node1.clone(), node2.clone()], 10).await;
+
+    let mut client = ChordClient::connect(format!("http://{}", node1.addr))
+        .await
+        .unwrap();
+
+    for i in 0..5 {
+        client
+            .put(Request::new(PutRequest {
+                key: format!("stats_key_{}", i),
+                value: "v".into(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+    }
+
+    let stats = client
+        .get_stats(Request::new(Empty {}))
+        .await
+        .expect("GetStats failed")
+        .into_inner();
+    println!("Node 1 stats: {:?}", stats);
+
+    let state = node1.state.read().await;
+    assert_eq!(stats.store_size, state.store.len() as u64);
+    assert_eq!(stats.successor_list_len, state.successor_list.len() as u64);
+    assert!(stats.has_predecessor, "Node 1 should have a predecessor");
+    let distinct: std::collections::HashSet<u64> =
+        state.finger_table.iter().map(|f| f.id).collect();
+    assert_eq!(stats.distinct_fingers, distinct.len() as u64);
+    assert!(stats.uptime_ms > 0);
+}
+
+#[tokio::test]
+async fn test_stats_report_replication_lag() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, _h2) = start_node("127.0.0.1:0".to_string()).await;
+
+    node2.join(node1.addr.clone()).await.unwrap();
+    stabilize_ring(&[node1.clone(), node2.clone()], 10).await;
+
+    let mut client = ChordClient::connect(format!("http://{}", node1.addr))
+        .await
+        .unwrap();
+    for i in 0..5 {
+        client
+            .put(Request::new(PutRequest {
+                key: format!("lag_key_{}", i),
+                value: "v".into(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // Each write has one replica, acked on whichever node owned it
+    let mut samples = 0;
+    for node in [&node1, &node2] {
+        let stats = node.stats().await;
+        assert!(stats.replication_lag_p99_ms >= stats.replication_lag_p50_ms);
+        samples += stats.replication_lag_samples;
+    }
+    assert_eq!(samples, 5);
+}
+
+#[test]
+fn test_latency_buckets() {
+    assert_eq!(bucket_for(Duration::ZERO), 0);
+    assert_eq!(bucket_for(Duration::from_micros(1)), 1);
+    assert_eq!(bucket_for(Duration::from_micros(1023)), 10);
+    assert_eq!(bucket_for(Duration::from_micros(1024)), 11);
+    assert_eq!(bucket_for(Duration::from_secs(3600)), LATENCY_BUCKETS - 1);
+    for micros in [0, 1, 5, 700, 40_000] {
+        let elapsed = Duration::from_micros(micros);
+        assert!(elapsed < bucket_upper_bound(bucket_for(elapsed)));
+    }
+
+    let mut buckets = vec![0; LATENCY_BUCKETS];
+    assert_eq!(percentile(&buckets, 0.5), Duration::ZERO);
+    buckets[3] = 90;
+    buckets[10] = 10;
+    assert_eq!(percentile(&buckets, 0.5), bucket_upper_bound(3));
+    assert_eq!(percentile(&buckets, 0.9), bucket_upper_bound(3));
+    assert_eq!(percentile(&buckets, 0.99), bucket_upper_bound(10));
+}
+
+#[tokio::test]
+async fn test_stats_report_request_latencies() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, _h2) = start_node("127.0.0.1:0".to_string()).await;
+
+    node2.join(node1.addr.clone()).await.unwrap();
+    stabilize_ring(&[node1.clone(), node2.clone()], 10).await;
+
+    let mut client = ChordClient::connect(format!("http://{}", node1.addr))
+        .await
+        .unwrap();
+    for i in 0..5 {
+        client
+            .put(Request::new(PutRequest {
+                key: format!("latency_key_{}", i),
+                value: "v".into(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+    }
+    for i in 0..3 {
+        client
+            .get(Request::new(GetRequest {
+                key: format!("latency_key_{}", i),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+    }
+
+    // Every request to node 1 is counted there, forwarded or not
+    let stats = client
+        .get_stats(Request::new(Empty {}))
+        .await
+        .unwrap()
+        .into_inner();
+    let count = |op: &str| -> u64 {
+        let latency = stats.op_latencies.iter().find(|l| l.op == op).unwrap();
+        assert_eq!(latency.buckets.len(), LATENCY_BUCKETS);
+        latency.buckets.iter().sum()
+    };
+    assert_eq!(count("put"), 5);
+    assert_eq!(count("get"), 3);
+    assert_eq!(count("append"), 0);
+}
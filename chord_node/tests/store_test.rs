@@ -0,0 +1,117 @@
+use chord_node::{KvStore, LruStore, MemoryStore, Node, StoredValue};
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::PutRequest;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node_with};
+
+#[test]
+fn test_memory_store_through_trait_object() {
+    let mut store: Box<dyn KvStore> = Box::new(MemoryStore::new());
+    assert!(store.is_empty());
+
+    assert!(store
+        .put("a".to_string(), StoredValue::new(b"1".to_vec()))
+        .is_none());
+    let replaced = store.put("a".to_string(), StoredValue::new(b"2".to_vec()));
+    assert_eq!(replaced.unwrap().value, b"1");
+    store.put("b".to_string(), StoredValue::new(b"3".to_vec()));
+
+    assert_eq!(store.len(), 2);
+    assert_eq!(store.get("a").unwrap().value, b"2");
+    assert!(store.contains_key("b"));
+    let mut keys = store.keys();
+    keys.sort();
+    assert_eq!(keys, vec!["a", "b"]);
+    assert_eq!(store.entries().len(), 2);
+
+    assert_eq!(store.delete("a").unwrap().value, b"2");
+    assert!(store.delete("a").is_none());
+    assert!(store.get("a").is_none());
+    assert_eq!(store.len(), 1);
+}
+
+fn value(bytes: usize) -> StoredValue {
+    StoredValue::new(vec![0; bytes])
+}
+
+#[test]
+fn test_lru_store_evicts_least_recently_used() {
+    let mut store = LruStore::new(Some(3), None);
+    for key in ["a", "b", "c"] {
+        store.put(key.to_string(), value(1));
+    }
+    // Reading "a" makes "b" the coldest key
+    store.get("a");
+    store.put("d".to_string(), value(1));
+
+    assert_eq!(store.evict(&|_| true), vec!["b"]);
+    let mut keys = store.keys();
+    keys.sort();
+    assert_eq!(keys, vec!["a", "c", "d"]);
+}
+
+#[test]
+fn test_lru_store_evicts_primary_keys_before_replicas() {
+    let mut store = LruStore::new(Some(2), None);
+    let is_primary = |key: &str| key.starts_with("primary");
+    store.put("replica_old".to_string(), value(1));
+    store.put("primary_1".to_string(), value(1));
+    store.put("primary_2".to_string(), value(1));
+
+    assert_eq!(store.evict(&is_primary), vec!["primary_1"]);
+    assert!(store.contains_key("replica_old"));
+
+    // With no primary keys left, replicas go too rather than overflowing
+    store.delete("primary_2");
+    for i in 0..3 {
+        store.put(format!("replica_{}", i), value(1));
+    }
+    assert_eq!(store.evict(&is_primary), vec!["replica_old", "replica_0"]);
+    assert_eq!(store.len(), 2);
+}
+
+#[test]
+fn test_lru_store_byte_limit() {
+    let mut store = LruStore::new(None, Some(100));
+    for i in 0..10 {
+        // 2 byte key + 20 byte value
+        store.put(format!("k{}", i), value(20));
+        store.evict(&|_| true);
+    }
+    assert_eq!(store.len(), 4);
+    let mut keys = store.keys();
+    keys.sort();
+    assert_eq!(keys, vec!["k6", "k7", "k8", "k9"]);
+}
+
+#[tokio::test]
+async fn test_bounded_node_keeps_newest_primary_keys() {
+    let (node, _h) = start_node_with("127.0.0.1:0".to_string(), |id, addr| {
+        Node::new(id, addr).with_store(Box::new(LruStore::new(Some(5), None)))
+    })
+    .await;
+    stabilize_ring(std::slice::from_ref(&node), 2).await;
+
+    let mut client = ChordClient::connect(format!("http://{}", node.addr))
+        .await
+        .unwrap();
+    let keys: Vec<String> = (0..12).map(|i| format!("cache_key_{}", i)).collect();
+    for key in &keys {
+        client
+            .put(Request::new(PutRequest {
+                key: key.clone(),
+                value: "v".into(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+    }
+
+    let mut stored = node.state.read().await.store.keys();
+    stored.sort();
+    let mut newest = keys[keys.len() - 5..].to_vec();
+    newest.sort();
+    assert_eq!(stored, newest);
+}
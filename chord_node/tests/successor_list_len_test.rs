@@ -0,0 +1,64 @@
+use chord_node::constants::REPLICATION_COUNT;
+use chord_node::{ConfigError, Node};
+use std::sync::Arc;
+
+mod common;
+use common::{stabilize_ring, start_node_with};
+
+#[test]
+fn test_successor_list_shorter_than_replication_is_rejected() {
+    let err = Node::with_successor_list_len(1, "127.0.0.1:0".to_string(), REPLICATION_COUNT - 1)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ConfigError::SuccessorListTooShort {
+            successor_list_len: REPLICATION_COUNT - 1,
+            replication_count: REPLICATION_COUNT,
+        }
+    );
+    assert!(Node::with_successor_list_len(1, "127.0.0.1:0".to_string(), 0).is_err());
+}
+
+#[test]
+fn test_replication_count_is_checked_against_successor_list_len() {
+    let node = || Node::with_successor_list_len(1, "127.0.0.1:0".to_string(), REPLICATION_COUNT);
+    let err = node()
+        .unwrap()
+        .with_replication_count(REPLICATION_COUNT + 1)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ConfigError::SuccessorListTooShort {
+            successor_list_len: REPLICATION_COUNT,
+            replication_count: REPLICATION_COUNT + 1,
+        }
+    );
+
+    let node = node()
+        .and_then(|node| node.with_replication_count(REPLICATION_COUNT - 1))
+        .unwrap();
+    assert_eq!(node.successor_list_len(), REPLICATION_COUNT);
+    assert_eq!(node.replication_count(), REPLICATION_COUNT - 1);
+}
+
+#[tokio::test]
+async fn test_successor_list_is_truncated_to_configured_len() {
+    let len = REPLICATION_COUNT + 1;
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    for i in 0..6 {
+        let (node, _h) = start_node_with("127.0.0.1:0".to_string(), |id, addr| {
+            Node::with_successor_list_len(id, addr, len).unwrap()
+        })
+        .await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+    }
+    stabilize_ring(&nodes, 15).await;
+
+    for node in &nodes {
+        assert_eq!(node.successor_list_len(), len);
+        assert_eq!(node.state.read().await.successor_list.len(), len);
+    }
+}
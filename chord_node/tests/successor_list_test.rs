@@ -0,0 +1,54 @@
+use chord_node::Node;
+use std::sync::Arc;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+async fn build_ring(size: usize) -> Vec<Arc<Node>> {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    for i in 0..size {
+        let (node, _handle) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+    }
+    stabilize_ring(&nodes, 10).await;
+    nodes
+}
+
+/// Expected successor list for each node: the other nodes in ring order.
+async fn assert_successor_lists(nodes: &[Arc<Node>]) {
+    let mut ids: Vec<u64> = nodes.iter().map(|n| n.id).collect();
+    ids.sort();
+
+    for node in nodes {
+        let pos = ids.iter().position(|&id| id == node.id).unwrap();
+        let expected: Vec<u64> = (1..ids.len())
+            .map(|offset| ids[(pos + offset) % ids.len()])
+            .collect();
+
+        let state = node.state.read().await;
+        let actual: Vec<u64> = state.successor_list.iter().map(|s| s.id).collect();
+        println!("Node {} successors: {:?}", node.id, actual);
+
+        assert!(
+            !actual.contains(&node.id),
+            "Node {} lists itself as a successor",
+            node.id
+        );
+        assert_eq!(actual, expected, "Wrong successor list for {}", node.id);
+    }
+}
+
+#[tokio::test]
+async fn test_successor_list_two_node_ring() {
+    let nodes = build_ring(2).await;
+    assert_successor_lists(&nodes).await;
+}
+
+#[tokio::test]
+async fn test_successor_list_three_node_ring() {
+    let nodes = build_ring(3).await;
+    assert_successor_lists(&nodes).await;
+}
@@ -0,0 +1,69 @@
+use chord_node::Node;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::{NodeInfo, TracedLookupRequest};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_traced_lookup_records_route() {
+    let mut nodes: Vec<Arc<Node>> = Vec::new();
+    let mut _handles = Vec::new();
+    for i in 0..6 {
+        let (node, h) = start_node("127.0.0.1:0".to_string()).await;
+        if i > 0 {
+            node.join(nodes[0].addr.clone()).await.unwrap();
+        }
+        nodes.push(node);
+        _handles.push(h);
+    }
+    stabilize_ring(&nodes, 15).await;
+
+    let origin = nodes[0].clone();
+    let mut client = ChordClient::connect(format!("http://{}", origin.addr))
+        .await
+        .unwrap();
+    let mut saw_forward = false;
+    for i in 0..20u64 {
+        let id = i.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        let response = client
+            .find_successor_traced(Request::new(TracedLookupRequest {
+                id,
+                path: Vec::new(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let expected = origin.find_successor_internal(id).await.unwrap();
+        assert_eq!(response.successor.map(|n| n.id), Some(expected.id));
+
+        assert_eq!(response.path[0].id, origin.id, "path should start here");
+        let distinct: HashSet<u64> = response.path.iter().map(|n| n.id).collect();
+        assert_eq!(distinct.len(), response.path.len(), "path repeats a node");
+        saw_forward |= response.path.len() > 1;
+    }
+    assert!(saw_forward, "no lookup was forwarded");
+}
+
+#[tokio::test]
+async fn test_traced_lookup_reports_loop() {
+    let (node, _h) = start_node("127.0.0.1:0".to_string()).await;
+    stabilize_ring(std::slice::from_ref(&node), 2).await;
+
+    // A path that already contains this node means the route came back
+    let path = vec![NodeInfo {
+        id: node.id,
+        address: node.addr.clone(),
+        observer: false,
+    }];
+    let response = node
+        .find_successor_traced_internal(node.id.wrapping_add(1), path)
+        .await
+        .unwrap();
+    assert!(response.successor.is_none());
+    assert_eq!(response.path.len(), 2);
+}
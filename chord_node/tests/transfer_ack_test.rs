@@ -0,0 +1,57 @@
+use chord_node::{Node, StoredValue};
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::NodeInfo;
+use chord_proto::{hash_addr, MAX_KEY_BYTES};
+use std::time::Duration;
+use tonic::Request;
+
+mod common;
+use common::start_node;
+
+#[tokio::test]
+async fn test_sender_keeps_keys_the_receiver_rejected() {
+    let (node, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (pred, _h2) = start_node("127.0.0.1:0".to_string()).await;
+
+    // Keys the new predecessor takes over from the lone node; one of them
+    // is over the key size limit, e.g. stored by an older version
+    let moving = |k: &String| Node::is_in_range_inclusive(hash_addr(k), node.id, pred.id);
+    let small: Vec<String> = (0..)
+        .map(|i| format!("key_{}", i))
+        .filter(moving)
+        .take(5)
+        .collect();
+    let oversized = (0..)
+        .map(|i| format!("{}{}", "k".repeat(MAX_KEY_BYTES), i))
+        .find(moving)
+        .unwrap();
+    {
+        let mut state = node.state.write().await;
+        for key in small.iter().chain([&oversized]) {
+            state
+                .store
+                .put(key.clone(), StoredValue::new(b"v".to_vec()));
+        }
+    }
+
+    node.notify(Request::new(NodeInfo {
+        id: pred.id,
+        address: pred.addr.clone(),
+        observer: false,
+    }))
+    .await
+    .unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    let pred_state = pred.state.read().await;
+    let node_state = node.state.read().await;
+    for key in &small {
+        assert!(pred_state.store.get(key).is_some());
+        assert!(node_state.store.get(key).is_none());
+    }
+    assert!(pred_state.store.get(&oversized).is_none());
+    assert!(
+        node_state.store.get(&oversized).is_some(),
+        "A key the receiver rejected must not be dropped"
+    );
+}
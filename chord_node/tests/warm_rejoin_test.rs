@@ -0,0 +1,98 @@
+use chord_node::StoredValue;
+use chord_proto::chord::chord_server::Chord;
+use chord_proto::chord::{GetRequest, PutRequest};
+use chord_proto::hash_addr;
+use tonic::Request;
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+#[tokio::test]
+async fn test_rejoin_hands_off_foreign_keys() {
+    let mut nodes = Vec::new();
+    for _ in 0..3 {
+        let (node, _handle) = start_node("127.0.0.1:0".to_string()).await;
+        nodes.push(node);
+    }
+    for node in nodes.iter().skip(1) {
+        node.join(nodes[0].addr.clone()).await.unwrap();
+    }
+    stabilize_ring(&nodes, 10).await;
+
+    nodes[0]
+        .put(Request::new(PutRequest {
+            key: "shared_key".to_string(),
+            value: "fresh".into(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+
+    // A restarted node whose store still holds data from its previous run
+    let (rejoined, _h) = start_node("127.0.0.1:0".to_string()).await;
+    {
+        let mut state = rejoined.state.write().await;
+        for i in 0..30 {
+            state.store.put(
+                format!("rejoin_key_{}", i),
+                StoredValue::new(format!("value_{}", i).into_bytes()),
+            );
+        }
+        state.store.put(
+            "shared_key".to_string(),
+            StoredValue {
+                updated_at: 1,
+                ..StoredValue::new(b"stale".to_vec())
+            },
+        );
+    }
+
+    rejoined
+        .join_with_handoff(nodes[0].addr.clone())
+        .await
+        .expect("Join with handoff failed");
+    println!(
+        "Rejoined node {} kept {} keys",
+        rejoined.id,
+        rejoined.state.read().await.store.len()
+    );
+
+    nodes.push(rejoined.clone());
+    stabilize_ring(&nodes, 10).await;
+
+    // Whatever the rejoined node still holds, it owns
+    let kept: Vec<String> = rejoined.state.read().await.store.keys();
+    for key in kept {
+        let owner = rejoined
+            .find_successor_internal(hash_addr(&key))
+            .await
+            .unwrap();
+        assert_eq!(owner.id, rejoined.id, "Key '{}' should have moved", key);
+    }
+
+    for i in 0..30 {
+        let resp = nodes[0]
+            .get(Request::new(GetRequest {
+                key: format!("rejoin_key_{}", i),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(resp.found, "rejoin_key_{} was lost", i);
+        assert_eq!(resp.value, format!("value_{}", i).into_bytes());
+    }
+
+    let resp = nodes[0]
+        .get(Request::new(GetRequest {
+            key: "shared_key".to_string(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(
+        resp.value, b"fresh",
+        "Stale copy must not overwrite a newer write"
+    );
+}
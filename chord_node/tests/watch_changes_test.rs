@@ -0,0 +1,85 @@
+use chord_node::Node;
+use chord_proto::chord::chord_client::ChordClient;
+use chord_proto::chord::{ChangeEvent, ChangeOp, DeleteRangeRequest, Empty, PutRequest};
+use chord_proto::hash_addr;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::{Request, Streaming};
+
+mod common;
+use common::{stabilize_ring, start_node};
+
+async fn watch(node: &Arc<Node>) -> Streaming<ChangeEvent> {
+    let mut client = ChordClient::connect(format!("http://{}", node.addr))
+        .await
+        .unwrap();
+    client
+        .watch_changes(Request::new(Empty {}))
+        .await
+        .expect("WatchChanges failed")
+        .into_inner()
+}
+
+async fn next_event(stream: &mut Streaming<ChangeEvent>) -> ChangeEvent {
+    tokio::time::timeout(Duration::from_secs(5), stream.message())
+        .await
+        .expect("Timed out waiting for a change event")
+        .expect("Watch stream failed")
+        .expect("Watch stream ended")
+}
+
+#[tokio::test]
+async fn test_watch_changes_on_owner_and_replica() {
+    let (node1, _h1) = start_node("127.0.0.1:0".to_string()).await;
+    let (node2, _h2) = start_node("127.0.0.1:0".to_string()).await;
+    node2.join(node1.addr.clone()).await.unwrap();
+    stabilize_ring(&[node1.clone(), node2.clone()], 10).await;
+
+    let key = "watched_key";
+    let (owner, replica) = if Node::is_in_range_inclusive(hash_addr(key), node2.id, node1.id) {
+        (node1.clone(), node2.clone())
+    } else {
+        (node2.clone(), node1.clone())
+    };
+    let mut owner_events = watch(&owner).await;
+    let mut replica_events = watch(&replica).await;
+
+    let mut client = ChordClient::connect(format!("http://{}", replica.addr))
+        .await
+        .unwrap();
+    client
+        .put(Request::new(PutRequest {
+            key: key.to_string(),
+            value: b"v1".to_vec(),
+            ..Default::default()
+        }))
+        .await
+        .expect("Put failed");
+
+    let event = next_event(&mut owner_events).await;
+    assert_eq!(event.op(), ChangeOp::Put);
+    assert_eq!(event.key, key);
+    assert_eq!(event.value, b"v1");
+    let version = event.version;
+
+    let event = next_event(&mut replica_events).await;
+    assert_eq!(event.op(), ChangeOp::Replicate);
+    assert_eq!(event.key, key);
+    assert_eq!(event.version, version);
+
+    // start_id == end_id covers the whole ring
+    client
+        .delete_range(Request::new(DeleteRangeRequest {
+            start_id: 0,
+            end_id: 0,
+        }))
+        .await
+        .expect("DeleteRange failed");
+
+    for events in [&mut owner_events, &mut replica_events] {
+        let event = next_event(events).await;
+        assert_eq!(event.op(), ChangeOp::Delete);
+        assert_eq!(event.key, key);
+        assert!(event.value.is_empty());
+    }
+}
@@ -0,0 +1,45 @@
+//! Node address handling. An address is `host:port`, where the host is an
+//! IPv4 literal, a bracketed IPv6 literal or a DNS name. Node ids are hashed
+//! from the address, so every spelling of one address is reduced to the same
+//! string before it is hashed, stored or dialed.
+
+use std::net::{Ipv6Addr, SocketAddr};
+
+/// The canonical form of `addr`: any scheme and trailing slash dropped, IP
+/// literals in their standard notation (`[0:0::1]:80` becomes `[::1]:80`),
+/// and DNS names lowercased without a trailing dot. Anything else is
+/// returned trimmed but otherwise unchanged.
+pub fn normalize_addr(addr: &str) -> String {
+    let addr = addr.trim();
+    let addr = addr.split_once("://").map_or(addr, |(_, rest)| rest);
+    let addr = addr.trim_end_matches('/');
+    if let Ok(socket) = addr.parse::<SocketAddr>() {
+        return socket.to_string();
+    }
+    match addr.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => format!(
+            "{}:{}",
+            host.trim_end_matches('.').to_ascii_lowercase(),
+            port
+        ),
+        _ => addr.to_string(),
+    }
+}
+
+/// The address of `host` on `port`, bracketing a bare IPv6 literal so the
+/// port stays separable.
+pub fn host_port(host: &str, port: u16) -> String {
+    let host = host.trim();
+    let bare = host.trim_start_matches('[').trim_end_matches(']');
+    match bare.parse::<Ipv6Addr>() {
+        Ok(ip) => format!("[{}]:{}", ip, port),
+        Err(_) => normalize_addr(&format!("{}:{}", host, port)),
+    }
+}
+
+/// `addr` as a URL to dial, normalized, keeping its scheme or adding
+/// `http://` when none is given.
+pub fn node_url(addr: &str) -> String {
+    let scheme = addr.trim().split_once("://").map_or("http", |(s, _)| s);
+    format!("{}://{}", scheme, normalize_addr(addr))
+}
@@ -0,0 +1,70 @@
+//! Key-value streams with every value split into `ValueChunk`s, so that
+//! bulk exports and imports can carry values larger than one gRPC message.
+//! A key's first chunk is marked `found` and carries its key, timestamp,
+//! replication factor and metadata; the chunks after it carry only data.
+
+use crate::chord::{KeyValue, ValueChunk};
+use crate::{MAX_VALUE_BYTES, VALUE_CHUNK_SIZE};
+
+/// Splits `kv` into chunks of at most `VALUE_CHUNK_SIZE` value bytes.
+pub fn key_value_chunks(kv: KeyValue) -> Vec<ValueChunk> {
+    let mut chunks: Vec<ValueChunk> = kv
+        .value
+        .chunks(VALUE_CHUNK_SIZE)
+        .map(|data| ValueChunk {
+            data: data.to_vec(),
+            ..Default::default()
+        })
+        .collect();
+    if chunks.is_empty() {
+        chunks.push(ValueChunk::default());
+    }
+    chunks[0].key = kv.key;
+    chunks[0].updated_at = kv.updated_at;
+    chunks[0].replication_factor = kv.replication_factor;
+    chunks[0].metadata = kv.metadata;
+    chunks[0].found = true;
+    chunks
+}
+
+/// Puts the entries of a chunked stream back together, one key at a time.
+#[derive(Debug, Default)]
+pub struct KeyValueAssembler {
+    current: Option<KeyValue>,
+}
+
+impl KeyValueAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the next chunk of the stream. Returns the previous entry once
+    /// `chunk` starts the next one. A value is only buffered until it is
+    /// over `MAX_VALUE_BYTES`; the rest of it is dropped, since the entry
+    /// is rejected anyway.
+    pub fn push(&mut self, chunk: ValueChunk) -> Result<Option<KeyValue>, String> {
+        if !chunk.found {
+            let current = self
+                .current
+                .as_mut()
+                .ok_or("value chunk before the first key")?;
+            if current.value.len() <= MAX_VALUE_BYTES {
+                current.value.extend_from_slice(&chunk.data);
+            }
+            return Ok(None);
+        }
+        let next = KeyValue {
+            key: chunk.key,
+            value: chunk.data,
+            updated_at: chunk.updated_at,
+            replication_factor: chunk.replication_factor,
+            metadata: chunk.metadata,
+        };
+        Ok(self.current.replace(next))
+    }
+
+    /// The last entry, once the stream has ended.
+    pub fn finish(self) -> Option<KeyValue> {
+        self.current
+    }
+}
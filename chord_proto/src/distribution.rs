@@ -0,0 +1,33 @@
+//! Measures of how evenly keys are spread over the nodes, from each node's
+//! primary key count.
+
+/// Gini coefficient of `counts`: 0 when they are all equal, approaching 1 as
+/// a single node holds everything. 0 with no nodes or no keys.
+pub fn gini(counts: &[u64]) -> f64 {
+    let total: u64 = counts.iter().sum();
+    if counts.is_empty() || total == 0 {
+        return 0.0;
+    }
+    let mut sorted = counts.to_vec();
+    sorted.sort_unstable();
+    let n = sorted.len() as f64;
+    // Sum of (2i - n - 1) * x_i over the counts in ascending order, i from 1
+    let weighted: f64 = sorted
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| (2.0 * (i + 1) as f64 - n - 1.0) * count as f64)
+        .sum();
+    weighted / (n * total as f64)
+}
+
+/// The largest count over the mean: 1 when the keys are spread evenly, `n`
+/// when one of `n` nodes holds them all. 0 with no nodes or no keys.
+pub fn max_to_mean(counts: &[u64]) -> f64 {
+    let total: u64 = counts.iter().sum();
+    if counts.is_empty() || total == 0 {
+        return 0.0;
+    }
+    let mean = total as f64 / counts.len() as f64;
+    let max = counts.iter().copied().max().unwrap_or(0);
+    max as f64 / mean
+}
@@ -0,0 +1,37 @@
+//! Request latency histograms as reported in `NodeStats`. Bucket `i` counts
+//! requests that took under 2^i microseconds; the last bucket also takes
+//! every slower request.
+
+use std::time::Duration;
+
+/// Buckets per histogram; the last full one covers up to about 8 seconds.
+pub const LATENCY_BUCKETS: usize = 24;
+
+/// The bucket a request that took `elapsed` falls into.
+pub fn bucket_for(elapsed: Duration) -> usize {
+    let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+    ((u64::BITS - micros.leading_zeros()) as usize).min(LATENCY_BUCKETS - 1)
+}
+
+/// The latency every request in bucket `i` stayed under.
+pub fn bucket_upper_bound(i: usize) -> Duration {
+    Duration::from_micros(1 << i.min(LATENCY_BUCKETS - 1))
+}
+
+/// An upper bound on the latency at fraction `p` (0.0 - 1.0) of the
+/// requests counted in `buckets`, or zero if there are none.
+pub fn percentile(buckets: &[u64], p: f64) -> Duration {
+    let total: u64 = buckets.iter().sum();
+    if total == 0 {
+        return Duration::ZERO;
+    }
+    let rank = ((total as f64 * p).ceil() as u64).clamp(1, total);
+    let mut seen = 0;
+    for (i, count) in buckets.iter().enumerate() {
+        seen += count;
+        if seen >= rank {
+            return bucket_upper_bound(i);
+        }
+    }
+    bucket_upper_bound(buckets.len() - 1)
+}
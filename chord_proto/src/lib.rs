@@ -2,6 +2,38 @@ pub mod chord {
     tonic::include_proto!("chord");
 }
 
+pub mod addr;
+pub mod chunk;
+pub mod distribution;
+pub mod latency;
+pub mod ring;
+
+// Size limits on writes, shared by the nodes and the monitor
+pub const MAX_KEY_BYTES: usize = 1024;
+pub const MAX_VALUE_BYTES: usize = 32 * 1024 * 1024;
+// Total length of a value's metadata names and values
+pub const MAX_METADATA_BYTES: usize = 4096;
+// Values larger than this are sent as a stream of chunks of this size
+pub const VALUE_CHUNK_SIZE: usize = 1024 * 1024;
+
+// Joins a namespace and a key into the key that is hashed and stored
+pub const NAMESPACE_SEPARATOR: char = ':';
+// Starts every namespaced stored key. Keys in the default namespace are
+// stored as they are, so they may not start with it.
+pub const NAMESPACE_MARKER: char = '\u{0}';
+
+// Wire protocol version exchanged in Hello. Nodes from before the handshake
+// don't implement it and count as version 1.
+pub const PROTOCOL_VERSION: u32 = 4;
+// Oldest peer version a node joins through or accepts
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+// First version that stores value metadata
+pub const METADATA_PROTOCOL_VERSION: u32 = 2;
+// First version that streams key transfers in chunks
+pub const STREAMING_TRANSFER_PROTOCOL_VERSION: u32 = 3;
+// First version that exports its keys with the values in chunks
+pub const CHUNKED_EXPORT_PROTOCOL_VERSION: u32 = 4;
+
 pub fn hash_addr(addr: &str) -> u64 {
     use sha1::{Digest, Sha1};
     let mut hasher = Sha1::new();
@@ -11,3 +43,62 @@ pub fn hash_addr(addr: &str) -> u64 {
     bytes.copy_from_slice(&result[0..8]);
     u64::from_be_bytes(bytes)
 }
+
+/// `hash_addr` perturbed by `salt`, for picking another id when `addr`'s
+/// collides with a node already in the ring. A salt of 0 is `hash_addr`.
+pub fn hash_addr_salted(addr: &str, salt: u32) -> u64 {
+    match salt {
+        0 => hash_addr(addr),
+        salt => hash_addr(&format!("{}#{}", addr, salt)),
+    }
+}
+
+/// The stored key for `key` in `namespace`: the marker, `namespace:key`,
+/// or just `key` in the default (empty) namespace. Only namespaced keys
+/// start with the marker and the namespace can't contain the separator,
+/// so no two (namespace, key) pairs map to the same stored key.
+pub fn namespaced_key(namespace: &str, key: &str) -> Result<String, String> {
+    if namespace.is_empty() {
+        if key.starts_with(NAMESPACE_MARKER) {
+            return Err(format!(
+                "Key {:?} starts with the reserved namespace marker",
+                key
+            ));
+        }
+        return Ok(key.to_string());
+    }
+    if namespace.contains(NAMESPACE_SEPARATOR) {
+        return Err(format!(
+            "Namespace '{}' contains '{}'",
+            namespace, NAMESPACE_SEPARATOR
+        ));
+    }
+    Ok(format!(
+        "{}{}{}{}",
+        NAMESPACE_MARKER, namespace, NAMESPACE_SEPARATOR, key
+    ))
+}
+
+/// Where a node's id comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeIdSource {
+    /// Used as is, so the node keeps its place on the ring wherever it runs.
+    Fixed(u64),
+    /// Hashed from this string: the listen address or an explicit seed.
+    Hashed(String),
+}
+
+impl NodeIdSource {
+    /// The id to try after `salt` collisions. Only hashed ids move; a fixed
+    /// id is the same on every attempt.
+    pub fn id(&self, salt: u32) -> u64 {
+        match self {
+            NodeIdSource::Fixed(id) => *id,
+            NodeIdSource::Hashed(source) => hash_addr_salted(source, salt),
+        }
+    }
+
+    pub fn is_fixed(&self) -> bool {
+        matches!(self, NodeIdSource::Fixed(_))
+    }
+}
@@ -0,0 +1,18 @@
+//! Key ownership worked out from a membership snapshot, without contacting
+//! the ring.
+
+use crate::chord::NodeInfo;
+
+/// The node responsible for `key_id` among `members`: the first one at or
+/// clockwise after it, wrapping past the top of the id space, that isn't an
+/// observer. This is the owner a lookup finds once a ring of exactly these
+/// members has stabilized. Members may be in any order. None if there are no
+/// members that store keys.
+pub fn owner_of(key_id: u64, members: &[NodeInfo]) -> Option<NodeInfo> {
+    let storing = || members.iter().filter(|member| !member.observer);
+    storing()
+        .filter(|member| member.id >= key_id)
+        .min_by_key(|member| member.id)
+        .or_else(|| storing().min_by_key(|member| member.id))
+        .cloned()
+}
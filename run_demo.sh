@@ -27,13 +27,13 @@ NODE_BIN="./target/debug/chord_node"
 
 echo "Starting monitor..."
 pushd chord_monitor > /dev/null
-../target/debug/chord_monitor &
+../target/debug/chord_monitor --node-binary ../target/debug/chord_node &
 MONITOR_PID=$!
 popd > /dev/null
 sleep 2
 
 echo "Starting node 1 (port 5000)..."
-$NODE_BIN --port 5000 --monitor 127.0.0.1:50051 &
+$NODE_BIN --port 5000 --create --monitor 127.0.0.1:50051 &
 NODE1_PID=$!
 sleep 2
 